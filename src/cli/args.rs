@@ -1,4 +1,9 @@
 use {
+    crate::core::{
+        MAX_PASSWORD_SCORE,
+        PaddingPolicy,
+        PasswordPolicy,
+    },
     argh::FromArgs,
     std::path::PathBuf,
 };
@@ -19,7 +24,76 @@ pub struct Args {
     #[argh(switch, short = 'o')]
     pub open: bool,
 
+    /// measure the key derivation on this machine and print the resulting
+    /// costs, which are used if a new closet is created
+    #[argh(switch)]
+    pub calibrate: bool,
+
+    /// target duration of a key derivation for calibration, in milliseconds
+    #[argh(option, default = "500")]
+    pub calibration_time: u64,
+
+    /// maximal memory used by a key derivation for calibration, in MiB
+    #[argh(option, default = "256")]
+    pub calibration_memory: u32,
+
+    /// padding of the drawers: 'random', 'pow2' (default for new closets), or a
+    /// bucket size in bytes. On an existing closet, applies from the next save
+    #[argh(option)]
+    pub padding: Option<PaddingPolicy>,
+
+    /// print what the closet file reveals without any passphrase (costs,
+    /// padding, sizes of the crypted drawers), then quit
+    #[argh(switch)]
+    pub info: bool,
+
+    /// read a drawer passphrase on the standard input (for a deep drawer,
+    /// the ones of its parents first, one per line), print the entries of
+    /// this drawer which are expired or expiring soon (or breached, with
+    /// --breaches), then quit, with a non zero exit code if some are expired
+    /// or breached
+    #[argh(switch)]
+    pub check: bool,
+
+    /// a local copy of the Pwned Passwords list (SHA-1 version, ordered
+    /// by hash) in which the audit and --check look for the secrets
+    #[argh(option)]
+    pub breaches: Option<PathBuf>,
+
+    /// print a generated password, then quit
+    #[argh(switch)]
+    pub generate: bool,
+
+    /// rules of the generated password, e.g. '16,no-symbols' or
+    /// '24,lower,digits' (add 'ambiguous' to allow characters like 0 and O)
+    #[argh(option)]
+    pub policy: Option<PasswordPolicy>,
+
+    /// minimal strength of the passphrases of new drawers, from 0 (any
+    /// non empty passphrase) to 4 (very strong)
+    #[argh(option, default = "2", from_str_fn(parse_min_strength))]
+    pub min_strength: u8,
+
+    /// another copy of the closet file, which diverged, to merge into
+    /// the opened one
+    #[argh(option)]
+    pub merge: Option<PathBuf>,
+
+    /// with --merge, a version of the closet from before the copies
+    /// diverged, telling which copy changed a value
+    #[argh(option)]
+    pub ancestor: Option<PathBuf>,
+
     #[argh(positional)]
     /// the closet file to open or create
     pub path: Option<PathBuf>,
 }
+
+fn parse_min_strength(s: &str) -> Result<u8, String> {
+    match s.parse::<u8>() {
+        Ok(score) if score <= MAX_PASSWORD_SCORE => Ok(score),
+        _ => Err(format!(
+            "min strength must be a score between 0 and {MAX_PASSWORD_SCORE}"
+        )),
+    }
+}
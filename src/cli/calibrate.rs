@@ -0,0 +1,51 @@
+use {
+    super::Args,
+    crate::{
+        core::*,
+        error::SafeClosetError,
+    },
+    std::time::Duration,
+};
+
+/// Benchmark the key derivation, print the chosen costs and
+/// the durations they imply, then return them
+pub fn calibrate(args: &Args) -> Result<KdfParams, SafeClosetError> {
+    let target = Duration::from_millis(args.calibration_time);
+    let max_mem_cost = args.calibration_memory.saturating_mul(1024);
+    println!(
+        "Calibrating the key derivation for {}ms and at most {}MiB...",
+        args.calibration_time, args.calibration_memory,
+    );
+    let calibration = KdfParams::calibrate(target, max_mem_cost)?;
+    let KdfParams {
+        mem_cost,
+        time_cost,
+        lanes,
+    } = calibration.params;
+    println!("memory cost: {} MiB ({mem_cost} KiB)", mem_cost / 1024);
+    println!("time cost: {time_cost} passes");
+    println!("lanes: {lanes}");
+    println!(
+        "measured derivation: {:.2}s",
+        calibration.derivation_duration.as_secs_f64(),
+    );
+    println!("A passphrase is tried at every level from the deepest open drawer up to the root,");
+    println!("so the worst case unlock duration grows with the number of open drawers.");
+    println!(
+        "A passphrase with non ASCII characters may be tried in {MAX_PASSWORD_FORMS} unicode forms:"
+    );
+    for open_drawers in 0..4 {
+        println!(
+            " - {open_drawers} open drawer{}: {:.2}s ({:.2}s with non ASCII characters)",
+            if open_drawers == 1 { "" } else { "s" },
+            calibration
+                .worst_case_unlock_duration(open_drawers, true)
+                .as_secs_f64(),
+            calibration
+                .worst_case_unlock_duration(open_drawers, false)
+                .as_secs_f64(),
+        );
+    }
+    println!("Saving costs one derivation per open drawer.");
+    Ok(calibration.params)
+}
@@ -0,0 +1,143 @@
+use {
+    crate::{
+        core::*,
+        error::SafeClosetError,
+    },
+    crokey::crossterm::{
+        event::{
+            self,
+            Event,
+            KeyCode,
+            KeyEvent,
+            KeyEventKind,
+            KeyModifiers,
+        },
+        terminal,
+        tty::IsTty,
+    },
+    std::{
+        io::{
+            self,
+            BufRead,
+            Write,
+        },
+        path::Path,
+    },
+};
+
+/// Open the drawer whose passphrase is given on the standard input,
+/// print its entries which are expired or expiring soon, and, when
+/// a list of breached passwords is given, the breached secrets.
+///
+/// A deep drawer is reached with the passphrases of its parents, each
+/// one followed by the passphrase of the drawer it contains.
+///
+/// Return whether some entries are expired or breached.
+pub fn check_drawer(
+    path: &Path,
+    breaches: Option<&Path>,
+) -> Result<bool, SafeClosetError> {
+    let passphrases = read_passphrases()?;
+    let mut open_closet = OpenCloset::open(path.to_path_buf())?;
+    for passphrase in &passphrases {
+        open_closet
+            .open_drawer(passphrase)
+            .ok_or(SafeClosetError::NoDrawerForPassphrase)?;
+    }
+    let drawer = open_closet
+        .deepest_open_drawer()
+        .ok_or(SafeClosetError::NoDrawerForPassphrase)?;
+    let entries = &drawer.content.entries;
+    let now = unix_now();
+    for entry in entries {
+        let Some(expiry) = entry.expiry else {
+            continue;
+        };
+        let Some(time) = expiry.time(entry) else {
+            continue;
+        };
+        let date = format_date(time);
+        match ExpiryStatus::of(entry, now) {
+            ExpiryStatus::Expired => println!("expired since {date}: {}", entry.name),
+            ExpiryStatus::Soon => println!("expiring on {date}: {}", entry.name),
+            ExpiryStatus::Valid => {}
+        }
+    }
+    let report = ExpiryReport::of(entries, now);
+    println!("{report}");
+    let mut breached = 0;
+    if let Some(breaches) = breaches {
+        let mut list = BreachList::open(breaches)?;
+        for finding in check_breaches(&[(drawer.depth, entries)], &mut list)? {
+            match &finding.field {
+                Some(field) => println!("{}: {} ({})", finding.issue, finding.name, field),
+                None => println!("{}: {}", finding.issue, finding.name),
+            }
+            breached += 1;
+        }
+        println!("{breached} breached secrets");
+    }
+    Ok(report.expired > 0 || breached > 0)
+}
+
+/// Read the passphrases leading to the drawer to check, without echo
+/// when typed in a terminal, or as the lines of the standard input when
+/// it's piped, as in a cron job
+fn read_passphrases() -> Result<Vec<String>, SafeClosetError> {
+    let stdin = io::stdin();
+    if !stdin.is_tty() {
+        let mut passphrases = Vec::new();
+        for line in stdin.lock().lines() {
+            let line = line?;
+            let passphrase = line.trim_end_matches('\r');
+            if !passphrase.is_empty() {
+                passphrases.push(passphrase.to_string());
+            }
+        }
+        return Ok(passphrases);
+    }
+    let mut passphrases = vec![read_passphrase("Drawer passphrase: ")?];
+    loop {
+        let passphrase = read_passphrase("Passphrase of a drawer inside (enter to check): ")?;
+        if passphrase.is_empty() {
+            return Ok(passphrases);
+        }
+        passphrases.push(passphrase);
+    }
+}
+
+fn read_passphrase(prompt: &str) -> Result<String, SafeClosetError> {
+    eprint!("{prompt}");
+    io::stderr().flush()?;
+    terminal::enable_raw_mode()?;
+    let passphrase = read_hidden_line();
+    terminal::disable_raw_mode()?;
+    eprintln!();
+    passphrase
+}
+
+fn read_hidden_line() -> Result<String, SafeClosetError> {
+    let mut line = String::new();
+    loop {
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event::read()?
+        else {
+            continue;
+        };
+        match code {
+            KeyCode::Enter => return Ok(line),
+            KeyCode::Backspace => {
+                line.pop();
+            }
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                return Err(io::Error::new(io::ErrorKind::Interrupted, "interrupted").into());
+            }
+            KeyCode::Char(c) => line.push(c),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use {
+    crate::{
+        core::*,
+        error::SafeClosetError,
+    },
+    std::{
+        collections::BTreeMap,
+        path::Path,
+    },
+};
+
+/// Print what anybody can learn from the closet file,
+/// without any passphrase
+pub fn print_info(path: &Path) -> Result<(), SafeClosetError> {
+    let closet = Closet::from_file(path)?;
+    let KdfParams {
+        mem_cost,
+        time_cost,
+        lanes,
+    } = closet.kdf;
+    println!("closet file: {}", path.display());
+    println!("key derivation: Argon2i, {mem_cost} KiB, {time_cost} passes, {lanes} lanes");
+    println!("padding: {}", closet.padding);
+    println!("top level drawers: {}", closet.drawers.len());
+    let mut sizes: BTreeMap<usize, usize> = BTreeMap::new();
+    for drawer in &closet.drawers {
+        *sizes.entry(drawer.crypted_size()).or_default() += 1;
+    }
+    println!("sizes of the crypted drawers:");
+    for (size, count) in sizes {
+        println!(
+            " - {size} bytes: {count} drawer{}",
+            if count > 1 { "s" } else { "" },
+        );
+    }
+    println!("Deeper drawers are crypted inside the top level ones and don't show here.");
+    Ok(())
+}
@@ -1,4 +1,7 @@
 mod args;
+mod calibrate;
+mod check;
+mod info;
 
 pub use args::Args;
 
@@ -19,9 +22,44 @@ pub fn run() -> Result<(), SafeClosetError> {
     }
     info!("args: {:#?}", &args);
 
+    if args.generate {
+        println!("{}", args.policy.clone().unwrap_or_default().generate()?);
+        return Ok(());
+    }
+
+    let kdf = if args.calibrate {
+        Some(calibrate::calibrate(&args)?)
+    } else {
+        None
+    };
+
     if let Some(path) = &args.path {
-        let closet = OpenCloset::open_or_create(path.clone())?;
+        if args.info {
+            info::print_info(path)?;
+            return Ok(());
+        }
+        if args.check {
+            if check::check_drawer(path, args.breaches.as_deref())? {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        let mut closet = if path.exists() {
+            OpenCloset::open(path.clone())?
+        } else {
+            OpenCloset::create_with_params(
+                path.clone(),
+                kdf.unwrap_or_default(),
+                args.padding.unwrap_or_default(),
+            )?
+        };
+        if let Some(padding) = args.padding {
+            closet.root_closet().padding = padding;
+        }
+        closet.set_min_password_score(args.min_strength);
         tui::run(closet, &args)?;
+    } else if args.calibrate {
+        println!("Those costs would be used for a closet created with --calibrate.");
     } else {
         println!(
             "Please provide as argument the path to the closet file to create or open, \
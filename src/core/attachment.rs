@@ -0,0 +1,197 @@
+use {
+    super::*,
+    serde::{
+        Deserialize,
+        Serialize,
+    },
+    sha2::{
+        Digest,
+        Sha256,
+    },
+    std::{
+        fmt,
+        fs,
+        io::Write,
+        path::Path,
+    },
+};
+
+/// Files bigger than this can't be attached, as the whole drawer is
+/// crypted and written at every save
+pub const MAX_ATTACHMENT_SIZE: u64 = 16 * 1024 * 1024;
+
+/// MIME types of the usual extensions of attached files, the
+/// others being `application/octet-stream`
+const MIME_TYPES: &[(&str, &str)] = &[
+    ("txt", "text/plain"),
+    ("pem", "application/x-pem-file"),
+    ("key", "application/x-pem-file"),
+    ("crt", "application/x-x509-ca-cert"),
+    ("cer", "application/x-x509-ca-cert"),
+    ("der", "application/x-x509-ca-cert"),
+    ("p12", "application/x-pkcs12"),
+    ("pfx", "application/x-pkcs12"),
+    ("pdf", "application/pdf"),
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("json", "application/json"),
+    ("zip", "application/zip"),
+    ("gpg", "application/pgp-encrypted"),
+    ("asc", "application/pgp-keys"),
+    ("kdbx", "application/x-keepass2"),
+];
+
+/// A file stored in an entry
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attachment {
+    /// the file name, without directory
+    pub name: String,
+    pub mime_type: String,
+    /// the content, written as a MessagePack binary
+    #[serde(with = "serde_bytes")]
+    pub bytes: Vec<u8>,
+}
+
+impl Attachment {
+    pub fn new<N: Into<String>>(
+        name: N,
+        bytes: Vec<u8>,
+    ) -> Self {
+        let name = name.into();
+        let mime_type = mime_type_of(&name).to_string();
+        Self {
+            name,
+            mime_type,
+            bytes,
+        }
+    }
+    /// Read the file at the given path
+    pub fn from_path(path: &Path) -> Result<Self, CoreError> {
+        let size = fs::metadata(path)?.len();
+        if size > MAX_ATTACHMENT_SIZE {
+            return Err(CoreError::AttachmentTooBig(size));
+        }
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Ok(Self::new(name, fs::read(path)?))
+    }
+    /// Write the attachment in a new file, only readable by
+    /// the user on unix systems
+    pub fn extract_to(
+        &self,
+        path: &Path,
+    ) -> Result<(), CoreError> {
+        if path.exists() {
+            return Err(CoreError::FileExists(path.to_path_buf()));
+        }
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(path)?;
+        file.write_all(&self.bytes)?;
+        Ok(())
+    }
+    pub fn size(&self) -> usize {
+        self.bytes.len()
+    }
+    /// Return the SHA-256 hash of the content, in hexadecimal
+    pub fn sha256(&self) -> String {
+        Sha256::digest(&self.bytes)
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+}
+
+/// Not deriving Debug, to not dump the content in logs
+impl fmt::Debug for Attachment {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.debug_struct("Attachment")
+            .field("name", &self.name)
+            .field("mime_type", &self.mime_type)
+            .field("size", &self.size())
+            .finish()
+    }
+}
+
+/// Guess the MIME type of a file from its extension
+pub fn mime_type_of(name: &str) -> &'static str {
+    let extension = match name.rsplit_once('.') {
+        Some((_, extension)) => extension.to_ascii_lowercase(),
+        None => return "application/octet-stream",
+    };
+    MIME_TYPES
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map_or("application/octet-stream", |(_, mime_type)| mime_type)
+}
+
+/// Format a size in bytes for humans, like `3.2 kB`
+pub fn format_size(size: usize) -> String {
+    const UNITS: &[&str] = &["kB", "MB", "GB"];
+    if size < 1000 {
+        return format!("{size} B");
+    }
+    let mut value = size as f64 / 1000.0;
+    let mut unit = 0;
+    while value >= 1000.0 && unit + 1 < UNITS.len() {
+        value /= 1000.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+#[test]
+fn test_attachment() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let source = temp_dir.path().join("id_rsa.pem");
+    fs::write(&source, b"not a real key").unwrap();
+    let attachment = Attachment::from_path(&source).unwrap();
+    assert_eq!(attachment.name, "id_rsa.pem");
+    assert_eq!(attachment.mime_type, "application/x-pem-file");
+    assert_eq!(attachment.size(), 14);
+    assert_eq!(
+        Attachment::new("a", b"abc".to_vec()).sha256(),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+    );
+    let target = temp_dir.path().join("extracted");
+    attachment.extract_to(&target).unwrap();
+    assert_eq!(fs::read(&target).unwrap(), b"not a real key");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&target).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+    assert!(matches!(
+        attachment.extract_to(&target),
+        Err(CoreError::FileExists(_)),
+    ));
+    assert_eq!(mime_type_of("codes.PDF"), "application/pdf");
+    assert_eq!(mime_type_of("README"), "application/octet-stream");
+    assert_eq!(format_size(999), "999 B");
+    assert_eq!(format_size(3_240), "3.2 kB");
+    assert_eq!(format_size(16_777_216), "16.8 MB");
+}
+
+/// check the content is encoded as a binary, not as an array of
+/// integers taking up to two bytes per byte
+#[test]
+fn test_attachment_encoding() {
+    let bytes: Vec<u8> = (0..1000).map(|i| (i % 256) as u8).collect();
+    let attachment = Attachment::new("a.bin", bytes);
+    let encoded = rmp_serde::encode::to_vec_named(&attachment).unwrap();
+    assert!(encoded.len() < attachment.size() + 100);
+    let decoded: Attachment = rmp_serde::decode::from_slice(&encoded).unwrap();
+    assert!(decoded == attachment);
+}
@@ -0,0 +1,255 @@
+use {
+    super::*,
+    std::fmt,
+};
+
+/// Secrets shorter than this are reported
+pub const MIN_SECRET_LENGTH: usize = 12;
+
+/// Secrets whose estimated strength is below this score are reported
+pub const MIN_SECRET_SCORE: u8 = 2;
+
+/// Values differing by at most this number of character edits
+/// are reported as near duplicates
+const MAX_NEAR_DUPLICATE_DISTANCE: usize = 2;
+
+/// Values shorter than this aren't compared for near duplicates,
+/// as short values are similar by chance
+const MIN_NEAR_DUPLICATE_LENGTH: usize = 6;
+
+/// Where an entry is, among the open drawers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntryLocation {
+    /// the depth of the drawer
+    pub depth: usize,
+    /// the index of the entry in the drawer
+    pub idx: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditIssue {
+    /// neither value, nor fields, nor attached files
+    Empty,
+    /// the same secret is used in other entries
+    Reused {
+        count: usize,
+    },
+    /// the secret is almost the one of another entry
+    NearDuplicate {
+        other: String,
+    },
+    Short {
+        length: usize,
+    },
+    Weak {
+        label: &'static str,
+    },
+    /// the secret is in a list of breached passwords
+    Breached {
+        count: u64,
+    },
+}
+
+/// A problem found on an entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditFinding {
+    pub location: EntryLocation,
+    pub name: String,
+    /// the name of the audited field, None for the value
+    pub field: Option<String>,
+    pub issue: AuditIssue,
+}
+
+/// A secret of an entry: its value or a secret field
+pub struct AuditedSecret<'e> {
+    pub location: EntryLocation,
+    pub name: &'e str,
+    pub field: Option<&'e str>,
+    pub secret: &'e str,
+}
+
+impl<'e> AuditedSecret<'e> {
+    pub fn finding(
+        &self,
+        issue: AuditIssue,
+    ) -> AuditFinding {
+        AuditFinding {
+            location: self.location,
+            name: self.name.to_string(),
+            field: self.field.map(|f| f.to_string()),
+            issue,
+        }
+    }
+}
+
+impl fmt::Display for AuditIssue {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty entry"),
+            Self::Reused { count } => write!(f, "same secret in {count} other entries"),
+            Self::NearDuplicate { other } => write!(f, "secret close to the one of {other}"),
+            Self::Short { length } => write!(f, "short secret ({length} chars)"),
+            Self::Weak { label } => write!(f, "{label} secret"),
+            Self::Breached { count } => write!(f, "secret found {count} times in breaches"),
+        }
+    }
+}
+
+/// Tell whether a value is likely a secret worth auditing: notes
+/// spanning several lines, URLs and one-time code seeds aren't
+fn is_audited_value(entry: &Entry) -> bool {
+    let value = &entry.value;
+    !value.is_empty()
+        && !value.contains('\n')
+        && !value.starts_with("http://")
+        && !value.starts_with("https://")
+        && entry.totp().is_none()
+}
+
+/// Return the number of single char edits (insertions, deletions
+/// or substitutions) needed to change `a` into `b`
+fn edit_distance(
+    a: &[char],
+    b: &[char],
+) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Return the secrets of the entries of the given drawers, given
+/// with their depths
+pub fn collect_secrets<'e>(drawers: &[(usize, &'e [Entry])]) -> Vec<AuditedSecret<'e>> {
+    let mut secrets = Vec::new();
+    for (depth, entries) in drawers {
+        for (idx, entry) in entries.iter().enumerate() {
+            let location = EntryLocation { depth: *depth, idx };
+            if is_audited_value(entry) {
+                secrets.push(AuditedSecret {
+                    location,
+                    name: &entry.name,
+                    field: None,
+                    secret: &entry.value,
+                });
+            }
+            for field in &entry.fields {
+                if field.kind == FieldKind::Secret && !field.value.is_empty() {
+                    secrets.push(AuditedSecret {
+                        location,
+                        name: &entry.name,
+                        field: Some(&field.name),
+                        secret: &field.value,
+                    });
+                }
+            }
+        }
+    }
+    secrets
+}
+
+/// Audit the entries of the given drawers, given with their depths
+pub fn audit_drawers(drawers: &[(usize, &[Entry])]) -> Vec<AuditFinding> {
+    let mut findings = Vec::new();
+    for (depth, entries) in drawers {
+        for (idx, entry) in entries.iter().enumerate() {
+            let is_empty = entry.value.is_empty()
+                && entry.fields.is_empty()
+                && entry.attachments.is_empty()
+                && !entry.is_empty();
+            if is_empty {
+                findings.push(AuditFinding {
+                    location: EntryLocation { depth: *depth, idx },
+                    name: entry.name.clone(),
+                    field: None,
+                    issue: AuditIssue::Empty,
+                });
+            }
+        }
+    }
+    let secrets = collect_secrets(drawers);
+    let chars: Vec<Vec<char>> = secrets.iter().map(|s| s.secret.chars().collect()).collect();
+    for (i, secret) in secrets.iter().enumerate() {
+        let count = secrets
+            .iter()
+            .enumerate()
+            .filter(|(j, other)| *j != i && other.secret == secret.secret)
+            .count();
+        if count > 0 {
+            findings.push(secret.finding(AuditIssue::Reused { count }));
+        } else if chars[i].len() >= MIN_NEAR_DUPLICATE_LENGTH {
+            let close = secrets.iter().enumerate().find(|(j, other)| {
+                *j != i
+                    && other.secret != secret.secret
+                    && chars[*j].len() >= MIN_NEAR_DUPLICATE_LENGTH
+                    && chars[i].len().abs_diff(chars[*j].len()) <= MAX_NEAR_DUPLICATE_DISTANCE
+                    && edit_distance(&chars[i], &chars[*j]) <= MAX_NEAR_DUPLICATE_DISTANCE
+            });
+            if let Some((_, other)) = close {
+                findings.push(secret.finding(AuditIssue::NearDuplicate {
+                    other: other.name.to_string(),
+                }));
+            }
+        }
+        let length = chars[i].len();
+        let strength = PasswordStrength::estimate(secret.secret);
+        if strength.score() < MIN_SECRET_SCORE {
+            findings.push(secret.finding(AuditIssue::Weak {
+                label: strength.label(),
+            }));
+        } else if length < MIN_SECRET_LENGTH {
+            findings.push(secret.finding(AuditIssue::Short { length }));
+        }
+    }
+    findings.sort_by_key(|finding| (finding.location.depth, finding.location.idx));
+    findings
+}
+
+#[test]
+fn test_audit() {
+    let mut entries = vec![
+        Entry::new("mail", "correct horse battery staple"),
+        Entry::new("bank", "correct horse battery staple"),
+        Entry::new("shop", "Tr0ub4dor&3-xyzzy-42"),
+        Entry::new("shop2", "Tr0ub4dor&3-xyzzy-43"),
+        Entry::new("forum", "password"),
+        Entry::new("wifi", "k8#Vq2!x"),
+        Entry::new("todo", ""),
+        Entry::new("notes", "some\nlines"),
+    ];
+    entries[7]
+        .fields
+        .push(EntryField::new("pin", FieldKind::Secret, "password"));
+    let findings = audit_drawers(&[(0, &entries)]);
+    let issues_of = |idx: usize| -> Vec<&AuditIssue> {
+        findings
+            .iter()
+            .filter(|f| f.location.idx == idx)
+            .map(|f| &f.issue)
+            .collect()
+    };
+    assert_eq!(issues_of(0), vec![&AuditIssue::Reused { count: 1 }]);
+    assert_eq!(issues_of(1), vec![&AuditIssue::Reused { count: 1 }]);
+    assert_eq!(
+        issues_of(2),
+        vec![&AuditIssue::NearDuplicate {
+            other: "shop2".to_string()
+        }]
+    );
+    assert!(issues_of(4).contains(&&AuditIssue::Reused { count: 1 }));
+    assert!(matches!(issues_of(4)[1], AuditIssue::Weak { .. }));
+    assert_eq!(issues_of(5), vec![&AuditIssue::Short { length: 8 }]);
+    assert_eq!(issues_of(6), vec![&AuditIssue::Empty]);
+    let pin = findings.iter().find(|f| f.location.idx == 7).unwrap();
+    assert_eq!(pin.field.as_deref(), Some("pin"));
+    assert_eq!(edit_distance(&['a', 'b', 'c'], &['a', 'c', 'd']), 2);
+}
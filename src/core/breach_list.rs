@@ -0,0 +1,181 @@
+use {
+    super::*,
+    sha1::{
+        Digest,
+        Sha1,
+    },
+    std::{
+        cmp::Ordering,
+        fs::File,
+        io::{
+            Read,
+            Seek,
+            SeekFrom,
+        },
+        path::Path,
+    },
+};
+
+/// Length of a SHA-1 hash in hexadecimal
+const HASH_LENGTH: usize = 40;
+
+/// Lines are like `7C4A8D09CA3762AF61E59520943DC26494F8941B:24230577`,
+/// this is more than the longest one
+const MAX_LINE_LENGTH: usize = 64;
+
+/// A local copy of the Pwned Passwords list in its SHA-1 version
+/// ordered by hash, where secrets are searched by binary search,
+/// without reading the whole file nor writing anything
+pub struct BreachList {
+    file: File,
+    len: u64,
+}
+
+/// A line of the list
+struct BreachLine {
+    /// the position of the line in the file
+    start: u64,
+    /// the position of the next line
+    next: u64,
+    hash: [u8; HASH_LENGTH],
+    count: u64,
+}
+
+impl BreachList {
+    pub fn open(path: &Path) -> Result<Self, CoreError> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+        Ok(Self { file, len })
+    }
+    /// Return the first line starting at or after the given position
+    fn line_at_or_after(
+        &mut self,
+        pos: u64,
+    ) -> Result<Option<BreachLine>, CoreError> {
+        // we read from the char before to know whether pos is a line start
+        let read_start = pos.saturating_sub(1);
+        self.file.seek(SeekFrom::Start(read_start))?;
+        let mut buffer = [0; 2 * MAX_LINE_LENGTH];
+        let mut filled = 0;
+        loop {
+            let n = self.file.read(&mut buffer[filled..])?;
+            if n == 0 {
+                break;
+            }
+            filled += n;
+            if filled == buffer.len() {
+                break;
+            }
+        }
+        let buffer = &buffer[..filled];
+        let offset = if pos == 0 {
+            0
+        } else {
+            match buffer.iter().position(|&b| b == b'\n') {
+                Some(idx) => idx + 1,
+                None => return Ok(None),
+            }
+        };
+        let line = &buffer[offset..];
+        let end = line.iter().position(|&b| b == b'\n');
+        let Some(hash) = line.get(..HASH_LENGTH) else {
+            return Ok(None);
+        };
+        let mut hash: [u8; HASH_LENGTH] =
+            hash.try_into().map_err(|_| CoreError::UnconsistentData)?;
+        hash.make_ascii_uppercase();
+        let count = line[HASH_LENGTH..end.unwrap_or(line.len())]
+            .iter()
+            .filter(|b| b.is_ascii_digit())
+            .fold(0, |count: u64, b| count * 10 + u64::from(b - b'0'));
+        let start = read_start + offset as u64;
+        let next = match end {
+            Some(end) => start + end as u64 + 1,
+            None => self.len,
+        };
+        Ok(Some(BreachLine {
+            start,
+            next,
+            hash,
+            count,
+        }))
+    }
+    /// Return the number of times the secret was found in breaches, if it was
+    pub fn count(
+        &mut self,
+        secret: &str,
+    ) -> Result<Option<u64>, CoreError> {
+        let target: String = Sha1::digest(secret.as_bytes())
+            .iter()
+            .map(|b| format!("{b:02X}"))
+            .collect();
+        let target = target.as_bytes();
+        // the searched line, if any, starts in [lo, hi), lo being a line start
+        let mut lo = 0;
+        let mut hi = self.len;
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let line = match self.line_at_or_after(mid)? {
+                Some(line) if line.start < hi => line,
+                _ => {
+                    hi = mid;
+                    continue;
+                }
+            };
+            match line.hash.as_slice().cmp(target) {
+                Ordering::Less => lo = line.next,
+                Ordering::Equal => return Ok(Some(line.count)),
+                Ordering::Greater => hi = line.start,
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Look for the secrets of the entries of the given drawers
+/// in the list of breached passwords
+pub fn check_breaches(
+    drawers: &[(usize, &[Entry])],
+    list: &mut BreachList,
+) -> Result<Vec<AuditFinding>, CoreError> {
+    let mut findings = Vec::new();
+    for secret in collect_secrets(drawers) {
+        if let Some(count) = list.count(secret.secret)? {
+            findings.push(secret.finding(AuditIssue::Breached { count }));
+        }
+    }
+    Ok(findings)
+}
+
+#[test]
+fn test_breach_list() {
+    let breached: Vec<String> = (0..500).map(|i| format!("secret-{i}")).collect();
+    let mut lines: Vec<String> = breached
+        .iter()
+        .enumerate()
+        .map(|(i, secret)| {
+            let hash: String = Sha1::digest(secret.as_bytes())
+                .iter()
+                .map(|b| format!("{b:02X}"))
+                .collect();
+            format!("{}:{}", hash, i + 1)
+        })
+        .collect();
+    lines.sort();
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("pwned.txt");
+    std::fs::write(&path, lines.join("\r\n")).unwrap();
+    let mut list = BreachList::open(&path).unwrap();
+    for (i, secret) in breached.iter().enumerate() {
+        assert_eq!(list.count(secret).unwrap(), Some(i as u64 + 1));
+    }
+    assert_eq!(list.count("secret-500").unwrap(), None);
+    assert_eq!(list.count("").unwrap(), None);
+    let entries = vec![
+        Entry::new("forum", "secret-41"),
+        Entry::new("bank", "Tr0ub4dor&3-xyzzy-42"),
+    ];
+    let findings = check_breaches(&[(0, &entries)], &mut list).unwrap();
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].issue, AuditIssue::Breached { count: 42 });
+}
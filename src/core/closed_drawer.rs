@@ -1,6 +1,7 @@
 use {
     super::*,
     aes_gcm_siv::{
+        Aes256GcmSiv,
         Nonce,
         aead::Aead,
     },
@@ -10,8 +11,11 @@ use {
     },
 };
 
+/// The size of the authentication tag added by AES-GCM-SIV
+const AEAD_TAG_SIZE: usize = 16;
+
 /// a closed, crypted, drawer
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ClosedDrawer {
     id: DrawerId,
 
@@ -36,18 +40,30 @@ impl ClosedDrawer {
         Self { id, nonce, content }
     }
 
-    /// Try to decrypt the content with the provided password
-    /// and the closet's salt, then return the open drawer with
-    /// clear data and the password to allow reencrypting.
+    /// Return the size of the crypted content, which, with a bucket
+    /// based padding policy, is the bucket size plus the size of the
+    /// authentication tag
+    pub fn crypted_size(&self) -> usize {
+        self.content.len()
+    }
+
+    /// Return the size of the serialized content before encryption
+    /// (which adds an authentication tag)
+    pub fn clear_size(&self) -> usize {
+        self.content.len().saturating_sub(AEAD_TAG_SIZE)
+    }
+
+    /// Try to decrypt the content with the cipher derived from the
+    /// provided password and the closet's salt, then return the open
+    /// drawer with clear data and the password to allow reencrypting.
     ///
     /// This function can also be used to check drawer existence.
     pub fn open(
         &self,
         depth: usize,
         password: String,
-        closet: &Closet,
+        cipher: &Aes256GcmSiv,
     ) -> Result<OpenDrawer, CoreError> {
-        let cipher = closet.cipher(&password)?;
         let nonce = Nonce::from_slice(&self.nonce);
         let clear_content = cipher
             .decrypt(nonce, self.content.as_ref())
@@ -1,10 +1,6 @@
 use {
     super::*,
-    aes_gcm_siv::{
-        Aes256GcmSiv,
-        Key,
-        KeyInit,
-    },
+    aes_gcm_siv::Aes256GcmSiv,
     rand::{
         Rng,
         rng,
@@ -18,10 +14,11 @@ use {
         fs,
         path::Path,
     },
+    unicode_normalization::UnicodeNormalization,
 };
 
 /// The closet containing all the crypted drawers
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Closet {
     /// Clear comments, which can be read with a standard binary/hex editor
     #[serde(default = "default_clear_comments")]
@@ -30,8 +27,24 @@ pub struct Closet {
     /// The salt used to generate the cipher keys from the passwords
     pub salt: String,
 
+    /// The costs of the derivation of the cipher keys
+    #[serde(default)]
+    pub kdf: KdfParams,
+
+    /// How the drawers are padded before encryption
+    #[serde(default = "PaddingPolicy::legacy")]
+    pub padding: PaddingPolicy,
+
     /// The crypted drawers
     pub drawers: Vec<ClosedDrawer>,
+
+    /// The decoys created with the closet, which no drawer knows yet,
+    /// to be adopted by the first drawer created in the closet.
+    ///
+    /// Only kept in deep closets, which are crypted with their drawer:
+    /// it's never written in the clear part of a closet file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    unclaimed_decoys: Vec<DrawerId>,
 }
 
 /// Return the default comments which are written in the clear
@@ -43,6 +56,14 @@ fn default_clear_comments() -> String {
     "Closet file written with SafeCloset\nhttps://dystroy.org/safecloset".to_string()
 }
 
+/// Probability, for a decoy known by a drawer being closed,
+/// not to be regenerated
+const DECOY_DROP_PROBABILITY: f64 = 0.3;
+
+/// Bounds of the number of decoys a drawer keeps along itself
+const MIN_SIBLING_DECOYS: usize = 2;
+const MAX_SIBLING_DECOYS: usize = 6;
+
 /// compute the number of decoy drawers we must create for
 /// the given depth
 fn random_decoy_drawers_count(depth: usize) -> usize {
@@ -59,22 +80,58 @@ fn random_decoy_drawers_count(depth: usize) -> usize {
 }
 
 impl Closet {
-    pub fn new(depth: usize) -> Result<Self, CoreError> {
+    pub fn new(
+        depth: usize,
+        kdf: KdfParams,
+        padding: PaddingPolicy,
+    ) -> Result<Self, CoreError> {
         let comments = default_clear_comments();
         let salt = random_password();
         let drawers = Vec::new();
         let mut closet = Self {
             comments,
             salt,
+            kdf,
+            padding,
             drawers,
+            unclaimed_decoys: Vec::new(),
         };
         // creating decoy drawers
         for _ in 0..random_decoy_drawers_count(depth) {
-            closet.create_drawer_unchecked(depth, random_password())?;
+            closet.create_decoy_drawer(depth, DrawerId::new(), padding.random_decoy_size())?;
         }
+        closet.unclaimed_decoys = closet.drawer_ids();
         Ok(closet)
     }
 
+    /// Create a top level closet whose only real drawer holds a copy
+    /// of the content of the given drawer, under a new passphrase.
+    ///
+    /// The closet has a fresh salt and its own decoys, and the copy
+    /// gets a new id, its entries losing their past values. The closet of the copy is rebuilt from the plan,
+    /// with fresh salts and decoys too: it holds only the deep drawers
+    /// whose passphrases were given, re-encrypted, and nothing else of
+    /// the original closet of the drawer.
+    pub fn with_drawer_copy(
+        drawer: &OpenDrawer,
+        password: String,
+        deep_drawers: RekeyPlan,
+        kdf: KdfParams,
+        padding: PaddingPolicy,
+    ) -> Result<(Self, DrawerId), CoreError> {
+        let mut closet = Self::new(0, kdf, padding)?;
+        let mut content = drawer.content.clone();
+        content.closet = deep_drawers.into_closet()?;
+        content.id = DrawerId::new();
+        content.truncate_histories(0);
+        content.sibling_decoys = closet.claim_decoys();
+        let id = content.id.clone();
+        let mut copy = OpenDrawer::new(0, password, content);
+        closet.add_drawer(&mut copy)?;
+        closet.shuffle_drawers();
+        Ok((closet, id))
+    }
+
     /// Save the closet to a file
     pub fn save(
         &self,
@@ -99,14 +156,23 @@ impl Closet {
             return Err(CoreError::FileExists(path.to_path_buf()));
         }
         let mut file = fs::File::create(path)?;
-        rmp_serde::encode::write_named(&mut file, &self)?;
+        if self.unclaimed_decoys.is_empty() {
+            rmp_serde::encode::write_named(&mut file, &self)?;
+        } else {
+            // the root closet is in the clear: the decoys mustn't be listed
+            let mut closet = self.clone();
+            closet.unclaimed_decoys.clear();
+            rmp_serde::encode::write_named(&mut file, &closet)?;
+        }
         Ok(())
     }
 
     /// read a closet from a file
     pub fn from_file(path: &Path) -> Result<Self, CoreError> {
         let file = fs::File::open(path)?;
-        let closet = rmp_serde::decode::from_read(file)?;
+        let closet: Self = rmp_serde::decode::from_read(file)?;
+        // the costs are in the clear part, which anybody can change
+        closet.kdf.check()?;
         Ok(closet)
     }
 
@@ -120,26 +186,86 @@ impl Closet {
         depth: usize,
         password: String,
     ) -> Result<OpenDrawer, CoreError> {
-        let drawer_content = DrawerContent::new(depth)?;
+        let mut drawer_content = DrawerContent::new(depth, self.kdf, self.padding)?;
+        drawer_content.sibling_decoys = self.claim_decoys();
         let mut open_drawer = OpenDrawer::new(depth, password, drawer_content);
         let closed_drawer = open_drawer.close(self)?;
         self.drawers.push(closed_drawer);
         Ok(open_drawer)
     }
 
+    /// Create a drawer nobody can open, with the given id, padded
+    /// to at least `min_size` bytes, and add it to the closet.
+    ///
+    /// As the key is random and not derived from a password, this
+    /// doesn't pay the cost of the key derivation, while the result
+    /// can't be told apart from a real drawer.
+    fn create_decoy_drawer(
+        &mut self,
+        depth: usize,
+        id: DrawerId,
+        min_size: usize,
+    ) -> Result<(), CoreError> {
+        let mut drawer_content = DrawerContent::new(depth, self.kdf, self.padding)?;
+        drawer_content.id = id;
+        if min_size > 0 {
+            // The decoy is padded to the size of the real drawer it goes
+            // along, which its own decoys could exceed. As nobody can
+            // look inside, it doesn't need them.
+            drawer_content.closet.drawers.clear();
+        }
+        let mut open_drawer = OpenDrawer::new(depth, random_password(), drawer_content);
+        let cipher = random_cipher();
+        let closed_drawer = open_drawer.close_with_cipher(&cipher, self.padding, min_size)?;
+        self.drawers.push(closed_drawer);
+        Ok(())
+    }
+
+    /// Remove the decoys known by a drawer being closed, and return
+    /// the number of new decoys which should replace them (a little
+    /// more or a little less).
+    ///
+    /// Drawers which aren't listed as decoys are never touched, as they
+    /// may be real drawers opened with other passphrases.
+    fn remove_sibling_decoys(
+        &mut self,
+        decoys: &[DrawerId],
+    ) -> usize {
+        let mut kept = 0;
+        for id in decoys {
+            if let Some(idx) = self.drawers.iter().position(|d| d.get_id() == id) {
+                self.drawers.remove(idx);
+                if !rng().random_bool(DECOY_DROP_PROBABILITY) {
+                    kept += 1;
+                }
+            }
+        }
+        (kept + rng().random_range(0..3)).clamp(MIN_SIBLING_DECOYS, MAX_SIBLING_DECOYS)
+    }
+
+    /// Take the decoys created with the closet, which weren't
+    /// adopted by a drawer yet
+    pub(crate) fn claim_decoys(&mut self) -> Vec<DrawerId> {
+        std::mem::take(&mut self.unclaimed_decoys)
+    }
+
+    /// Return the ids of all the drawers of the closet
+    pub(crate) fn drawer_ids(&self) -> Vec<DrawerId> {
+        self.drawers.iter().map(|d| d.get_id().clone()).collect()
+    }
+
     /// Create a drawer, add it to the closet.
     ///
-    /// Return an error if the password is already used by
-    /// another drawer (which probably means the user wanted
-    /// to open a drawer and not create one).
+    /// Return an error if the password is weaker than the given
+    /// score or is already used by another drawer (which probably
+    /// means the user wanted to open a drawer and not create one).
     pub fn create_drawer(
         &mut self,
         depth: usize,
         password: String,
+        min_score: u8,
     ) -> Result<OpenDrawer, CoreError> {
-        if password.len() < MIN_PASSWORD_LENGTH {
-            return Err(CoreError::PasswordTooShort);
-        }
+        PasswordStrength::check(&password, min_score)?;
         if self.is_password_taken(depth, &password) {
             return Err(CoreError::PasswordAlreadyUsed);
         }
@@ -149,18 +275,39 @@ impl Closet {
     /// Open the drawer responding to this password and return it.
     ///
     /// Return None when no drawer can be opened with this password.
+    ///
+    /// The key is derived only once, whatever the number of drawers,
+    /// except when the password has characters with several unicode
+    /// forms: drawers written before normalization may then need the
+    /// raw bytes, or the decomposed (NFD) form when they were created on
+    /// a platform producing it. Such drawer is crypted with the
+    /// normalized password on its next save.
     pub fn open_drawer(
         &self,
         depth: usize,
         password: &str,
     ) -> Option<OpenDrawer> {
-        for closed_drawer in &self.drawers {
-            let open_drawer = time!(
-                "closed_drawer.open",
-                closed_drawer.open(depth, password.to_string(), self,)
-            );
-            if let Ok(open_drawer) = open_drawer {
-                return Some(open_drawer);
+        let normalized = normalize_password(password);
+        let decomposed: String = password.nfd().collect();
+        let mut keys = vec![normalized.as_str()];
+        for key in [password, decomposed.as_str()] {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        for key in keys {
+            let cipher = match time!(self.kdf.cipher(key.as_bytes(), self.salt.as_bytes())) {
+                Ok(cipher) => cipher,
+                Err(e) => {
+                    warn!("cipher derivation failed: {e}");
+                    return None;
+                }
+            };
+            for closed_drawer in &self.drawers {
+                let open_drawer = closed_drawer.open(depth, password.to_string(), &cipher);
+                if let Ok(open_drawer) = open_drawer {
+                    return Some(open_drawer);
+                }
             }
         }
         None
@@ -174,26 +321,127 @@ impl Closet {
         self.open_drawer(depth, password).is_some()
     }
 
-    /// Close the passed drawer, put it back among closed ones
-    pub fn close_drawer(
+    /// Encrypt the drawer with this closet's salt and add it among
+    /// the closed drawers, as a new one
+    pub(crate) fn add_drawer(
         &mut self,
-        mut open_drawer: OpenDrawer,
-    ) -> Result<bool, CoreError> {
+        open_drawer: &mut OpenDrawer,
+    ) -> Result<(), CoreError> {
         let closed_drawer = open_drawer.close(self)?;
-        Ok(self.push_drawer_back(closed_drawer))
+        self.drawers.push(closed_drawer);
+        Ok(())
     }
 
-    fn push_drawer_back(
+    /// Add a drawer coming from another closet, with the drawers it
+    /// contains, crypted with this closet's salt under its passphrase.
+    ///
+    /// The drawer gets a new id. Fail, with no change, if its passphrase
+    /// already opens a drawer of this closet.
+    pub fn import_drawer(
         &mut self,
-        drawer: ClosedDrawer,
+        depth: usize,
+        mut open_drawer: OpenDrawer,
+    ) -> Result<(), CoreError> {
+        if self.is_password_taken(depth, &open_drawer.password) {
+            return Err(CoreError::PasswordAlreadyUsed);
+        }
+        open_drawer.depth = depth;
+        open_drawer.content.id = DrawerId::new();
+        open_drawer.content.sibling_decoys.clear();
+        self.add_drawer(&mut open_drawer)?;
+        self.shuffle_drawers();
+        Ok(())
+    }
+
+    /// Tell whether the drawers of the other closet would open here
+    /// with the same passphrases, both closets deriving the same keys
+    pub fn shares_keys_with(
+        &self,
+        other: &Closet,
     ) -> bool {
-        for idx in 0..self.drawers.len() {
-            if self.drawers[idx].has_same_id(&drawer) {
-                self.drawers[idx] = drawer;
-                return true;
+        self.salt == other.salt && self.kdf == other.kdf
+    }
+
+    /// Add copies of closed drawers, which must come from a closet
+    /// sharing the keys of this one, and return the number of added ones.
+    ///
+    /// Drawers whose id is already in this closet are skipped.
+    pub fn import_closed_drawers(
+        &mut self,
+        closed_drawers: Vec<ClosedDrawer>,
+    ) -> usize {
+        let mut count = 0;
+        for closed_drawer in closed_drawers {
+            if !self
+                .drawers
+                .iter()
+                .any(|d| d.get_id() == closed_drawer.get_id())
+            {
+                self.drawers.push(closed_drawer);
+                count += 1;
             }
         }
-        false
+        self.shuffle_drawers();
+        count
+    }
+
+    /// Close the passed drawer, put it back among closed ones.
+    ///
+    /// The drawer gets a new id and the decoys it knows are replaced
+    /// with new ones, of the same size. This way, someone comparing
+    /// two versions of the file sees a set of removed drawers and a
+    /// set of new ones, and can't tell which one is the real drawer.
+    ///
+    /// Return false, with no change, if the drawer isn't in this closet.
+    pub fn close_drawer(
+        &mut self,
+        mut open_drawer: OpenDrawer,
+    ) -> Result<bool, CoreError> {
+        let old_id = open_drawer.content.id.clone();
+        if !self.drawers.iter().any(|d| d.get_id() == &old_id) {
+            return Ok(false);
+        }
+        let decoys_count = self.remove_sibling_decoys(&open_drawer.content.sibling_decoys);
+        let decoys: Vec<DrawerId> = (0..decoys_count).map(|_| DrawerId::new()).collect();
+        open_drawer.content.sibling_decoys = decoys.clone();
+        open_drawer.content.id = DrawerId::new();
+        let closed_drawer = open_drawer.close(self)?;
+        for id in decoys {
+            self.create_decoy_drawer(open_drawer.depth, id, closed_drawer.clear_size())?;
+        }
+        let Some(idx) = self.drawers.iter().position(|d| d.get_id() == &old_id) else {
+            return Ok(false);
+        };
+        self.drawers[idx] = closed_drawer;
+        self.shuffle_drawers();
+        Ok(true)
+    }
+
+    /// Remove the passed drawer, with its entries and the deep drawers
+    /// it contains, and put in its place a new decoy of the same size,
+    /// so that the number of drawers doesn't tell a drawer was deleted.
+    ///
+    /// The new decoy and the decoys the deleted drawer renewed on each
+    /// save are left to be adopted by the next drawer created in this
+    /// closet (for the top level closet, only until the application quits).
+    ///
+    /// Return false, with no change, if the drawer isn't in this closet.
+    pub fn delete_drawer(
+        &mut self,
+        open_drawer: &OpenDrawer,
+    ) -> Result<bool, CoreError> {
+        let id = &open_drawer.content.id;
+        let Some(idx) = self.drawers.iter().position(|d| d.get_id() == id) else {
+            return Ok(false);
+        };
+        let deleted = self.drawers.remove(idx);
+        let decoy_id = DrawerId::new();
+        self.create_decoy_drawer(open_drawer.depth, decoy_id.clone(), deleted.clear_size())?;
+        self.unclaimed_decoys
+            .extend(open_drawer.content.sibling_decoys.iter().cloned());
+        self.unclaimed_decoys.push(decoy_id);
+        self.shuffle_drawers();
+        Ok(true)
     }
 
     /// Change the order of drawers
@@ -219,23 +467,424 @@ impl Closet {
         })
     }
 
+    /// Derive the cipher from the NFC normalized password,
+    /// so that it doesn't depend on the platform it's typed on
     pub fn cipher(
         &self,
         password: &str,
     ) -> Result<Aes256GcmSiv, CoreError> {
-        let config = argon2::Config {
-            ad: &[],
-            hash_length: 32,
-            lanes: 1,
-            mem_cost: 4096,
-            secret: &[],
-            thread_mode: argon2::ThreadMode::default(),
-            time_cost: 3,
-            variant: argon2::Variant::Argon2i,
-            version: argon2::Version::Version13,
-        };
-        let hash = argon2::hash_raw(password.as_bytes(), self.salt.as_bytes(), &config)?;
-        let key = Key::<Aes256GcmSiv>::from_slice(&hash);
-        Ok(Aes256GcmSiv::new(key))
+        let password = normalize_password(password);
+        self.kdf.cipher(password.as_bytes(), self.salt.as_bytes())
+    }
+}
+
+/// The maximal number of forms of a password tried to open a drawer:
+/// NFC normalized, as typed, and NFD decomposed
+pub const MAX_PASSWORD_FORMS: u32 = 3;
+
+/// Return the NFC normalized form of the password.
+///
+/// Some platforms produce decomposed characters (e.g. 'e' followed
+/// by a combining acute accent instead of 'é'), some don't.
+pub fn normalize_password(password: &str) -> String {
+    password.nfc().collect()
+}
+
+/// check that, when comparing successive versions of a closet file,
+/// the drawer really used can't be told apart from its decoys, neither
+/// by their ids nor by their sizes, and that the drawers which weren't
+/// opened are left untouched
+#[test]
+fn test_decoy_churn() {
+    for padding in [PaddingPolicy::Random, PaddingPolicy::PowerOfTwo] {
+        check_decoy_churn(padding);
     }
 }
+#[cfg(test)]
+fn check_decoy_churn(padding: PaddingPolicy) {
+    let used_pwd = "the drawer used every day";
+    let unused_pwd = "a drawer never opened";
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("test-churn.closet");
+    let mut open_closet =
+        OpenCloset::create_with_params(path.to_path_buf(), KdfParams::for_tests(), padding)
+            .unwrap();
+    let initial_decoys = open_closet.root_closet().drawer_ids();
+    open_closet.create_drawer(used_pwd).unwrap();
+    open_closet.close_deepest_drawer().unwrap();
+    open_closet.create_drawer(unused_pwd).unwrap();
+    open_closet.close_and_save().unwrap();
+    let mut open_closet = OpenCloset::open(path.to_path_buf()).unwrap();
+    let unused_id = open_closet
+        .open_drawer(unused_pwd)
+        .unwrap()
+        .content
+        .id
+        .clone();
+    let blob = |closet: &Closet, id: &DrawerId| {
+        closet
+            .drawers
+            .iter()
+            .find(|d| d.get_id() == id)
+            .map(|d| rmp_serde::to_vec_named(d).unwrap())
+    };
+    let size = |closet: &Closet, id: &DrawerId| {
+        closet
+            .drawers
+            .iter()
+            .find(|d| d.get_id() == id)
+            .map_or(0, |d| d.crypted_size())
+    };
+    let mut previous = Closet::from_file(&path).unwrap();
+    // the decoys created with the closet were adopted by the first
+    // drawer, then replaced when it was closed
+    let previous_ids = previous.drawer_ids();
+    assert!(initial_decoys.iter().all(|id| !previous_ids.contains(id)));
+    let initial_count = previous.drawers.len();
+    let mut previous_new_ids: Vec<DrawerId> = Vec::new();
+    let mut suspects_counts = Vec::new();
+    for i in 0..15 {
+        let mut open_closet = OpenCloset::open(path.to_path_buf()).unwrap();
+        let drawer = open_closet.open_drawer(used_pwd).unwrap();
+        drawer
+            .content
+            .entries
+            .push(Entry::new(i.to_string(), "a value making the drawer grow"));
+        open_closet.close_and_save().unwrap();
+        let current = Closet::from_file(&path).unwrap();
+        let mut open_closet = OpenCloset::open(path.to_path_buf()).unwrap();
+        let used_id = open_closet
+            .open_drawer(used_pwd)
+            .unwrap()
+            .content
+            .id
+            .clone();
+        let previous_ids = previous.drawer_ids();
+        let current_ids = current.drawer_ids();
+        // a drawer present in both versions is unchanged
+        for id in current_ids.iter().filter(|id| previous_ids.contains(id)) {
+            assert_eq!(blob(&previous, id), blob(&current, id));
+        }
+        // the drawers which appeared in the previous version all disappear,
+        // the real one like its decoys
+        for id in &previous_new_ids {
+            assert!(!current_ids.contains(id));
+        }
+        // the real drawer is among the new ones, but never alone
+        let new_ids: Vec<DrawerId> = current_ids
+            .into_iter()
+            .filter(|id| !previous_ids.contains(id))
+            .collect();
+        assert!(new_ids.contains(&used_id));
+        // and the new decoys have exactly its size, so that the sizes
+        // of the new blobs don't narrow the suspects
+        let used_size = size(&current, &used_id);
+        for id in &new_ids {
+            assert_eq!(size(&current, id), used_size);
+        }
+        suspects_counts.push(new_ids.len());
+        previous_new_ids = new_ids;
+        previous = current;
+    }
+    assert!(suspects_counts.iter().all(|&n| n > MIN_SIBLING_DECOYS));
+    // guessing the real drawer among the new ones seldom succeeds
+    let guess_success: f64 =
+        suspects_counts.iter().map(|&n| 1.0 / n as f64).sum::<f64>() / suspects_counts.len() as f64;
+    assert!(guess_success <= 1.0 / (1 + MIN_SIBLING_DECOYS) as f64);
+    // the number of drawers doesn't drift
+    assert!(previous.drawers.len() <= initial_count + MAX_SIBLING_DECOYS);
+    assert!(previous.drawer_ids().contains(&unused_id));
+    temp_dir.close().unwrap();
+}
+
+/// check that a deleted drawer, and the drawers it contained, can't be
+/// opened anymore, while the number of drawers of the closet is unchanged
+#[test]
+fn test_delete_drawer() {
+    let kept_pwd = "a drawer which stays";
+    let deleted_pwd = "an obsolete drawer";
+    let deep_pwd = "a drawer in the obsolete one";
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("test-delete.closet");
+    // with random padding, the size of the new decoy is the only one
+    // which doesn't depend on a bucket
+    let mut open_closet = OpenCloset::create_with_params(
+        path.to_path_buf(),
+        KdfParams::for_tests(),
+        PaddingPolicy::Random,
+    )
+    .unwrap();
+    open_closet.create_drawer(kept_pwd).unwrap();
+    open_closet.close_deepest_drawer().unwrap();
+    let deleted = open_closet.create_drawer(deleted_pwd).unwrap();
+    for i in 0..50 {
+        deleted
+            .content
+            .entries
+            .push(Entry::new(format!("key {i}"), "value"));
+    }
+    open_closet.create_drawer(deep_pwd).unwrap();
+    open_closet.close_and_save().unwrap();
+    let sizes = |closet: &Closet| {
+        let mut sizes: Vec<usize> = closet.drawers.iter().map(|d| d.clear_size()).collect();
+        sizes.sort_unstable();
+        sizes
+    };
+    let before = sizes(&Closet::from_file(&path).unwrap());
+    let mut open_closet = OpenCloset::open(path.to_path_buf()).unwrap();
+    let drawer = open_closet.open_take_drawer(deleted_pwd).unwrap();
+    open_closet.delete_drawer(&drawer).unwrap();
+    // the decoys of the deleted drawer are adopted by the next one
+    let orphans = drawer.content.sibling_decoys.clone();
+    assert!(!orphans.is_empty());
+    let adopter = open_closet.create_drawer("a new drawer").unwrap();
+    for id in &orphans {
+        assert!(adopter.content.sibling_decoys.contains(id));
+    }
+    assert_eq!(adopter.content.sibling_decoys.len(), orphans.len() + 1);
+    open_closet.close_deepest_drawer().unwrap();
+    let mut open_closet = OpenCloset::open(path.to_path_buf()).unwrap();
+    let drawer = open_closet.open_take_drawer(deleted_pwd).unwrap();
+    open_closet.delete_drawer(&drawer).unwrap();
+    open_closet.close_and_save().unwrap();
+    // the new decoy has the size of the deleted drawer
+    assert_eq!(sizes(&Closet::from_file(&path).unwrap()), before);
+    let mut open_closet = OpenCloset::open(path.to_path_buf()).unwrap();
+    assert!(open_closet.open_drawer(deleted_pwd).is_none());
+    assert!(open_closet.open_drawer(kept_pwd).is_some());
+    temp_dir.close().unwrap();
+}
+
+/// check that an extracted drawer, with the deep drawers whose passphrases
+/// are given, opens in the new closet with the new passphrase only, and that
+/// nothing else of the original closet is copied
+#[test]
+fn test_extract_drawer() {
+    let top_pwd = "the project drawer";
+    let deep_pwd = "a drawer in the project one";
+    let other_deep_pwd = "another drawer in the project one";
+    let new_pwd = "the passphrase given to the contractor";
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("test-extract.closet");
+    let extract_path = temp_dir.path().join("test-extracted.closet");
+    let mut open_closet = OpenCloset::create_for_tests(path.to_path_buf()).unwrap();
+    let top = open_closet.create_drawer(top_pwd).unwrap();
+    let mut entry = Entry::new("server", "old secret");
+    entry.set_value("secret".to_string(), 10);
+    top.content.entries.push(entry);
+    let deep = open_closet.create_drawer(deep_pwd).unwrap();
+    let mut entry = Entry::new("root", "old very secret");
+    entry.set_value("very secret".to_string(), 10);
+    deep.content.entries.push(entry);
+    open_closet.close_deepest_drawer().unwrap();
+    open_closet.create_drawer(other_deep_pwd).unwrap();
+    open_closet.close_and_save().unwrap();
+    let mut open_closet = OpenCloset::open(path.to_path_buf()).unwrap();
+    let top = open_closet.open_take_drawer(top_pwd).unwrap();
+    let deep_drawers = || {
+        let mut plan = RekeyPlan::for_drawer_copy(&top.content.closet);
+        assert!(plan.add_password(&top.content.closet, &[deep_pwd.to_string()]));
+        plan
+    };
+    open_closet
+        .extract_drawer(&top, &extract_path, new_pwd, deep_drawers())
+        .unwrap();
+    assert!(
+        open_closet
+            .extract_drawer(&top, &extract_path, new_pwd, deep_drawers())
+            .is_err()
+    );
+    let mut extracted = OpenCloset::open(extract_path.to_path_buf()).unwrap();
+    assert!(extracted.root_drawers_count() > 1);
+    assert!(extracted.open_drawer(top_pwd).is_none());
+    let copy = extracted.open_drawer(new_pwd).unwrap();
+    assert!(copy.content.id != top.content.id);
+    assert_eq!(copy.content.entries[0].value, "secret");
+    assert!(copy.content.entries[0].history.is_empty());
+    assert!(!top.content.entries[0].history.is_empty());
+    assert_ne!(copy.content.closet.salt, top.content.closet.salt);
+    let copied_ids = copy.content.closet.drawer_ids();
+    assert!(
+        top.content
+            .closet
+            .drawer_ids()
+            .iter()
+            .all(|id| !copied_ids.contains(id))
+    );
+    let deep = extracted.open_drawer(deep_pwd).unwrap();
+    assert_eq!(deep.content.entries[0].value, "very secret");
+    assert!(deep.content.entries[0].history.is_empty());
+    extracted.close_deepest_drawer().unwrap();
+    assert!(extracted.open_drawer(other_deep_pwd).is_none());
+    temp_dir.close().unwrap();
+}
+
+/// check that a top level drawer can be hidden in another one, and a deep
+/// drawer promoted to the top level, the drawers they contain following
+#[test]
+fn test_relocate_drawer() {
+    let a_pwd = "a top level drawer";
+    let b_pwd = "another top level drawer";
+    let c_pwd = "a drawer in the first one";
+    let d_pwd = "a drawer in c";
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("test-relocate.closet");
+    let mut open_closet = OpenCloset::create_for_tests(path.to_path_buf()).unwrap();
+    open_closet.create_drawer(b_pwd).unwrap();
+    open_closet.close_deepest_drawer().unwrap();
+    open_closet.create_drawer(a_pwd).unwrap();
+    let c = open_closet.create_drawer(c_pwd).unwrap();
+    c.content.entries.push(Entry::new("c", "in c"));
+    let d = open_closet.create_drawer(d_pwd).unwrap();
+    d.content.entries.push(Entry::new("d", "in d"));
+    open_closet.close_and_save().unwrap();
+
+    // promoting c to the top level
+    let mut open_closet = OpenCloset::open(path.to_path_buf()).unwrap();
+    open_closet.open_drawer(a_pwd).unwrap();
+    let c = open_closet.open_take_drawer(c_pwd).unwrap();
+    let target = RelocationTarget::Upper(0);
+    assert!(open_closet.check_relocation(&c, &target).is_err());
+    let target = RelocationTarget::Root;
+    open_closet.check_relocation(&c, &target).unwrap();
+    let c = open_closet.relocate_drawer(c, target).unwrap();
+    assert_eq!(c.depth, 0);
+    assert_eq!(open_closet.depth(), 0);
+    let mut open_closet = OpenCloset::open(path.to_path_buf()).unwrap();
+    let c = open_closet.open_drawer(c_pwd).unwrap();
+    assert_eq!(c.content.entries[0].value, "in c");
+    // d followed c
+    let d = open_closet.open_drawer(d_pwd).unwrap();
+    assert_eq!(d.depth, 1);
+    assert_eq!(d.content.entries[0].value, "in d");
+    open_closet.close_deepest_drawer().unwrap();
+    open_closet.close_deepest_drawer().unwrap();
+    // the old place of c is taken by a decoy
+    open_closet.open_drawer(a_pwd).unwrap();
+    assert_eq!(open_closet.open_drawer(c_pwd).unwrap().depth, 0);
+
+    // hiding b in a
+    let mut open_closet = OpenCloset::open(path.to_path_buf()).unwrap();
+    let b = open_closet.open_take_drawer(b_pwd).unwrap();
+    let a = open_closet.deepest_closet().open_drawer(0, a_pwd).unwrap();
+    let target = RelocationTarget::Sibling(a);
+    open_closet.check_relocation(&b, &target).unwrap();
+    let b = open_closet.relocate_drawer(b, target).unwrap();
+    assert_eq!(b.depth, 1);
+    let mut open_closet = OpenCloset::open(path.to_path_buf()).unwrap();
+    assert!(open_closet.open_drawer(b_pwd).is_none());
+    open_closet.open_drawer(a_pwd).unwrap();
+    assert_eq!(open_closet.open_drawer(b_pwd).unwrap().depth, 1);
+    temp_dir.close().unwrap();
+}
+
+/// check that a passphrase opens its drawer whatever its normalization, and that
+/// a drawer crypted with a non normalized passphrase by an older version still
+/// opens, then is migrated on save
+#[test]
+fn test_password_normalization() {
+    let nfc = "les sanglots lents et violents de l'autre \u{e2}ne";
+    let nfd = "les sanglots lents et violents de l'autre a\u{302}ne";
+    assert_ne!(nfc, nfd);
+    assert_eq!(normalize_password(nfd), nfc);
+    let kdf = KdfParams::for_tests();
+    let mut closet = Closet::new(0, kdf, PaddingPolicy::default()).unwrap();
+    // typed on a platform producing decomposed characters, opened on another one
+    let drawer = closet.create_drawer(0, nfd.to_string(), 0).unwrap();
+    closet.close_drawer(drawer).unwrap();
+    assert!(closet.open_drawer(0, nfc).is_some());
+    // a drawer crypted with the raw bytes, like older versions did
+    let legacy = "l'\u{e9}t\u{e9} e\u{301}tait chaud";
+    let mut drawer = OpenDrawer::new(
+        0,
+        legacy.to_string(),
+        DrawerContent::new(0, kdf, closet.padding).unwrap(),
+    );
+    drawer.content.entries.push(Entry::new("key", "value"));
+    let raw_cipher = kdf
+        .cipher(legacy.as_bytes(), closet.salt.as_bytes())
+        .unwrap();
+    let closed_drawer = drawer
+        .close_with_cipher(&raw_cipher, closet.padding, 0)
+        .unwrap();
+    closet.drawers.push(closed_drawer);
+    let drawer = closet.open_drawer(0, legacy).unwrap();
+    assert_eq!(drawer.content.entries, vec![Entry::new("key", "value")]);
+    assert!(closet.close_drawer(drawer).unwrap());
+    // after the save, the normalized passphrase opens it
+    let migrated = closet.open_drawer(0, &normalize_password(legacy)).unwrap();
+    assert_eq!(migrated.content.entries, vec![Entry::new("key", "value")]);
+    // a drawer crypted with the decomposed bytes by an older version, typed
+    // on a platform producing them, then opened from one producing NFC
+    let typed = "No\u{eb}l \u{e0} la mer";
+    let decomposed = "Noe\u{308}l a\u{300} la mer";
+    let mut drawer = OpenDrawer::new(
+        0,
+        decomposed.to_string(),
+        DrawerContent::new(0, kdf, closet.padding).unwrap(),
+    );
+    drawer
+        .content
+        .entries
+        .push(Entry::new("other key", "other value"));
+    let nfd_cipher = kdf
+        .cipher(decomposed.as_bytes(), closet.salt.as_bytes())
+        .unwrap();
+    let closed_drawer = drawer
+        .close_with_cipher(&nfd_cipher, closet.padding, 0)
+        .unwrap();
+    closet.drawers.push(closed_drawer);
+    let drawer = closet.open_drawer(0, typed).unwrap();
+    assert_eq!(
+        drawer.content.entries,
+        vec![Entry::new("other key", "other value")]
+    );
+    assert!(closet.close_drawer(drawer).unwrap());
+    // after the save, it's crypted with the normalized passphrase
+    assert!(
+        closet
+            .drawers
+            .iter()
+            .all(|d| d.open(0, decomposed.to_string(), &nfd_cipher).is_err())
+    );
+    assert!(closet.open_drawer(0, typed).is_some());
+}
+
+/// check the title of a drawer is kept crypted with its content
+#[test]
+fn test_drawer_title() {
+    let kdf = KdfParams::for_tests();
+    let mut closet = Closet::new(0, kdf, PaddingPolicy::default()).unwrap();
+    let mut drawer = closet.create_drawer(0, "pwd".to_string(), 0).unwrap();
+    assert_eq!(drawer.content.title, None);
+    assert!(drawer.content.set_title("  Bank accounts "));
+    assert!(!drawer.content.set_title("Bank accounts"));
+    closet.close_drawer(drawer).unwrap();
+    let mut drawer = closet.open_drawer(0, "pwd").unwrap();
+    assert_eq!(drawer.content.title.as_deref(), Some("Bank accounts"));
+    assert!(drawer.content.set_title(""));
+    assert_eq!(drawer.content.title, None);
+}
+
+/// check a closet file written before the padding was configurable
+/// keeps the random padding, while new closets are padded to buckets
+#[test]
+fn test_legacy_padding() {
+    #[derive(Serialize)]
+    struct LegacyCloset {
+        comments: String,
+        salt: String,
+        drawers: Vec<ClosedDrawer>,
+    }
+    let kdf = KdfParams::for_tests();
+    let closet = Closet::new(0, kdf, PaddingPolicy::default()).unwrap();
+    assert_eq!(closet.padding, PaddingPolicy::PowerOfTwo);
+    let legacy = LegacyCloset {
+        comments: closet.comments,
+        salt: closet.salt,
+        drawers: closet.drawers,
+    };
+    let bytes = rmp_serde::encode::to_vec_named(&legacy).unwrap();
+    let closet: Closet = rmp_serde::decode::from_slice(&bytes).unwrap();
+    assert_eq!(closet.padding, PaddingPolicy::Random);
+}
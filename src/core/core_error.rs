@@ -4,8 +4,8 @@ pub enum CoreError {
     #[error("IO error: {0}")]
     IO(#[from] std::io::Error),
 
-    #[error("Passphrase too short")]
-    PasswordTooShort,
+    #[error("Passphrase too weak ({0})")]
+    PasswordTooWeak(&'static str),
 
     #[error("MessagePack Encode error: {0}")]
     MessagePackEncode(#[from] rmp_serde::encode::Error),
@@ -42,4 +42,22 @@ pub enum CoreError {
 
     #[error("Operation only permitted at max depth")]
     OperationOnlyPermittedAtMaxDepth,
+
+    #[error("Operation only permitted when no drawer is open")]
+    OperationOnlyPermittedWithNoOpenDrawer,
+
+    #[error("Invalid password policy: {0}")]
+    InvalidPasswordPolicy(String),
+
+    #[error("File too big to be attached ({0} bytes)")]
+    AttachmentTooBig(u64),
+
+    #[error("The extracted closet can't be reopened")]
+    ExtractCheckFailed,
+
+    #[error("The drawer can't be moved there")]
+    InvalidRelocation,
+
+    #[error("Invalid key derivation costs: {0}")]
+    InvalidKdfParams(String),
 }
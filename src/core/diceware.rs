@@ -0,0 +1,130 @@
+use {
+    rand::{
+        Rng,
+        rng,
+    },
+    std::fmt,
+};
+
+static ENGLISH_WORDS: &str = include_str!("wordlists/english.txt");
+static FRENCH_WORDS: &str = include_str!("wordlists/french.txt");
+
+/// The separators proposed between words, in cycling order
+pub const DICEWARE_SEPARATORS: &[&str] = &["-", " ", ".", "_"];
+
+pub const MIN_DICEWARE_WORD_COUNT: usize = 3;
+pub const MAX_DICEWARE_WORD_COUNT: usize = 16;
+
+/// The language of an embedded word list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DicewareLanguage {
+    #[default]
+    English,
+    French,
+}
+
+impl DicewareLanguage {
+    pub const ALL: &'static [Self] = &[Self::English, Self::French];
+    /// Return the words of the embedded list, all distinct
+    /// and made of lowercase ASCII letters
+    pub fn words(self) -> impl Iterator<Item = &'static str> {
+        match self {
+            Self::English => ENGLISH_WORDS,
+            Self::French => FRENCH_WORDS,
+        }
+        .lines()
+    }
+    pub fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|&l| l == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+}
+
+impl fmt::Display for DicewareLanguage {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::English => write!(f, "english"),
+            Self::French => write!(f, "french"),
+        }
+    }
+}
+
+/// Settings of a generator of passphrases made of words
+/// randomly picked in an embedded list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diceware {
+    pub word_count: usize,
+    pub separator: &'static str,
+    pub language: DicewareLanguage,
+}
+
+impl Default for Diceware {
+    fn default() -> Self {
+        Self {
+            word_count: 6,
+            separator: DICEWARE_SEPARATORS[0],
+            language: DicewareLanguage::default(),
+        }
+    }
+}
+
+impl Diceware {
+    /// Return the entropy, in bits, of a generated passphrase (the
+    /// separator doesn't count as it's not secret)
+    pub fn entropy_bits(&self) -> f64 {
+        let list_len = self.language.words().count();
+        self.word_count as f64 * (list_len as f64).log2()
+    }
+    pub fn generate(&self) -> String {
+        let words: Vec<&str> = self.language.words().collect();
+        let mut rng = rng();
+        (0..self.word_count)
+            .map(|_| words[rng.random_range(0..words.len())])
+            .collect::<Vec<&str>>()
+            .join(self.separator)
+    }
+    pub fn add_words(
+        &mut self,
+        delta: isize,
+    ) {
+        let word_count = (self.word_count as isize + delta).max(0) as usize;
+        self.word_count = word_count.clamp(MIN_DICEWARE_WORD_COUNT, MAX_DICEWARE_WORD_COUNT);
+    }
+    pub fn next_separator(&mut self) {
+        let idx = DICEWARE_SEPARATORS
+            .iter()
+            .position(|&s| s == self.separator)
+            .unwrap_or(0);
+        self.separator = DICEWARE_SEPARATORS[(idx + 1) % DICEWARE_SEPARATORS.len()];
+    }
+}
+
+/// check the embedded lists are usable and the passphrases
+/// follow the settings
+#[test]
+fn test_diceware() {
+    for &language in DicewareLanguage::ALL {
+        let mut words: Vec<&str> = language.words().collect();
+        assert_eq!(words.len(), 1024);
+        assert!(
+            words
+                .iter()
+                .all(|w| !w.is_empty() && w.chars().all(|c| c.is_ascii_lowercase()))
+        );
+        words.sort_unstable();
+        words.dedup();
+        assert_eq!(words.len(), 1024);
+        let diceware = Diceware {
+            word_count: 5,
+            separator: ".",
+            language,
+        };
+        assert_eq!(diceware.entropy_bits(), 50.0);
+        let passphrase = diceware.generate();
+        assert_eq!(passphrase.split('.').count(), 5);
+        assert!(passphrase.split('.').all(|w| words.contains(&w)));
+    }
+}
@@ -7,10 +7,14 @@ use {
 };
 
 /// What's inside a drawer
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct DrawerContent {
     pub id: DrawerId,
 
+    /// an optional name, only readable once the drawer is open
+    #[serde(default)]
+    pub title: Option<String>,
+
     /// the entries of this depth
     pub entries: Vec<Entry>,
 
@@ -20,6 +24,11 @@ pub struct DrawerContent {
     /// the crypted sub-drawers
     pub closet: Closet,
 
+    /// the decoys, in the closet containing this drawer, which are
+    /// regenerated when this drawer is saved
+    #[serde(default)]
+    pub(crate) sibling_decoys: Vec<DrawerId>,
+
     /// some random bytes, rewritten before every save
     garbage: Box<[u8]>,
 }
@@ -31,21 +40,46 @@ impl Identified for DrawerContent {
 }
 
 impl DrawerContent {
-    pub fn new(depth: usize) -> Result<Self, CoreError> {
+    pub fn new(
+        depth: usize,
+        kdf: KdfParams,
+        padding: PaddingPolicy,
+    ) -> Result<Self, CoreError> {
         let id = DrawerId::new();
         let entries = Vec::new();
         let settings = DrawerSettings::default();
-        let closet = Closet::new(depth + 1)?;
+        let closet = Closet::new(depth + 1, kdf, padding)?;
         let garbage = Vec::new().into(); // will be (re)filled for save
         Ok(Self {
             id,
+            title: None,
             entries,
             settings,
             closet,
+            sibling_decoys: Vec::new(),
             garbage,
         })
     }
 
+    /// Set the title of the drawer, an empty one removing it,
+    /// and return whether it changed
+    pub fn set_title(
+        &mut self,
+        title: &str,
+    ) -> bool {
+        let title = title.trim();
+        let title = if title.is_empty() {
+            None
+        } else {
+            Some(title.to_string())
+        };
+        if self.title == title {
+            return false;
+        }
+        self.title = title;
+        true
+    }
+
     /// Return the index of the first empty entry, creating
     /// it if necessary
     pub fn empty_entry(&mut self) -> usize {
@@ -58,6 +92,31 @@ impl DrawerContent {
         self.entries.len() - 1
     }
 
+    /// Truncate the histories of all entries to the given depth
+    /// (a depth of 0 purging them)
+    pub fn truncate_histories(
+        &mut self,
+        depth: usize,
+    ) {
+        for entry in &mut self.entries {
+            entry.history.truncate(depth);
+        }
+    }
+
+    /// Return the tags used in the drawer, sorted, with the
+    /// number of entries having each of them
+    pub fn tag_counts(&self) -> Vec<(String, usize)> {
+        let mut counts: Vec<(String, usize)> = Vec::new();
+        for tag in self.entries.iter().flat_map(|entry| &entry.tags) {
+            match counts.iter_mut().find(|(t, _)| t.eq_ignore_ascii_case(tag)) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((tag.clone(), 1)),
+            }
+        }
+        counts.sort_by_key(|(tag, _)| tag.to_lowercase());
+        counts
+    }
+
     /// Insert a new entry at the given index or before and
     /// return the index of the new entry
     #[allow(dead_code)]
@@ -91,10 +150,46 @@ impl DrawerContent {
 
     /// Shuffle the drawers (thus ensuring the last created one
     /// isn't at the end), add some random bytes which makes the
-    /// content's size undetectable
-    pub fn add_noise(&mut self) {
-        self.garbage = random_bytes_random_size(5..5000);
+    /// content's size undetectable, then serialize the content.
+    ///
+    /// With a bucket based padding policy, the serialized content
+    /// has exactly the size of a bucket, of at least `min_size` bytes.
+    ///
+    /// With the random policy, a non zero `min_size` (the size of the
+    /// real drawer a decoy goes along) is the exact size of the
+    /// serialized content, unless the content is bigger.
+    pub fn serialize_with_noise(
+        &mut self,
+        padding: PaddingPolicy,
+        min_size: usize,
+    ) -> Result<Vec<u8>, CoreError> {
         self.closet.shuffle_drawers();
+        if padding == PaddingPolicy::Random && min_size == 0 {
+            self.garbage = random_bytes_random_size(5..5000);
+            return Ok(rmp_serde::encode::to_vec_named(self)?);
+        }
+        self.garbage = Vec::new().into();
+        let unpadded_size = rmp_serde::encode::to_vec_named(self)?.len();
+        // size without the header of the empty garbage array
+        let base_size = unpadded_size - 1;
+        let target_size = |size: usize| match padding {
+            PaddingPolicy::Random => Some(size),
+            _ => padding.bucket_size(size),
+        };
+        let mut target = target_size(unpadded_size.max(min_size));
+        while let Some(size) = target {
+            if let Some(len) = garbage_len(size - base_size) {
+                self.garbage = random_fixint_bytes(len);
+                let serialized = rmp_serde::encode::to_vec_named(self)?;
+                if serialized.len() != size {
+                    return Err(CoreError::InternalError("wrong padding".to_string()));
+                }
+                return Ok(serialized);
+            }
+            // no garbage length fits exactly, we take the next bucket
+            target = target_size(size + 1);
+        }
+        Err(CoreError::InternalError("no padding bucket".to_string()))
     }
 
     /// Remove entries with both name and value empty
@@ -102,3 +197,19 @@ impl DrawerContent {
         self.entries.retain(|e| !e.is_empty());
     }
 }
+
+/// Return the length of a garbage array whose serialization takes
+/// exactly `space` bytes, if there's one.
+///
+/// The garbage is serialized by MessagePack as an array whose header
+/// takes 1, 3 or 5 bytes depending on its length, and whose items
+/// take one byte each as they're all lower than 128.
+fn garbage_len(space: usize) -> Option<usize> {
+    [(1, 0..=15), (3, 16..=0xFFFF), (5, 0x1_0000..=0xFFFF_FFFF)]
+        .into_iter()
+        .find_map(|(header, lengths)| {
+            space
+                .checked_sub(header)
+                .filter(|len| lengths.contains(len))
+        })
+}
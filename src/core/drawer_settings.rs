@@ -1,10 +1,19 @@
-use serde::{
-    Deserialize,
-    Serialize,
+use {
+    super::*,
+    serde::{
+        Deserialize,
+        Serialize,
+    },
 };
 
+/// Number of previous values kept per entry, unless
+/// changed in the drawer settings
+pub const DEFAULT_HISTORY_DEPTH: usize = 10;
+
+pub const MAX_HISTORY_DEPTH: usize = 100;
+
 /// settings of a drawer, saved in the drawer
-#[derive(Serialize, Deserialize, Default)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct DrawerSettings {
     /// whether to hide unselected entry values
     pub hide_values: bool,
@@ -14,4 +23,30 @@ pub struct DrawerSettings {
     /// whether to show values as markdown
     #[serde(default)]
     pub values_as_markdown: bool,
+    /// whether to show the date of the last change of entries
+    #[serde(default)]
+    pub show_ages: bool,
+    /// named rules for the password generator
+    #[serde(default)]
+    pub password_policies: Vec<PasswordPolicy>,
+    /// number of previous values kept in the history of each entry
+    #[serde(default = "default_history_depth")]
+    pub history_depth: usize,
+}
+
+fn default_history_depth() -> usize {
+    DEFAULT_HISTORY_DEPTH
+}
+
+impl Default for DrawerSettings {
+    fn default() -> Self {
+        Self {
+            hide_values: false,
+            open_all_values: false,
+            values_as_markdown: false,
+            show_ages: false,
+            password_policies: Vec::new(),
+            history_depth: DEFAULT_HISTORY_DEPTH,
+        }
+    }
 }
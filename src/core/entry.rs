@@ -1,13 +1,46 @@
-use serde::{
-    Deserialize,
-    Serialize,
+use {
+    super::*,
+    serde::{
+        Deserialize,
+        Serialize,
+    },
 };
 
+/// A previous value of an entry, kept in its history
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PastValue {
+    pub value: String,
+    /// when the value was replaced, in seconds since the epoch
+    pub replaced: u64,
+}
+
 /// one of the socks in the drawer
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub struct Entry {
     pub name: String,
     pub value: String,
+    /// the typed fields, if any, completing or replacing the value
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<EntryField>,
+    /// creation time, in seconds since the epoch (unknown for
+    /// entries created before timestamps were recorded)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<u64>,
+    /// time of the last change of the name, value or fields
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<u64>,
+    /// the previous values, most recent first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<PastValue>,
+    /// the tags, without their leading `#`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// the attached files
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+    /// when the entry must be changed, if it must
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiry: Option<Expiry>,
 }
 
 impl Entry {
@@ -19,9 +52,180 @@ impl Entry {
         Self {
             name: name.into(),
             value: value.into(),
+            fields: Vec::new(),
+            created: None,
+            modified: None,
+            history: Vec::new(),
+            tags: Vec::new(),
+            attachments: Vec::new(),
+            expiry: None,
         }
     }
     pub fn is_empty(&self) -> bool {
-        self.name.is_empty() && self.value.is_empty()
+        self.name.is_empty()
+            && self.value.is_empty()
+            && self.fields.is_empty()
+            && self.tags.is_empty()
+            && self.attachments.is_empty()
+    }
+    /// Record a change of the entry, now
+    pub fn touch(&mut self) {
+        let now = unix_now();
+        self.created.get_or_insert(now);
+        self.modified = Some(now);
+    }
+    /// Change the value, keeping the previous one in the history, which
+    /// is truncated to the given depth
+    pub fn set_value(
+        &mut self,
+        value: String,
+        history_depth: usize,
+    ) {
+        if value == self.value {
+            return;
+        }
+        let previous = std::mem::replace(&mut self.value, value);
+        if !previous.is_empty() {
+            self.history.insert(
+                0,
+                PastValue {
+                    value: previous,
+                    replaced: unix_now(),
+                },
+            );
+        }
+        self.history.truncate(history_depth);
+        self.touch();
     }
+    /// Make a past value the current one, the current
+    /// value going into the history
+    pub fn restore(
+        &mut self,
+        history_idx: usize,
+        history_depth: usize,
+    ) {
+        if history_idx < self.history.len() {
+            let past = self.history.remove(history_idx);
+            self.set_value(past.value, history_depth.max(1));
+        }
+    }
+    /// Return the number of seconds since the last change, if known
+    pub fn age(
+        &self,
+        now: u64,
+    ) -> Option<u64> {
+        self.modified.map(|modified| now.saturating_sub(modified))
+    }
+    /// Tell whether the entry has the given tag, case insensitively
+    pub fn has_tag(
+        &self,
+        tag: &str,
+    ) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+    /// Replace the tags with the ones of a string like `#prod web, #db`,
+    /// returning whether they changed
+    pub fn set_tags(
+        &mut self,
+        s: &str,
+    ) -> bool {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in s.split(|c: char| c.is_whitespace() || c == ',') {
+            let tag = tag.trim_start_matches('#');
+            if !tag.is_empty() && !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.to_string());
+            }
+        }
+        if tags == self.tags {
+            return false;
+        }
+        self.tags = tags;
+        true
+    }
+    /// Return the tags as written in the search input, like `#prod #web`
+    pub fn tags_string(&self) -> String {
+        self.tags
+            .iter()
+            .map(|tag| format!("#{tag}"))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+    /// Return the TOTP generator when the value is an otpauth URI
+    /// or a seed of one-time codes
+    pub fn totp(&self) -> Option<Totp> {
+        Totp::from_entry(self)
+    }
+    /// Tell whether the entry displays one-time codes, either as
+    /// its value or in a field
+    pub fn has_totp(&self) -> bool {
+        self.totp().is_some() || self.fields.iter().any(|field| field.totp().is_some())
+    }
+}
+
+/// check entries saved before the introduction of fields and timestamps
+/// are still read, and that such entries are saved as before
+#[test]
+fn test_entry_compatibility() {
+    #[derive(Serialize, Deserialize)]
+    struct OldEntry {
+        name: String,
+        value: String,
+    }
+    let old = OldEntry {
+        name: "key".to_string(),
+        value: "value".to_string(),
+    };
+    let bytes = rmp_serde::to_vec_named(&old).unwrap();
+    let entry: Entry = rmp_serde::from_slice(&bytes).unwrap();
+    assert_eq!(entry, Entry::new("key", "value"));
+    assert_eq!(rmp_serde::to_vec_named(&entry).unwrap(), bytes);
+    let mut entry = Entry::new("site", "");
+    entry
+        .fields
+        .push(EntryField::new("login", FieldKind::Text, "me"));
+    entry
+        .fields
+        .push(EntryField::new("password", FieldKind::Secret, "pwd"));
+    let bytes = rmp_serde::to_vec_named(&entry).unwrap();
+    let read: Entry = rmp_serde::from_slice(&bytes).unwrap();
+    assert_eq!(read, entry);
+    assert!(!read.is_empty());
+    entry.touch();
+    assert!(entry.created.is_some());
+    assert_eq!(entry.created, entry.modified);
+    let bytes = rmp_serde::to_vec_named(&entry).unwrap();
+    let read: Entry = rmp_serde::from_slice(&bytes).unwrap();
+    assert_eq!(read, entry);
+}
+
+/// check the history keeps the previous values, most recent first,
+/// within the depth
+#[test]
+fn test_entry_history() {
+    let mut entry = Entry::new("key", "");
+    for value in ["v1", "v2", "v3", "v3", "v4"] {
+        entry.set_value(value.to_string(), 2);
+    }
+    assert_eq!(entry.value, "v4");
+    let past: Vec<&str> = entry.history.iter().map(|p| p.value.as_str()).collect();
+    assert_eq!(past, vec!["v3", "v2"]);
+    entry.restore(1, 2);
+    assert_eq!(entry.value, "v2");
+    let past: Vec<&str> = entry.history.iter().map(|p| p.value.as_str()).collect();
+    assert_eq!(past, vec!["v4", "v3"]);
+    entry.set_value("v5".to_string(), 0);
+    assert!(entry.history.is_empty());
+}
+
+#[test]
+fn test_entry_tags() {
+    let mut entry = Entry::new("key", "value");
+    assert!(entry.set_tags("#prod web, #Prod  #db,"));
+    assert_eq!(entry.tags, vec!["prod", "web", "db"]);
+    assert!(entry.has_tag("PROD"));
+    assert!(!entry.has_tag("staging"));
+    assert_eq!(entry.tags_string(), "#prod #web #db");
+    assert!(!entry.set_tags(&entry.tags_string()));
+    assert!(entry.set_tags(""));
+    assert!(entry.tags.is_empty());
 }
@@ -0,0 +1,82 @@
+use {
+    super::*,
+    serde::{
+        Deserialize,
+        Serialize,
+    },
+    std::fmt,
+};
+
+/// The type of a field, telling how it's displayed and copied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum FieldKind {
+    #[default]
+    Text,
+    /// a password or any value which must stay masked in the drawer
+    Secret,
+    Url,
+    /// an otpauth URI or a base32 seed of one-time codes
+    Totp,
+    /// a free text, possibly on several lines
+    Note,
+}
+
+impl FieldKind {
+    pub const ALL: &'static [Self] = &[Self::Text, Self::Secret, Self::Url, Self::Totp, Self::Note];
+    pub fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|&k| k == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+    /// Tell whether values of this kind must not be displayed
+    /// in the drawer
+    pub fn is_masked(self) -> bool {
+        matches!(self, Self::Secret | Self::Totp)
+    }
+}
+
+impl fmt::Display for FieldKind {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let name = match self {
+            Self::Text => "text",
+            Self::Secret => "secret",
+            Self::Url => "url",
+            Self::Totp => "totp",
+            Self::Note => "note",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A named and typed value of an entry, for example
+/// the username or the password of a login
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct EntryField {
+    pub name: String,
+    pub kind: FieldKind,
+    pub value: String,
+}
+
+impl EntryField {
+    pub fn new<N: Into<String>, V: Into<String>>(
+        name: N,
+        kind: FieldKind,
+        value: V,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            value: value.into(),
+        }
+    }
+    /// Return the TOTP generator of a field of the TOTP kind,
+    /// if its value can be read
+    pub fn totp(&self) -> Option<Totp> {
+        match self.kind {
+            FieldKind::Totp => Totp::from_value(&self.value),
+            _ => None,
+        }
+    }
+}
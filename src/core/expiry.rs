@@ -0,0 +1,181 @@
+use {
+    super::*,
+    serde::{
+        Deserialize,
+        Serialize,
+    },
+    std::fmt,
+};
+
+/// Entries expiring in less than this number of days are reported
+pub const EXPIRY_WARNING_DAYS: u64 = 14;
+
+/// When an entry must be changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Expiry {
+    /// a fixed date, in seconds since the epoch
+    At(u64),
+    /// a rotation interval, in days, counted from the last change
+    /// of the entry
+    Every(u64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpiryStatus {
+    Valid,
+    /// expiring in less than EXPIRY_WARNING_DAYS days
+    Soon,
+    Expired,
+}
+
+impl Expiry {
+    /// Parse either a date like `2025-12-31` or an interval like
+    /// `90d`, `12w`, `6m` or `1y`
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if let Some(time) = parse_date(s) {
+            return Some(Self::At(time));
+        }
+        let s = s.strip_prefix("every ").unwrap_or(s).trim();
+        let unit = s.chars().last()?;
+        let days_per_unit = match unit {
+            'd' => 1,
+            'w' => 7,
+            'm' => 30,
+            'y' => 365,
+            _ => return None,
+        };
+        let count: u64 = s[..s.len() - 1].parse().ok()?;
+        if count == 0 {
+            return None;
+        }
+        count.checked_mul(days_per_unit).map(Self::Every)
+    }
+    /// Return the time the entry expires, in seconds since the
+    /// epoch, or None for a rotation of an entry whose last change
+    /// is unknown
+    pub fn time(
+        self,
+        entry: &Entry,
+    ) -> Option<u64> {
+        match self {
+            Self::At(time) => Some(time),
+            Self::Every(days) => entry
+                .modified
+                .map(|modified| modified.saturating_add(days.saturating_mul(SECONDS_PER_DAY))),
+        }
+    }
+}
+
+impl fmt::Display for Expiry {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::At(time) => write!(f, "{}", format_date(*time)),
+            Self::Every(days) => write!(f, "every {days}d"),
+        }
+    }
+}
+
+impl ExpiryStatus {
+    pub fn of(
+        entry: &Entry,
+        now: u64,
+    ) -> Self {
+        match entry.expiry.and_then(|expiry| expiry.time(entry)) {
+            None => Self::Valid,
+            Some(time) => {
+                if time <= now {
+                    Self::Expired
+                } else if time <= now + EXPIRY_WARNING_DAYS * SECONDS_PER_DAY {
+                    Self::Soon
+                } else {
+                    Self::Valid
+                }
+            }
+        }
+    }
+}
+
+/// Numbers of entries of a drawer needing a rotation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExpiryReport {
+    pub expired: usize,
+    pub soon: usize,
+}
+
+impl ExpiryReport {
+    pub fn of(
+        entries: &[Entry],
+        now: u64,
+    ) -> Self {
+        let mut report = Self::default();
+        for entry in entries {
+            match ExpiryStatus::of(entry, now) {
+                ExpiryStatus::Expired => report.expired += 1,
+                ExpiryStatus::Soon => report.soon += 1,
+                ExpiryStatus::Valid => {}
+            }
+        }
+        report
+    }
+}
+
+impl fmt::Display for ExpiryReport {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "{} expired entr{}, {} expiring in less than {} days",
+            self.expired,
+            if self.expired == 1 { "y" } else { "ies" },
+            self.soon,
+            EXPIRY_WARNING_DAYS,
+        )
+    }
+}
+
+#[test]
+fn test_expiry() {
+    let now = 1_000 * SECONDS_PER_DAY;
+    assert_eq!(Expiry::parse("90d"), Some(Expiry::Every(90)));
+    assert_eq!(Expiry::parse("every 2w"), Some(Expiry::Every(14)));
+    assert_eq!(
+        Expiry::parse("1970-01-11"),
+        Some(Expiry::At(10 * SECONDS_PER_DAY))
+    );
+    assert_eq!(Expiry::parse("0d"), None);
+    assert_eq!(Expiry::parse("soon"), None);
+    assert_eq!(Expiry::parse("9999999999999999999y"), None);
+    let parsed = Expiry::parse(&Expiry::Every(90).to_string());
+    assert_eq!(parsed, Some(Expiry::Every(90)));
+    let mut entry = Entry::new("key", "value");
+    assert_eq!(ExpiryStatus::of(&entry, now), ExpiryStatus::Valid);
+    entry.expiry = Some(Expiry::Every(90));
+    // the last change is unknown: it can't be told expired
+    assert_eq!(ExpiryStatus::of(&entry, now), ExpiryStatus::Valid);
+    entry.modified = Some(now - 100 * SECONDS_PER_DAY);
+    assert_eq!(ExpiryStatus::of(&entry, now), ExpiryStatus::Expired);
+    entry.modified = Some(now - 80 * SECONDS_PER_DAY);
+    assert_eq!(ExpiryStatus::of(&entry, now), ExpiryStatus::Soon);
+    entry.modified = Some(now - 10 * SECONDS_PER_DAY);
+    assert_eq!(ExpiryStatus::of(&entry, now), ExpiryStatus::Valid);
+    entry.expiry = Some(Expiry::Every(u64::MAX));
+    assert_eq!(ExpiryStatus::of(&entry, now), ExpiryStatus::Valid);
+    entry.expiry = Some(Expiry::At(now + 20 * SECONDS_PER_DAY));
+    assert_eq!(ExpiryStatus::of(&entry, now), ExpiryStatus::Valid);
+    let mut expired = Entry::new("old", "value");
+    expired.expiry = Some(Expiry::At(now - 1));
+    let report = ExpiryReport::of(&[entry, expired], now);
+    assert_eq!(
+        report,
+        ExpiryReport {
+            expired: 1,
+            soon: 0
+        }
+    );
+}
@@ -0,0 +1,250 @@
+use {
+    super::*,
+    aes_gcm_siv::{
+        Aes256GcmSiv,
+        Key,
+        KeyInit,
+    },
+    serde::{
+        Deserialize,
+        Serialize,
+    },
+    std::time::{
+        Duration,
+        Instant,
+    },
+};
+
+/// The smallest memory cost (in KiB) the calibration may choose
+const MIN_CALIBRATED_MEM_COST: u32 = 8 * 1024;
+
+/// The greatest memory cost (in KiB) of a closet which can be opened
+pub const MAX_MEM_COST: u32 = 2 * 1024 * 1024;
+
+/// The greatest number of passes of a closet which can be opened
+pub const MAX_TIME_COST: u32 = 100;
+
+/// The greatest degree of parallelism of a closet which can be opened
+pub const MAX_LANES: u32 = 16;
+
+/// The costs of the Argon2 derivation of a cipher key
+/// from a passphrase.
+///
+/// They're stored in the clear part of each closet, so that
+/// a closet file stays readable whatever the parameters used
+/// when writing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+    /// The amount of memory used, in KiB
+    pub mem_cost: u32,
+    /// The number of passes
+    pub time_cost: u32,
+    /// The degree of parallelism
+    pub lanes: u32,
+}
+
+/// The result of a calibration of the derivation costs
+/// for the current machine
+#[derive(Debug, Clone, Copy)]
+pub struct KdfCalibration {
+    pub params: KdfParams,
+    /// The measured duration of one derivation with the params
+    pub derivation_duration: Duration,
+}
+
+impl Default for KdfParams {
+    /// Return the parameters used before they were configurable,
+    /// which are thus the ones of closets written without them
+    fn default() -> Self {
+        Self {
+            mem_cost: 4096,
+            time_cost: 3,
+            lanes: 1,
+        }
+    }
+}
+
+impl KdfParams {
+    /// Return very low costs, for tests deriving many keys
+    #[cfg(test)]
+    pub fn for_tests() -> Self {
+        Self {
+            mem_cost: 64,
+            time_cost: 1,
+            lanes: 1,
+        }
+    }
+
+    /// Check the costs are in the bounds of the ones SafeCloset writes,
+    /// so that a corrupt or tampered file can't make a derivation take
+    /// all the memory or last forever
+    pub fn check(&self) -> Result<(), CoreError> {
+        if self.lanes < 1 || self.lanes > MAX_LANES {
+            return Err(CoreError::InvalidKdfParams(format!(
+                "{} lanes (expected 1 to {MAX_LANES})",
+                self.lanes
+            )));
+        }
+        if self.time_cost < 1 || self.time_cost > MAX_TIME_COST {
+            return Err(CoreError::InvalidKdfParams(format!(
+                "{} passes (expected 1 to {MAX_TIME_COST})",
+                self.time_cost
+            )));
+        }
+        if self.mem_cost < 8 * self.lanes || self.mem_cost > MAX_MEM_COST {
+            return Err(CoreError::InvalidKdfParams(format!(
+                "{} KiB of memory (expected {} to {MAX_MEM_COST})",
+                self.mem_cost,
+                8 * self.lanes,
+            )));
+        }
+        Ok(())
+    }
+
+    fn config(&self) -> argon2::Config<'static> {
+        argon2::Config {
+            ad: &[],
+            hash_length: 32,
+            lanes: self.lanes,
+            mem_cost: self.mem_cost,
+            secret: &[],
+            thread_mode: argon2::ThreadMode::default(),
+            time_cost: self.time_cost,
+            variant: argon2::Variant::Argon2i,
+            version: argon2::Version::Version13,
+        }
+    }
+
+    /// Derive the cipher from the password and the salt
+    pub fn cipher(
+        &self,
+        password: &[u8],
+        salt: &[u8],
+    ) -> Result<Aes256GcmSiv, CoreError> {
+        let hash = argon2::hash_raw(password, salt, &self.config())?;
+        let key = Key::<Aes256GcmSiv>::from_slice(&hash);
+        Ok(Aes256GcmSiv::new(key))
+    }
+
+    /// Measure the duration of one derivation with those params
+    pub fn measure(&self) -> Result<Duration, CoreError> {
+        let password = random_password();
+        let salt = random_password();
+        let start = Instant::now();
+        self.cipher(password.as_bytes(), salt.as_bytes())?;
+        Ok(start.elapsed())
+    }
+
+    /// Compute the parameters making one derivation last about the target
+    /// duration on this machine, using as much as max_mem_cost KiB.
+    ///
+    /// The memory cost is reduced only when a single pass with the maximal
+    /// memory would already be longer than the target.
+    pub fn calibrate(
+        target: Duration,
+        max_mem_cost: u32,
+    ) -> Result<KdfCalibration, CoreError> {
+        let mut params = Self {
+            mem_cost: max_mem_cost.clamp(MIN_CALIBRATED_MEM_COST, MAX_MEM_COST),
+            time_cost: 1,
+            lanes: 1,
+        };
+        let mut pass_duration = params.measure()?;
+        while pass_duration > target && params.mem_cost / 2 >= MIN_CALIBRATED_MEM_COST {
+            params.mem_cost /= 2;
+            pass_duration = params.measure()?;
+        }
+        let passes = target.as_secs_f64() / pass_duration.as_secs_f64().max(0.000_001);
+        params.time_cost = (passes.round() as u32).clamp(1, MAX_TIME_COST);
+        let derivation_duration = if params.time_cost == 1 {
+            pass_duration
+        } else {
+            params.measure()?
+        };
+        Ok(KdfCalibration {
+            params,
+            derivation_duration,
+        })
+    }
+}
+
+impl KdfCalibration {
+    /// Return the expected worst-case duration of a drawer opening
+    /// attempt when `open_drawers` drawers are already open.
+    ///
+    /// As the passphrase is tried from the deepest open drawer up to
+    /// the root closet, there's one derivation per level, or up to
+    /// MAX_PASSWORD_FORMS when the passphrase isn't ASCII.
+    pub fn worst_case_unlock_duration(
+        &self,
+        open_drawers: usize,
+        ascii_password: bool,
+    ) -> Duration {
+        let forms = if ascii_password {
+            1
+        } else {
+            MAX_PASSWORD_FORMS
+        };
+        self.derivation_duration * (open_drawers as u32 + 1) * forms
+    }
+}
+
+/// check the calibration gives usable parameters and that a closet
+/// written with them can be read again
+#[test]
+fn test_calibrated_closet() {
+    let calibration = KdfParams::calibrate(Duration::from_millis(20), 8 * 1024).unwrap();
+    let kdf = calibration.params;
+    assert!(kdf.time_cost >= 1);
+    assert_eq!(kdf.mem_cost, MIN_CALIBRATED_MEM_COST);
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("test-calibrated.closet");
+    let mut open_closet =
+        OpenCloset::create_with_params(path.to_path_buf(), kdf, PaddingPolicy::default()).unwrap();
+    let drawer = open_closet.create_drawer("calibrated").unwrap();
+    drawer.content.entries.push(Entry::new("k", "v"));
+    open_closet.close_and_save().unwrap();
+    let mut open_closet = OpenCloset::open(path.to_path_buf()).unwrap();
+    assert_eq!(open_closet.root_closet().kdf, kdf);
+    let drawer = open_closet.open_drawer("calibrated").unwrap();
+    assert_eq!(drawer.content.entries, vec![Entry::new("k", "v")]);
+    assert_eq!(drawer.content.closet.kdf, kdf);
+    temp_dir.close().unwrap();
+}
+
+/// check a closet file whose costs were changed to absurd values
+/// is refused before any derivation
+#[test]
+fn test_tampered_kdf_params() {
+    assert!(KdfParams::default().check().is_ok());
+    assert!(KdfParams::for_tests().check().is_ok());
+    let temp_dir = tempfile::tempdir().unwrap();
+    for (i, kdf) in [
+        KdfParams {
+            mem_cost: u32::MAX,
+            ..KdfParams::default()
+        },
+        KdfParams {
+            time_cost: u32::MAX,
+            ..KdfParams::default()
+        },
+        KdfParams {
+            lanes: 0,
+            ..KdfParams::default()
+        },
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        assert!(kdf.check().is_err());
+        // decoys don't need a derivation, so the closet can be built
+        let closet = Closet::new(0, kdf, PaddingPolicy::default()).unwrap();
+        let path = temp_dir.path().join(format!("test-tampered-{i}.closet"));
+        closet.write_to_file(&path).unwrap();
+        assert!(matches!(
+            Closet::from_file(&path),
+            Err(CoreError::InvalidKdfParams(_))
+        ));
+    }
+    temp_dir.close().unwrap();
+}
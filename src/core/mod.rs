@@ -1,29 +1,55 @@
+mod attachment;
+mod audit;
+mod breach_list;
 mod closed_drawer;
 mod closet;
 mod core_error;
+mod diceware;
 mod drawer_content;
 mod drawer_id;
 mod drawer_settings;
 mod entry;
+mod entry_field;
+mod expiry;
+mod kdf_params;
 mod open_closet;
 mod open_drawer;
+mod padding_policy;
+mod password_policy;
+mod password_strength;
 mod random;
+mod rekey_plan;
+mod relocation_target;
+mod timestamp;
+mod totp;
 
 pub use {
+    attachment::*,
+    audit::*,
+    breach_list::*,
     closed_drawer::*,
     closet::*,
     core_error::*,
+    diceware::*,
     drawer_content::*,
     drawer_id::*,
     drawer_settings::*,
     entry::*,
+    entry_field::*,
+    expiry::*,
+    kdf_params::*,
     open_closet::*,
     open_drawer::*,
+    padding_policy::*,
+    password_policy::*,
+    password_strength::*,
     random::*,
+    rekey_plan::*,
+    relocation_target::*,
+    timestamp::*,
+    totp::*,
 };
 
-pub const MIN_PASSWORD_LENGTH: usize = 2;
-
 /// test most opening, saving, reopening, etc. operations in
 /// a complex scenario
 #[test]
@@ -1,8 +1,11 @@
 use {
     super::*,
-    std::path::{
-        Path,
-        PathBuf,
+    std::{
+        fs,
+        path::{
+            Path,
+            PathBuf,
+        },
     },
 };
 
@@ -19,20 +22,12 @@ pub struct OpenCloset {
 
     // the closet was just created because there no preexisting file
     created: bool,
+
+    // the minimal strength score of new passphrases
+    min_password_score: u8,
 }
 
 impl OpenCloset {
-    /// Either create a new closet, or open an existing one, depending
-    /// on whether the file exists
-    pub fn open_or_create<P: Into<PathBuf>>(path: P) -> Result<Self, CoreError> {
-        let path = path.into();
-        if path.exists() {
-            Self::open(path)
-        } else {
-            Self::create(path)
-        }
-    }
-
     pub fn root_closet(&mut self) -> &mut Closet {
         &mut self.root_closet
     }
@@ -41,6 +36,19 @@ impl OpenCloset {
         self.created
     }
 
+    pub fn min_password_score(&self) -> u8 {
+        self.min_password_score
+    }
+
+    /// Set the minimal strength score (see `PasswordStrength`) of the
+    /// passphrases of new drawers and of changed passphrases
+    pub fn set_min_password_score(
+        &mut self,
+        min_password_score: u8,
+    ) {
+        self.min_password_score = min_password_score;
+    }
+
     #[cfg(test)]
     pub fn root_drawers_count(&self) -> usize {
         self.root_closet.drawers.len()
@@ -48,15 +56,33 @@ impl OpenCloset {
 
     /// Create a new closet, with a random number of drawers
     /// (which won't be openable as you won't have their password)
+    #[allow(dead_code)]
     pub fn create(path: PathBuf) -> Result<Self, CoreError> {
+        Self::create_with_params(path, KdfParams::default(), PaddingPolicy::default())
+    }
+
+    /// Create a new closet with the cheap derivation costs of tests
+    #[cfg(test)]
+    pub fn create_for_tests(path: PathBuf) -> Result<Self, CoreError> {
+        Self::create_with_params(path, KdfParams::for_tests(), PaddingPolicy::default())
+    }
+
+    /// Create a new closet whose keys are derived with the given costs
+    /// and whose drawers are padded according to the given policy
+    pub fn create_with_params(
+        path: PathBuf,
+        kdf: KdfParams,
+        padding: PaddingPolicy,
+    ) -> Result<Self, CoreError> {
         if path.exists() {
             return Err(CoreError::FileExists(path));
         }
         let open_closet = OpenCloset {
             path,
-            root_closet: Closet::new(0)?,
+            root_closet: Closet::new(0, kdf, padding)?,
             open_drawers: Vec::new(),
             created: true,
+            min_password_score: 0,
         };
         Ok(open_closet)
     }
@@ -69,6 +95,7 @@ impl OpenCloset {
             root_closet,
             open_drawers: Vec::new(),
             created: false,
+            min_password_score: 0,
         };
         Ok(open_closet)
     }
@@ -196,9 +223,10 @@ impl OpenCloset {
         password: S,
     ) -> Result<&mut OpenDrawer, CoreError> {
         let depth = self.depth();
-        let open_drawer = self
-            .deepest_closet_mut()
-            .create_drawer(depth, password.into())?;
+        let min_score = self.min_password_score;
+        let open_drawer =
+            self.deepest_closet_mut()
+                .create_drawer(depth, password.into(), min_score)?;
         self.open_drawers.push(open_drawer);
         Ok(&mut self.open_drawers[depth])
     }
@@ -211,9 +239,10 @@ impl OpenCloset {
         password: S,
     ) -> Result<OpenDrawer, CoreError> {
         let depth = self.depth();
-        let open_drawer = self
-            .deepest_closet_mut()
-            .create_drawer(depth, password.into())?;
+        let min_score = self.min_password_score;
+        let open_drawer =
+            self.deepest_closet_mut()
+                .create_drawer(depth, password.into(), min_score)?;
         Ok(open_drawer)
     }
 
@@ -235,6 +264,12 @@ impl OpenCloset {
         }
     }
 
+    /// Return the open drawers, from the top level one, except
+    /// the one which may have been taken
+    pub fn open_drawers(&self) -> &[OpenDrawer] {
+        &self.open_drawers
+    }
+
     #[allow(dead_code)]
     pub fn deepest_open_drawer(&mut self) -> Option<&mut OpenDrawer> {
         self.open_drawers.last_mut()
@@ -247,7 +282,15 @@ impl OpenCloset {
         self.open_drawers.pop()
     }
 
-    fn deepest_closet(&self) -> &Closet {
+    /// Return the mutable open drawers, from the top level one,
+    /// except the one which may have been taken
+    pub fn open_drawers_mut(&mut self) -> &mut [OpenDrawer] {
+        &mut self.open_drawers
+    }
+
+    /// Return the closet containing the drawers of the deepest
+    /// level, which is the one of a taken drawer
+    pub fn deepest_closet(&self) -> &Closet {
         let depth = self.open_drawers.len();
         if self.open_drawers.is_empty() {
             &self.root_closet
@@ -256,7 +299,7 @@ impl OpenCloset {
         }
     }
 
-    fn deepest_closet_mut(&mut self) -> &mut Closet {
+    pub fn deepest_closet_mut(&mut self) -> &mut Closet {
         let depth = self.open_drawers.len();
         if self.open_drawers.is_empty() {
             &mut self.root_closet
@@ -282,25 +325,156 @@ impl OpenCloset {
         }
     }
 
-    /// Delete a drawer in the in-memory closet.
+    /// Delete a taken drawer, and all the drawers it contains, from the
+    /// in-memory closet, replacing it with a decoy.
     ///
     /// The operation isn't saved on disk until the closet is saved.
-    ///
-    /// This isn't called yet in the UI because I'm not sure it's useful
-    /// enough to clutter the menu
-    #[allow(dead_code)]
     pub fn delete_drawer(
         &mut self,
-        open_drawer: OpenDrawer,
+        open_drawer: &OpenDrawer,
     ) -> Result<(), CoreError> {
-        let closet = self.deepest_closet_mut();
-        for (idx, drawer) in closet.drawers.iter().enumerate() {
-            if drawer.has_same_id(&open_drawer) {
-                closet.drawers.remove(idx);
-                return Ok(());
+        if self.deepest_closet_mut().delete_drawer(open_drawer)? {
+            Ok(())
+        } else {
+            Err(CoreError::InvalidDelete)
+        }
+    }
+
+    /// Write a copy of the drawer, with the deep drawers of the plan, as
+    /// the only real drawer of a new closet file, under a new passphrase.
+    ///
+    /// The new file is then reopened, and removed if the copy can't be
+    /// read back.
+    pub fn extract_drawer(
+        &self,
+        drawer: &OpenDrawer,
+        path: &Path,
+        password: &str,
+        deep_drawers: RekeyPlan,
+    ) -> Result<(), CoreError> {
+        PasswordStrength::check(password, self.min_password_score)?;
+        let (closet, id) = Closet::with_drawer_copy(
+            drawer,
+            password.to_string(),
+            deep_drawers,
+            self.root_closet.kdf,
+            self.root_closet.padding,
+        )?;
+        closet.write_to_file(path)?;
+        let reopened = Closet::from_file(path)
+            .ok()
+            .and_then(|closet| closet.open_drawer(0, password));
+        let ok = reopened.map_or(false, |copy| {
+            copy.content.id == id && copy.content.entries.len() == drawer.content.entries.len()
+        });
+        if !ok {
+            fs::remove_file(path)?;
+            return Err(CoreError::ExtractCheckFailed);
+        }
+        Ok(())
+    }
+
+    /// Check that the taken drawer can be relocated to the target:
+    /// the target must be another closet than the current one, and
+    /// the passphrase of the drawer must not open a drawer there.
+    pub fn check_relocation(
+        &self,
+        drawer: &OpenDrawer,
+        target: &RelocationTarget,
+    ) -> Result<(), CoreError> {
+        let parent_depth = self.open_drawers.len();
+        let closet = match target {
+            RelocationTarget::Root if parent_depth > 0 => &self.root_closet,
+            RelocationTarget::Upper(depth) if depth + 1 < parent_depth => {
+                &self.open_drawers[*depth].content.closet
+            }
+            RelocationTarget::Sibling(sibling) if !sibling.has_same_id(drawer) => {
+                &sibling.content.closet
+            }
+            _ => {
+                return Err(CoreError::InvalidRelocation);
+            }
+        };
+        if closet.is_password_taken(target.new_depth(), &drawer.password) {
+            return Err(CoreError::PasswordAlreadyUsed);
+        }
+        Ok(())
+    }
+
+    /// Move the taken drawer, with the drawers it contains, into
+    /// another closet, leaving a decoy in its old place, then save
+    /// the closet and return the drawer, reopened at its new place
+    /// then taken again.
+    ///
+    /// The relocated drawer gets a new id, so that its old and new
+    /// versions can't be linked. Its decoys stay in the old closet, with
+    /// the decoy taking its place, like for a deleted drawer, and it
+    /// adopts the unclaimed decoys of its new closet, if any.
+    ///
+    /// The relocation must have been verified with `check_relocation`.
+    pub fn relocate_drawer(
+        &mut self,
+        mut drawer: OpenDrawer,
+        target: RelocationTarget,
+    ) -> Result<OpenDrawer, CoreError> {
+        if !self.deepest_closet_mut().delete_drawer(&drawer)? {
+            return Err(CoreError::InvalidRelocation);
+        }
+        drawer.depth = target.new_depth();
+        match target {
+            RelocationTarget::Root => {
+                while !self.open_drawers.is_empty() {
+                    self.close_deepest_drawer()?;
+                }
+            }
+            RelocationTarget::Upper(depth) => {
+                while self.open_drawers.len() > depth + 1 {
+                    self.close_deepest_drawer()?;
+                }
             }
+            RelocationTarget::Sibling(sibling) => {
+                self.push_back(sibling)?;
+            }
+        }
+        drawer.content.id = DrawerId::new();
+        let closet = self.deepest_closet_mut();
+        drawer.content.sibling_decoys = closet.claim_decoys();
+        closet.add_drawer(&mut drawer)?;
+        closet.shuffle_drawers();
+        self.open_drawers.push(drawer);
+        self.save_then_reopen()?;
+        self.take_deepest_open_drawer()
+            .ok_or(CoreError::NoOpenDrawer)
+    }
+
+    /// Replace the whole closet with the result of the rekey plan,
+    /// then save it.
+    ///
+    /// Drawers must have been closed before.
+    pub fn rekey(
+        &mut self,
+        plan: RekeyPlan,
+    ) -> Result<(), CoreError> {
+        if !self.open_drawers.is_empty() {
+            return Err(CoreError::OperationOnlyPermittedWithNoOpenDrawer);
         }
-        Err(CoreError::InvalidDelete)
+        self.root_closet = plan.into_closet()?;
+        self.root_closet.save(&self.path)
+    }
+
+    /// Replace the whole closet with another version of it, for
+    /// example the result of a merge, then save it.
+    ///
+    /// Drawers must have been closed before.
+    pub fn replace_root_closet(
+        &mut self,
+        closet: Closet,
+    ) -> Result<(), CoreError> {
+        if !self.open_drawers.is_empty() {
+            return Err(CoreError::OperationOnlyPermittedWithNoOpenDrawer);
+        }
+        self.root_closet = closet;
+        self.root_closet.save(&self.path)
     }
 
     /// Give a new password to the drawer.
@@ -318,9 +492,7 @@ impl OpenCloset {
         if open_drawer.depth != self.depth() {
             return Err(CoreError::OperationOnlyPermittedAtMaxDepth);
         }
-        if new_password.len() < MIN_PASSWORD_LENGTH {
-            return Err(CoreError::PasswordTooShort);
-        }
+        PasswordStrength::check(&new_password, self.min_password_score)?;
         if self
             .deepest_closet()
             .is_password_taken(open_drawer.depth, &new_password)
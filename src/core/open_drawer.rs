@@ -1,6 +1,9 @@
 use {
     super::*,
-    aes_gcm_siv::aead::Aead,
+    aes_gcm_siv::{
+        Aes256GcmSiv,
+        aead::Aead,
+    },
 };
 
 /// An open uncrypted drawer, with its content and the pass
@@ -30,14 +33,38 @@ impl OpenDrawer {
         }
     }
 
+    /// Tell whether the given passphrase is the one of the drawer,
+    /// whatever its unicode normalization
+    pub fn has_password(
+        &self,
+        password: &str,
+    ) -> bool {
+        normalize_password(&self.password) == normalize_password(password)
+    }
+
     /// Change the drawer_content into a closed_drawer
+    ///
+    /// The closet of the drawer gets the padding policy of the
+    /// closet containing the drawer.
     pub(crate) fn close(
         &mut self,
         closet: &Closet,
     ) -> Result<ClosedDrawer, CoreError> {
         let cipher = closet.cipher(&self.password)?;
-        self.content.add_noise();
-        let serialized_content = rmp_serde::encode::to_vec_named(&self.content)?;
+        self.content.closet.padding = closet.padding;
+        self.close_with_cipher(&cipher, closet.padding, 0)
+    }
+
+    /// Change the drawer_content into a closed_drawer, encrypted
+    /// with the given cipher, and padded to at least `min_size` bytes
+    /// if the padding policy uses buckets
+    pub(crate) fn close_with_cipher(
+        &mut self,
+        cipher: &Aes256GcmSiv,
+        padding: PaddingPolicy,
+        min_size: usize,
+    ) -> Result<ClosedDrawer, CoreError> {
+        let serialized_content = self.content.serialize_with_noise(padding, min_size)?;
         let nonce = random_nonce();
         let crypted_content = cipher
             .encrypt(&nonce, &*serialized_content)
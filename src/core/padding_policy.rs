@@ -0,0 +1,161 @@
+use {
+    rand::{
+        Rng,
+        rng,
+    },
+    serde::{
+        Deserialize,
+        Serialize,
+    },
+    std::{
+        fmt,
+        str::FromStr,
+    },
+};
+
+/// The smallest bucket of the power of two policy, in bytes
+pub const MIN_POW2_BUCKET_SIZE: usize = 1024;
+
+/// The smallest bucket size accepted for the fixed size policy, in bytes
+pub const MIN_FIXED_BUCKET_SIZE: u32 = 256;
+
+/// How the serialized content of drawers is padded before
+/// encryption, so that the size of a crypted drawer doesn't
+/// tell how much it contains.
+///
+/// The policy is stored in the clear part of each closet. Drawers
+/// of a closet are padded according to the closet's policy, and
+/// deeper closets get the policy of their parent when saved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PaddingPolicy {
+    /// Between 5 and 5000 bytes of random garbage, as was done
+    /// before the policy was configurable
+    Random,
+    /// Padding to the next power of two, with a minimum of
+    /// MIN_POW2_BUCKET_SIZE bytes
+    #[default]
+    PowerOfTwo,
+    /// Padding to the next multiple of the given size, in bytes
+    Fixed(u32),
+}
+
+impl PaddingPolicy {
+    /// Return the policy of the closets written before the policy
+    /// was configurable, which is the one of files without it
+    pub fn legacy() -> Self {
+        Self::Random
+    }
+
+    /// Return the size, in bytes, of the smallest bucket containing
+    /// the given size, or None when the policy doesn't use buckets
+    pub fn bucket_size(
+        self,
+        size: usize,
+    ) -> Option<usize> {
+        match self {
+            Self::Random => None,
+            Self::PowerOfTwo => Some(size.next_power_of_two().max(MIN_POW2_BUCKET_SIZE)),
+            Self::Fixed(n) => {
+                let n = n.max(MIN_FIXED_BUCKET_SIZE) as usize;
+                Some(((size + n - 1) / n).max(1) * n)
+            }
+        }
+    }
+
+    /// Return a random minimal size for a new decoy drawer, so
+    /// that decoys fill not only the smallest bucket
+    pub fn random_decoy_size(self) -> usize {
+        let mut k = 0;
+        while k < 4 && rng().random_bool(0.3) {
+            k += 1;
+        }
+        match self {
+            Self::Random => 0,
+            Self::PowerOfTwo => MIN_POW2_BUCKET_SIZE << k,
+            Self::Fixed(n) => n.max(MIN_FIXED_BUCKET_SIZE) as usize * (k + 1),
+        }
+    }
+}
+
+impl fmt::Display for PaddingPolicy {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::Random => write!(f, "random (5 to 5000 bytes of garbage)"),
+            Self::PowerOfTwo => write!(
+                f,
+                "power of two buckets (at least {MIN_POW2_BUCKET_SIZE} bytes)"
+            ),
+            Self::Fixed(n) => write!(f, "buckets of {n} bytes"),
+        }
+    }
+}
+
+impl FromStr for PaddingPolicy {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "random" => Ok(Self::Random),
+            "pow2" => Ok(Self::PowerOfTwo),
+            _ => match s.parse::<u32>() {
+                Ok(n) if n >= MIN_FIXED_BUCKET_SIZE => Ok(Self::Fixed(n)),
+                _ => Err(format!(
+                    "padding must be 'random', 'pow2', or a bucket size of at least {MIN_FIXED_BUCKET_SIZE} bytes"
+                )),
+            },
+        }
+    }
+}
+
+/// check that, with a bucket policy, all the top level drawers of a
+/// saved closet, decoys or not, have exactly the size of a bucket,
+/// and that the decoys created along a big drawer aren't smaller
+/// (they may be bigger as they contain their own random decoys)
+#[test]
+fn test_padding_buckets() {
+    use super::*;
+    let kdf = KdfParams::for_tests();
+    let temp_dir = tempfile::tempdir().unwrap();
+    for (i, padding) in [PaddingPolicy::PowerOfTwo, PaddingPolicy::Fixed(3000)]
+        .into_iter()
+        .enumerate()
+    {
+        let path = temp_dir.path().join(format!("test-padding-{i}.closet"));
+        let mut open_closet =
+            OpenCloset::create_with_params(path.to_path_buf(), kdf, padding).unwrap();
+        let drawer = open_closet.create_drawer("small drawer").unwrap();
+        drawer.content.entries.push(Entry::new("key", "value"));
+        open_closet.close_deepest_drawer().unwrap();
+        let drawer = open_closet.create_drawer("big drawer").unwrap();
+        for j in 0..500 {
+            drawer
+                .content
+                .entries
+                .push(Entry::new(format!("key {j}"), "a somewhat long value"));
+        }
+        open_closet.close_and_save().unwrap();
+        let closet = Closet::from_file(&path).unwrap();
+        assert_eq!(closet.padding, padding);
+        for drawer in &closet.drawers {
+            let size = drawer.clear_size();
+            assert_eq!(padding.bucket_size(size), Some(size));
+        }
+        let mut open_closet = OpenCloset::open(path.to_path_buf()).unwrap();
+        let drawer = open_closet.open_drawer("big drawer").unwrap();
+        assert_eq!(drawer.content.entries.len(), 500);
+        let big_size = drawer
+            .content
+            .serialize_with_noise(padding, 0)
+            .unwrap()
+            .len();
+        let not_smaller = closet
+            .drawers
+            .iter()
+            .filter(|d| d.clear_size() >= big_size)
+            .count();
+        assert!(not_smaller >= 3);
+    }
+    temp_dir.close().unwrap();
+}
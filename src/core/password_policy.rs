@@ -0,0 +1,227 @@
+use {
+    super::*,
+    rand::{
+        Rng,
+        rng,
+        seq::SliceRandom,
+    },
+    serde::{
+        Deserialize,
+        Serialize,
+    },
+    std::{
+        fmt,
+        str::FromStr,
+    },
+};
+
+const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &str = "0123456789";
+const SYMBOLS: &str = "!#$%&()*+,-./:;<=>?@[]^_{|}~\"'";
+
+/// Characters easily confused with other ones
+const AMBIGUOUS: &str = "0O1lI|\"'";
+
+pub const MAX_GENERATED_PASSWORD_LENGTH: usize = 256;
+
+/// The rules for generating a password, for example
+/// to comply with the requirements of a site
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PasswordPolicy {
+    /// The name under which the policy is saved in the drawer settings
+    pub name: String,
+    pub length: usize,
+    pub lowercase: bool,
+    pub uppercase: bool,
+    pub digits: bool,
+    pub symbols: bool,
+    /// Whether to exclude the characters easily confused
+    pub exclude_ambiguous: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            name: "default".to_string(),
+            length: 20,
+            lowercase: true,
+            uppercase: true,
+            digits: true,
+            symbols: true,
+            exclude_ambiguous: true,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// Return the character classes allowed by the policy
+    fn classes(&self) -> Vec<Vec<char>> {
+        [
+            (self.lowercase, LOWERCASE),
+            (self.uppercase, UPPERCASE),
+            (self.digits, DIGITS),
+            (self.symbols, SYMBOLS),
+        ]
+        .into_iter()
+        .filter(|(allowed, _)| *allowed)
+        .map(|(_, chars)| {
+            chars
+                .chars()
+                .filter(|c| !(self.exclude_ambiguous && AMBIGUOUS.contains(*c)))
+                .collect()
+        })
+        .collect()
+    }
+
+    /// Return the estimated entropy, in bits, of a password
+    /// generated with this policy
+    pub fn entropy_bits(&self) -> f64 {
+        let charset_len: usize = self.classes().iter().map(|class| class.len()).sum();
+        if charset_len == 0 {
+            0.0
+        } else {
+            self.length as f64 * (charset_len as f64).log2()
+        }
+    }
+
+    /// Generate a password with at least one character
+    /// of each allowed class
+    pub fn generate(&self) -> Result<String, CoreError> {
+        let classes = self.classes();
+        if classes.is_empty() {
+            return Err(CoreError::InvalidPasswordPolicy(
+                "no character class allowed".to_string(),
+            ));
+        }
+        if self.length < classes.len() || self.length > MAX_GENERATED_PASSWORD_LENGTH {
+            return Err(CoreError::InvalidPasswordPolicy(format!(
+                "length must be between {} and {MAX_GENERATED_PASSWORD_LENGTH}",
+                classes.len(),
+            )));
+        }
+        let mut rng = rng();
+        let charset: Vec<char> = classes.iter().flatten().copied().collect();
+        let mut chars: Vec<char> = classes
+            .iter()
+            .map(|class| class[rng.random_range(0..class.len())])
+            .collect();
+        while chars.len() < self.length {
+            chars.push(charset[rng.random_range(0..charset.len())]);
+        }
+        chars.shuffle(&mut rng);
+        Ok(chars.into_iter().collect())
+    }
+}
+
+/// Write the rules of the policy (not its name) in the
+/// format accepted by `from_str`, e.g. "16,lower,upper,digits"
+impl fmt::Display for PasswordPolicy {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{}", self.length)?;
+        for (allowed, name) in [
+            (self.lowercase, "lower"),
+            (self.uppercase, "upper"),
+            (self.digits, "digits"),
+            (self.symbols, "symbols"),
+        ] {
+            if allowed {
+                write!(f, ",{name}")?;
+            }
+        }
+        if !self.exclude_ambiguous {
+            write!(f, ",ambiguous")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse a comma separated list of rules: a length, and the allowed
+/// classes among "lower", "upper", "digits", "symbols". When no class
+/// is given, all are allowed, and "no-lower", "no-upper", "no-digits"
+/// or "no-symbols" remove one. Ambiguous characters are excluded unless
+/// "ambiguous" is given.
+impl FromStr for PasswordPolicy {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut policy = Self {
+            name: String::new(),
+            ..Default::default()
+        };
+        let tokens: Vec<&str> = s
+            .split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .collect();
+        let lists_classes = tokens
+            .iter()
+            .any(|t| matches!(*t, "lower" | "upper" | "digits" | "symbols"));
+        if lists_classes {
+            policy.lowercase = false;
+            policy.uppercase = false;
+            policy.digits = false;
+            policy.symbols = false;
+        }
+        for token in tokens {
+            match token {
+                "lower" => policy.lowercase = true,
+                "upper" => policy.uppercase = true,
+                "digits" => policy.digits = true,
+                "symbols" => policy.symbols = true,
+                "no-lower" => policy.lowercase = false,
+                "no-upper" => policy.uppercase = false,
+                "no-digits" => policy.digits = false,
+                "no-symbols" => policy.symbols = false,
+                "ambiguous" => policy.exclude_ambiguous = false,
+                "no-ambiguous" => policy.exclude_ambiguous = true,
+                _ => {
+                    policy.length = token
+                        .parse()
+                        .map_err(|_| format!("unexpected password rule: {token:?}"))?;
+                }
+            }
+        }
+        Ok(policy)
+    }
+}
+
+/// check the generated passwords follow the policy
+#[test]
+fn test_password_policy() {
+    let policy: PasswordPolicy = "16,no-symbols".parse().unwrap();
+    assert_eq!(policy.length, 16);
+    assert!(!policy.symbols);
+    assert_eq!(policy.to_string(), "16,lower,upper,digits");
+    assert_eq!(
+        policy.to_string().parse::<PasswordPolicy>().unwrap(),
+        policy
+    );
+    for _ in 0..100 {
+        let password = policy.generate().unwrap();
+        assert_eq!(password.chars().count(), 16);
+        assert!(password.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert!(password.chars().any(|c| c.is_ascii_lowercase()));
+        assert!(password.chars().any(|c| c.is_ascii_uppercase()));
+        assert!(password.chars().any(|c| c.is_ascii_digit()));
+        assert!(!password.chars().any(|c| AMBIGUOUS.contains(c)));
+    }
+    let policy: PasswordPolicy = "digits,6".parse().unwrap();
+    assert!(
+        policy
+            .generate()
+            .unwrap()
+            .chars()
+            .all(|c| c.is_ascii_digit())
+    );
+    assert!(
+        "digits,no-digits"
+            .parse::<PasswordPolicy>()
+            .unwrap()
+            .generate()
+            .is_err()
+    );
+}
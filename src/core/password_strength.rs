@@ -0,0 +1,327 @@
+use {
+    super::*,
+    once_cell::sync::Lazy,
+    std::{
+        collections::HashSet,
+        fmt,
+    },
+};
+
+/// Passwords so common they're among the first tried by an attacker,
+/// most common first
+const COMMON_PASSWORDS: &[&str] = &[
+    "123456",
+    "password",
+    "12345678",
+    "qwerty",
+    "123456789",
+    "12345",
+    "1234",
+    "111111",
+    "1234567",
+    "dragon",
+    "123123",
+    "baseball",
+    "abc123",
+    "football",
+    "monkey",
+    "letmein",
+    "696969",
+    "shadow",
+    "master",
+    "666666",
+    "qwertyuiop",
+    "123321",
+    "mustang",
+    "1234567890",
+    "michael",
+    "654321",
+    "superman",
+    "1qaz2wsx",
+    "7777777",
+    "121212",
+    "000000",
+    "qazwsx",
+    "123qwe",
+    "killer",
+    "trustno1",
+    "jordan",
+    "jennifer",
+    "zxcvbnm",
+    "asdfgh",
+    "hunter",
+    "buster",
+    "soccer",
+    "harley",
+    "batman",
+    "andrew",
+    "tigger",
+    "sunshine",
+    "iloveyou",
+    "charlie",
+    "robert",
+    "thomas",
+    "hockey",
+    "ranger",
+    "daniel",
+    "starwars",
+    "klaster",
+    "112233",
+    "george",
+    "computer",
+    "michelle",
+    "jessica",
+    "pepper",
+    "zaq1zaq1",
+    "summer",
+    "ashley",
+    "azerty",
+    "welcome",
+    "admin",
+    "secret",
+    "princess",
+    "passw0rd",
+    "motdepasse",
+    "soleil",
+    "doudou",
+    "loulou",
+    "chouchou",
+    "nicolas",
+    "marseille",
+    "bonjour",
+    "changeme",
+];
+
+/// Rows of the most common keyboard layouts, in which runs of
+/// adjacent keys are easy to guess
+const KEYBOARD_ROWS: &[&str] = &[
+    "`1234567890-=",
+    "qwertyuiop[]\\",
+    "asdfghjkl;'",
+    "zxcvbnm,./",
+    "azertyuiop",
+    "qsdfghjklm",
+    "wxcvbn,;:!",
+    "qwertzuiop",
+    "yxcvbnm",
+];
+
+/// Common substitutions of letters by digits or symbols
+const LEET_SUBSTITUTIONS: &[(char, char)] = &[
+    ('0', 'o'),
+    ('1', 'i'),
+    ('3', 'e'),
+    ('4', 'a'),
+    ('5', 's'),
+    ('7', 't'),
+    ('8', 'b'),
+    ('@', 'a'),
+    ('$', 's'),
+    ('!', 'i'),
+    ('|', 'l'),
+];
+
+/// The words of all the diceware lists, a passphrase made of such words
+/// being only as strong as their number allows
+static DICTIONARY: Lazy<HashSet<&'static str>> = Lazy::new(|| {
+    DicewareLanguage::ALL
+        .iter()
+        .flat_map(|language| language.words())
+        .collect()
+});
+
+/// Patterns longer than this aren't searched (a longer run
+/// is found as several patterns)
+const MAX_PATTERN_LENGTH: usize = 32;
+
+/// The minimal number of bits (log2 of the guesses) of each score,
+/// following the thresholds of zxcvbn (10³, 10⁶, 10⁸ and 10¹⁰ guesses)
+const SCORE_BITS: [f64; 4] = [10.0, 20.0, 26.6, 33.2];
+
+pub const MAX_PASSWORD_SCORE: u8 = 4;
+
+/// The estimated strength of a passphrase, in the manner of zxcvbn: the
+/// passphrase is split into the most guessable patterns (common passwords,
+/// dictionary words, sequences, repeats, keyboard runs, years) and the
+/// remaining characters are counted as brute-forced
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PasswordStrength {
+    /// log2 of the estimated number of guesses needed to find the passphrase
+    pub bits: f64,
+}
+
+impl PasswordStrength {
+    pub fn estimate(password: &str) -> Self {
+        let chars: Vec<char> = password.chars().collect();
+        // best[j] is the minimal cost, in bits, of the first j chars
+        let mut best = vec![0.0; chars.len() + 1];
+        for j in 1..=chars.len() {
+            best[j] = best[j - 1] + bruteforce_bits(chars[j - 1]);
+            for i in j.saturating_sub(MAX_PATTERN_LENGTH)..j {
+                if let Some(bits) = pattern_bits(&chars[i..j]) {
+                    if best[i] + bits < best[j] {
+                        best[j] = best[i] + bits;
+                    }
+                }
+            }
+        }
+        Self {
+            bits: best[chars.len()],
+        }
+    }
+    /// Return a score between 0 (very weak) and MAX_PASSWORD_SCORE (very strong)
+    pub fn score(self) -> u8 {
+        SCORE_BITS.iter().filter(|&&bits| self.bits >= bits).count() as u8
+    }
+    pub fn score_label(score: u8) -> &'static str {
+        match score {
+            0 => "very weak",
+            1 => "weak",
+            2 => "fair",
+            3 => "strong",
+            _ => "very strong",
+        }
+    }
+    pub fn label(self) -> &'static str {
+        Self::score_label(self.score())
+    }
+    /// Check the passphrase isn't empty and reaches the given score
+    pub fn check(
+        password: &str,
+        min_score: u8,
+    ) -> Result<(), CoreError> {
+        if password.is_empty() {
+            return Err(CoreError::PasswordTooWeak("empty"));
+        }
+        let strength = Self::estimate(password);
+        if strength.score() < min_score {
+            return Err(CoreError::PasswordTooWeak(strength.label()));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for PasswordStrength {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{} ({:.0} bits)", self.label(), self.bits)
+    }
+}
+
+/// Return the number of bits needed to brute-force a char,
+/// depending on its class
+fn bruteforce_bits(c: char) -> f64 {
+    let cardinality: f64 = if c.is_ascii_lowercase() || c.is_ascii_uppercase() {
+        26.0
+    } else if c.is_ascii_digit() {
+        10.0
+    } else if c.is_ascii() {
+        33.0
+    } else {
+        100.0
+    };
+    cardinality.log2()
+}
+
+/// Return the cost, in bits, of the cheapest pattern matching
+/// the whole slice, if any
+fn pattern_bits(chars: &[char]) -> Option<f64> {
+    let len = chars.len();
+    if len < 3 {
+        return None;
+    }
+    let len_bits = (len as f64).log2();
+    let mut candidates = Vec::new();
+    // repeat of the same char
+    if chars.iter().all(|&c| c == chars[0]) {
+        candidates.push(bruteforce_bits(chars[0]) + len_bits);
+    }
+    // alphabetical or numerical sequence, ascending or descending
+    if chars.iter().all(|c| c.is_ascii_alphanumeric()) {
+        let delta = chars[1] as i32 - chars[0] as i32;
+        if (delta == 1 || delta == -1)
+            && chars.windows(2).all(|w| w[1] as i32 - w[0] as i32 == delta)
+        {
+            let start_bits = if chars[0].is_ascii_digit() {
+                10f64
+            } else {
+                26f64
+            };
+            candidates.push(start_bits.log2() + len_bits + 1.0);
+        }
+    }
+    let lower: String = chars.iter().map(|c| c.to_ascii_lowercase()).collect();
+    // run of adjacent keys
+    let reversed: String = lower.chars().rev().collect();
+    if KEYBOARD_ROWS.iter().any(|row| row.contains(&lower)) {
+        candidates.push(47f64.log2() + len_bits);
+    } else if KEYBOARD_ROWS.iter().any(|row| row.contains(&reversed)) {
+        candidates.push(47f64.log2() + len_bits + 1.0);
+    }
+    // year
+    if len == 4 {
+        if let Ok(year) = lower.parse::<u32>() {
+            if (1900..2100).contains(&year) {
+                candidates.push(200f64.log2());
+            }
+        }
+    }
+    // common password or dictionary word, maybe capitalized or "l33t"
+    let mut substitutions = 0;
+    let unleeted: String = lower
+        .chars()
+        .map(
+            |c| match LEET_SUBSTITUTIONS.iter().find(|(leet, _)| *leet == c) {
+                Some((_, letter)) => {
+                    substitutions += 1;
+                    *letter
+                }
+                None => c,
+            },
+        )
+        .collect();
+    let variation_bits = case_variation_bits(chars) + substitutions as f64;
+    for (word, extra_bits) in [(&lower, 0.0), (&unleeted, substitutions as f64)] {
+        if let Some(rank) = COMMON_PASSWORDS.iter().position(|p| *p == word.as_str()) {
+            candidates.push(((rank + 2) as f64).log2() + case_variation_bits(chars) + extra_bits);
+        }
+    }
+    if DICTIONARY.contains(unleeted.as_str()) {
+        candidates.push((DICTIONARY.len() as f64).log2() + variation_bits);
+    }
+    candidates.into_iter().reduce(f64::min)
+}
+
+/// Return the bits added by the use of uppercase letters in a word
+fn case_variation_bits(chars: &[char]) -> f64 {
+    let upper = chars.iter().filter(|c| c.is_ascii_uppercase()).count();
+    let lower = chars.iter().filter(|c| c.is_ascii_lowercase()).count();
+    if upper == 0 {
+        0.0
+    } else if lower == 0 || (upper == 1 && chars[0].is_ascii_uppercase()) {
+        1.0
+    } else {
+        upper.min(lower) as f64 * ((upper + lower) as f64).log2()
+    }
+}
+
+/// check the estimation gives the expected scores on a few typical passphrases
+#[test]
+fn test_password_strength() {
+    let score = |password: &str| PasswordStrength::estimate(password).score();
+    assert_eq!(score(""), 0);
+    assert_eq!(score("password"), 0);
+    assert_eq!(score("P@ssw0rd"), 0);
+    assert_eq!(score("qwerty123"), 0);
+    assert_eq!(score("aaaaaaaaaaaa"), 0);
+    assert!(score("Dragon1990") <= 1);
+    assert_eq!(score("Lemon"), 1);
+    assert!(score("Ty7#pq2!Lm9z") >= 3);
+    assert_eq!(score("barrel-lemon-quartz-tulip-oyster"), 4);
+    assert_eq!(score("les sanglots lents des violons de l'automne"), 4);
+    assert!(PasswordStrength::check("", 0).is_err());
+    assert!(PasswordStrength::check("a", 0).is_ok());
+    assert!(PasswordStrength::check("password", 1).is_err());
+}
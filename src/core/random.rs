@@ -1,5 +1,10 @@
 use {
-    aes_gcm_siv::Nonce,
+    aes_gcm_siv::{
+        Aes256GcmSiv,
+        Key,
+        KeyInit,
+        Nonce,
+    },
     rand::{
         Rng,
         RngCore,
@@ -19,6 +24,16 @@ pub fn random_bytes(count: usize) -> Box<[u8]> {
     vec.into_boxed_slice()
 }
 
+/// Generate an array of random bytes all lower than 128, which
+/// makes each of them take exactly one byte in MessagePack
+pub fn random_fixint_bytes(count: usize) -> Box<[u8]> {
+    let mut bytes = random_bytes(count);
+    for b in bytes.iter_mut() {
+        *b &= 0x7F;
+    }
+    bytes
+}
+
 /// Generate a random length array of random bytes.
 ///
 /// min_size and max_size are both included.
@@ -36,6 +51,13 @@ pub fn random_nonce() -> Nonce {
     nonce
 }
 
+/// Build a cipher with a random key, for content nobody
+/// should be able to decrypt
+pub fn random_cipher() -> Aes256GcmSiv {
+    let key = random_bytes(32);
+    Aes256GcmSiv::new(Key::<Aes256GcmSiv>::from_slice(&key))
+}
+
 pub fn random_password() -> String {
     let mut rng = rng();
     (0..rng.random_range(30..80))
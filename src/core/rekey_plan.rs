@@ -0,0 +1,311 @@
+use super::*;
+
+/// What a rekey (a full re-encryption of the closet with fresh
+/// salts and nonces) would do, given the passphrases the user typed.
+///
+/// Only the drawers which can be opened with those passphrases can
+/// be re-encrypted. All the other ones, decoys or not, are lost and
+/// replaced with new decoys.
+pub struct RekeyPlan {
+    root: RekeyLevel,
+    /// whether the past values of the entries are dropped
+    purge_histories: bool,
+}
+
+/// A closet, as seen with the passphrases of the rekey
+struct RekeyLevel {
+    depth: usize,
+    comments: String,
+    kdf: KdfParams,
+    padding: PaddingPolicy,
+    known_drawers: Vec<RekeyDrawer>,
+    unknown_drawers_count: usize,
+}
+
+/// A drawer the user can open
+struct RekeyDrawer {
+    drawer: OpenDrawer,
+    /// index of the passphrase opening the drawer
+    password_idx: usize,
+    /// the content of the drawer's closet
+    sub_level: RekeyLevel,
+}
+
+impl RekeyLevel {
+    fn new(
+        closet: &Closet,
+        depth: usize,
+        passwords: &[String],
+    ) -> Self {
+        let mut known_drawers: Vec<RekeyDrawer> = Vec::new();
+        for (password_idx, password) in passwords.iter().enumerate() {
+            let Some(drawer) = closet.open_drawer(depth, password) else {
+                continue;
+            };
+            if known_drawers
+                .iter()
+                .any(|kd| kd.drawer.has_same_id(&drawer))
+            {
+                continue;
+            }
+            let sub_level = RekeyLevel::new(&drawer.content.closet, depth + 1, passwords);
+            known_drawers.push(RekeyDrawer {
+                drawer,
+                password_idx,
+                sub_level,
+            });
+        }
+        Self {
+            depth,
+            comments: closet.comments.clone(),
+            kdf: closet.kdf,
+            padding: closet.padding,
+            unknown_drawers_count: closet.drawers.len() - known_drawers.len(),
+            known_drawers,
+        }
+    }
+    /// Try the last of the passphrases at this level and at the levels
+    /// of the known drawers, and return whether it opened a new drawer.
+    ///
+    /// Only the new passphrase is derived, except in the closet of a newly
+    /// opened drawer, where all passphrases are tried.
+    fn add_password(
+        &mut self,
+        closet: &Closet,
+        passwords: &[String],
+    ) -> bool {
+        let Some(password) = passwords.last() else {
+            return false;
+        };
+        let mut added = false;
+        for kd in &mut self.known_drawers {
+            added |= kd
+                .sub_level
+                .add_password(&kd.drawer.content.closet, passwords);
+        }
+        if let Some(drawer) = closet.open_drawer(self.depth, password) {
+            if !self
+                .known_drawers
+                .iter()
+                .any(|kd| kd.drawer.has_same_id(&drawer))
+            {
+                let sub_level = RekeyLevel::new(&drawer.content.closet, self.depth + 1, passwords);
+                self.known_drawers.push(RekeyDrawer {
+                    drawer,
+                    password_idx: passwords.len() - 1,
+                    sub_level,
+                });
+                self.unknown_drawers_count = self.unknown_drawers_count.saturating_sub(1);
+                added = true;
+            }
+        }
+        added
+    }
+    fn known_drawers_count(&self) -> usize {
+        self.known_drawers
+            .iter()
+            .map(|kd| 1 + kd.sub_level.known_drawers_count())
+            .sum()
+    }
+    fn unknown_drawers_count(&self) -> usize {
+        self.unknown_drawers_count
+            + self
+                .known_drawers
+                .iter()
+                .map(|kd| kd.sub_level.unknown_drawers_count())
+                .sum::<usize>()
+    }
+    /// Add to the report one line per level with drawers which can't be opened
+    fn describe_losses(
+        &self,
+        location: &str,
+        lines: &mut Vec<String>,
+    ) {
+        if self.unknown_drawers_count > 0 {
+            lines.push(format!(
+                "* {}: {} drawer{}",
+                location,
+                self.unknown_drawers_count,
+                if self.unknown_drawers_count > 1 {
+                    "s"
+                } else {
+                    ""
+                },
+            ));
+        }
+        for kd in &self.known_drawers {
+            let location = if self.depth == 0 {
+                format!("in drawer of passphrase {}", kd.password_idx + 1)
+            } else {
+                format!("{location} › {}", kd.password_idx + 1)
+            };
+            kd.sub_level.describe_losses(&location, lines);
+        }
+    }
+    /// Build a new closet with a fresh salt and new decoys, containing
+    /// the known drawers, re-encrypted with new ids
+    fn into_closet(
+        self,
+        purge_histories: bool,
+    ) -> Result<Closet, CoreError> {
+        let mut closet = Closet::new(self.depth, self.kdf, self.padding)?;
+        closet.comments = self.comments;
+        // the fresh decoys are known by all the drawers of the level
+        let decoys = closet.claim_decoys();
+        for kd in self.known_drawers {
+            let RekeyDrawer {
+                mut drawer,
+                sub_level,
+                ..
+            } = kd;
+            drawer.content.closet = sub_level.into_closet(purge_histories)?;
+            if purge_histories {
+                drawer.content.truncate_histories(0);
+            }
+            drawer.content.id = DrawerId::new();
+            drawer.content.sibling_decoys = decoys.clone();
+            closet.add_drawer(&mut drawer)?;
+        }
+        closet.shuffle_drawers();
+        Ok(closet)
+    }
+}
+
+impl RekeyPlan {
+    /// Try to open all drawers, at all depths, with the given passphrases
+    pub fn new(
+        closet: &Closet,
+        passwords: &[String],
+    ) -> Self {
+        Self {
+            root: RekeyLevel::new(closet, 0, passwords),
+            purge_histories: false,
+        }
+    }
+    /// Start a plan rebuilding the closet of a drawer copied at the
+    /// top level of a new closet, with the deep drawers whose
+    /// passphrases are added, purged of their past values
+    pub fn for_drawer_copy(closet: &Closet) -> Self {
+        Self {
+            root: RekeyLevel::new(closet, 1, &[]),
+            purge_histories: true,
+        }
+    }
+    /// Take into account a new passphrase, the last of the given ones
+    /// which must be the ones the plan was built with, followed by the
+    /// new one.
+    ///
+    /// Return whether the passphrase opens a drawer which wasn't known.
+    pub fn add_password(
+        &mut self,
+        closet: &Closet,
+        passwords: &[String],
+    ) -> bool {
+        self.root.add_password(closet, passwords)
+    }
+    /// Return the number of drawers which will be re-encrypted
+    pub fn known_drawers_count(&self) -> usize {
+        self.root.known_drawers_count()
+    }
+    /// Return the number of drawers which will be lost
+    /// (and replaced with new decoys)
+    pub fn unknown_drawers_count(&self) -> usize {
+        self.root.unknown_drawers_count()
+    }
+    pub fn confirm_string(&self) -> String {
+        let mut lines = vec![format!(
+            "{} drawers will be re-encrypted with fresh salts and nonces.",
+            self.known_drawers_count(),
+        )];
+        if self.unknown_drawers_count() > 0 {
+            lines.push(
+                "The following drawers can't be opened with the typed passphrases. \
+                Decoys or not, they'll be **unrecoverable** and replaced with new decoys:"
+                    .to_string(),
+            );
+            self.root.describe_losses("top level", &mut lines);
+        }
+        lines.join("\n")
+    }
+    /// Build the new root closet
+    pub(crate) fn into_closet(self) -> Result<Closet, CoreError> {
+        self.root.into_closet(self.purge_histories)
+    }
+}
+
+/// check a rekey keeps the content of the known drawers at all depths,
+/// changes the salts, and loses the drawers whose passphrase wasn't given
+#[test]
+fn test_rekey() {
+    let pwd1 = "first drawer";
+    let pwd2 = "second drawer";
+    let pwd3 = "deep drawer in the second one";
+    let entry1 = Entry::new("key1", "value1");
+    let entry2 = Entry::new("key2", "value2");
+    let entry3 = Entry::new("key3", "value3");
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("test-rekey.closet");
+    let mut open_closet = OpenCloset::create_for_tests(path.to_path_buf()).unwrap();
+    let drawer1 = open_closet.create_drawer(pwd1).unwrap();
+    drawer1.content.entries.push(entry1.clone());
+    open_closet.close_deepest_drawer().unwrap();
+    let drawer2 = open_closet.create_drawer(pwd2).unwrap();
+    drawer2.content.entries.push(entry2.clone());
+    let drawer3 = open_closet.create_drawer(pwd3).unwrap();
+    drawer3.content.entries.push(entry3.clone());
+    open_closet.close_and_save().unwrap();
+
+    // rekey, knowing all passphrases
+    let mut open_closet = OpenCloset::open(path.to_path_buf()).unwrap();
+    let old_salt = open_closet.root_closet().salt.clone();
+    let old_deep_salt = open_closet
+        .open_drawer(pwd2)
+        .unwrap()
+        .content
+        .closet
+        .salt
+        .clone();
+    open_closet.close_deepest_drawer().unwrap();
+    // passphrases are added one by one, like in the wizard, the deep
+    // one being refused before the one of its parent
+    let mut plan = RekeyPlan::new(open_closet.root_closet(), &[]);
+    let mut passwords = Vec::new();
+    for (pwd, opens) in [(pwd3, false), (pwd2, true), (pwd3, true), (pwd1, true)] {
+        passwords.push(pwd.to_string());
+        assert_eq!(
+            plan.add_password(open_closet.root_closet(), &passwords),
+            opens
+        );
+        if !opens {
+            passwords.pop();
+        }
+    }
+    assert_eq!(plan.known_drawers_count(), 3);
+    open_closet.rekey(plan).unwrap();
+    let mut open_closet = OpenCloset::open(path.to_path_buf()).unwrap();
+    assert_ne!(open_closet.root_closet().salt, old_salt);
+    let drawer1 = open_closet.open_drawer(pwd1).unwrap();
+    assert_eq!(drawer1.content.entries, vec![entry1.clone()]);
+    let drawer2 = open_closet.open_drawer(pwd2).unwrap();
+    assert_eq!(drawer2.content.entries, vec![entry2.clone()]);
+    assert_ne!(drawer2.content.closet.salt, old_deep_salt);
+    let drawer3 = open_closet.open_drawer(pwd3).unwrap();
+    assert_eq!(drawer3.content.entries, vec![entry3.clone()]);
+    open_closet.close_and_save().unwrap();
+
+    // rekey, forgetting the first drawer
+    let mut open_closet = OpenCloset::open(path.to_path_buf()).unwrap();
+    let root_drawers_count = open_closet.root_drawers_count();
+    let passwords = vec![pwd2.to_string(), pwd3.to_string()];
+    let plan = RekeyPlan::new(open_closet.root_closet(), &passwords);
+    assert_eq!(plan.known_drawers_count(), 2);
+    assert!(plan.unknown_drawers_count() >= root_drawers_count - 1);
+    open_closet.rekey(plan).unwrap();
+    let mut open_closet = OpenCloset::open(path.to_path_buf()).unwrap();
+    assert!(open_closet.open_drawer(pwd1).is_none());
+    open_closet.open_drawer(pwd2).unwrap();
+    let drawer3 = open_closet.open_drawer(pwd3).unwrap();
+    assert_eq!(drawer3.content.entries, vec![entry3.clone()]);
+
+    temp_dir.close().unwrap();
+}
@@ -0,0 +1,24 @@
+use super::*;
+
+/// Where a taken drawer, with the drawers it contains, is moved
+#[allow(clippy::large_enum_variant)]
+pub enum RelocationTarget {
+    /// The root closet, making the drawer a top level one
+    Root,
+    /// The closet of the upper open drawer at this depth
+    Upper(usize),
+    /// The closet of another drawer of the same closet as the
+    /// relocated one, opened with its passphrase
+    Sibling(OpenDrawer),
+}
+
+impl RelocationTarget {
+    /// Return the depth the relocated drawer would have
+    pub fn new_depth(&self) -> usize {
+        match self {
+            Self::Root => 0,
+            Self::Upper(depth) => depth + 1,
+            Self::Sibling(sibling) => sibling.depth + 1,
+        }
+    }
+}
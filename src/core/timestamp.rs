@@ -0,0 +1,73 @@
+use std::time::{
+    SystemTime,
+    UNIX_EPOCH,
+};
+
+pub const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Return the current time, in seconds since the epoch
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Format a time, in seconds since the epoch, as an UTC date
+/// like `2024-03-05`
+pub fn format_date(time: u64) -> String {
+    // civil from days, after Howard Hinnant's algorithm
+    let days = (time / SECONDS_PER_DAY) as i64 + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Parse an UTC date like `2024-03-05`, returning the time
+/// of its start, in seconds since the epoch
+pub fn parse_date(s: &str) -> Option<u64> {
+    let mut parts = s.split('-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: i64 = parts.next()?.parse().ok()?;
+    let day: i64 = parts.next()?.parse().ok()?;
+    if parts.next().is_some() || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    // days from civil, after Howard Hinnant's algorithm
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * mp + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    let time = u64::try_from(days).ok()? * SECONDS_PER_DAY;
+    // rejecting days like 02-30
+    (format_date(time) == s).then_some(time)
+}
+
+#[test]
+fn test_format_date() {
+    assert_eq!(format_date(0), "1970-01-01");
+    assert_eq!(format_date(951_782_400), "2000-02-29");
+    assert_eq!(format_date(1_709_596_799), "2024-03-04");
+    assert_eq!(format_date(1_709_596_800), "2024-03-05");
+}
+
+#[test]
+fn test_parse_date() {
+    assert_eq!(parse_date("1970-01-01"), Some(0));
+    assert_eq!(parse_date("2000-02-29"), Some(951_782_400));
+    assert_eq!(parse_date("2024-03-05"), Some(1_709_596_800));
+    assert_eq!(parse_date("2023-02-29"), None);
+    assert_eq!(parse_date("2024-13-01"), None);
+    assert_eq!(parse_date("2024-3-5"), None);
+    assert_eq!(parse_date("1969-12-31"), None);
+    assert_eq!(parse_date("yesterday"), None);
+}
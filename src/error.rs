@@ -12,4 +12,7 @@ pub enum SafeClosetError {
 
     #[error("Crossbeam channel error: {0}")]
     Crossbeam(#[from] crossbeam::channel::RecvError),
+
+    #[error("This passphrase opens no drawer")]
+    NoDrawerForPassphrase,
 }
@@ -0,0 +1,628 @@
+use {
+    super::*,
+    crate::core::*,
+};
+
+/// What merging another copy of the closet into the local one would
+/// do, given the passphrases the user typed.
+///
+/// Drawers are matched by their passphrase at the same place of the
+/// hierarchy. As a drawer gets a new id each time it's saved, a drawer
+/// with the same id in both copies wasn't changed on either side.
+///
+/// Nothing is ever removed: entries and drawers absent from one copy
+/// are kept, or brought, from the other one.
+pub struct ClosetMerge {
+    local: Closet,
+    root: MergeLevel,
+    counts: MergeCounts,
+    /// whether a common ancestor was given
+    with_ancestor: bool,
+}
+
+/// The drawers of a closet, as merged with the passphrases tried
+/// until now
+#[derive(Default)]
+struct MergeLevel {
+    drawers: Vec<DrawerMerge>,
+    /// ids, in either copy, of the drawers already opened at this level
+    seen_ids: Vec<DrawerId>,
+}
+
+/// A drawer opened in the other copy, maybe also in the local closet
+pub struct DrawerMerge {
+    /// the title of the drawer, or a description of its place
+    pub label: String,
+    /// the description of the place of the drawer
+    place: String,
+    local: Option<OpenDrawer>,
+    other: OpenDrawer,
+    ancestor: Option<OpenDrawer>,
+    /// the local entries, with the values changed only in the other copy
+    entries: Vec<Entry>,
+    /// the entries only in the other copy, and the conflicting values
+    pub import_set: ImportSet,
+    /// number of values taken from the other copy
+    updated_count: usize,
+    sub_level: MergeLevel,
+}
+
+/// Counts gathered while building the plan
+#[derive(Default)]
+struct MergeCounts {
+    /// number of drawers with the same id in both copies
+    identical: usize,
+    /// number of drawers found only in the local closet
+    local_only: usize,
+}
+
+/// The closets of a level in both copies, and in the ancestor
+#[derive(Clone, Copy)]
+struct MergeSources<'c> {
+    local: &'c Closet,
+    other: &'c Closet,
+    ancestor: Option<&'c Closet>,
+}
+
+impl MergeLevel {
+    fn new(
+        depth: usize,
+        location: &str,
+        sources: MergeSources<'_>,
+        passwords: &[String],
+        counts: &mut MergeCounts,
+    ) -> Self {
+        let mut level = Self::default();
+        for password_idx in 0..passwords.len() {
+            level.try_password(depth, location, sources, passwords, password_idx, counts);
+        }
+        level
+    }
+    /// Try the last of the passphrases at this level and at the levels
+    /// of the merged drawers, and return whether it opened a new drawer
+    /// in either copy.
+    ///
+    /// Only the new passphrase is derived, except in the closets of a newly
+    /// opened drawer, where all passphrases are tried.
+    fn add_password(
+        &mut self,
+        depth: usize,
+        location: &str,
+        sources: MergeSources<'_>,
+        passwords: &[String],
+        counts: &mut MergeCounts,
+    ) -> bool {
+        if passwords.is_empty() {
+            return false;
+        }
+        let mut added = false;
+        for drawer in &mut self.drawers {
+            added |= drawer.add_password(depth + 1, passwords, counts);
+        }
+        let password_idx = passwords.len() - 1;
+        added |= self.try_password(depth, location, sources, passwords, password_idx, counts);
+        added
+    }
+    /// Open the drawers of the passphrase of the given index in both
+    /// copies and, if they weren't already seen, merge them, and
+    /// return true
+    fn try_password(
+        &mut self,
+        depth: usize,
+        location: &str,
+        sources: MergeSources<'_>,
+        passwords: &[String],
+        password_idx: usize,
+        counts: &mut MergeCounts,
+    ) -> bool {
+        let password = &passwords[password_idx];
+        let local_drawer = sources.local.open_drawer(depth, password);
+        let other_drawer = sources.other.open_drawer(depth, password);
+        let ids = local_drawer
+            .iter()
+            .chain(other_drawer.iter())
+            .map(|d| d.content.get_id());
+        let mut new_ids = Vec::new();
+        for id in ids {
+            if self.seen_ids.contains(id) {
+                new_ids.clear();
+                break;
+            }
+            new_ids.push(id.clone());
+        }
+        if new_ids.is_empty() {
+            // nothing opened, or a drawer already seen
+            return false;
+        }
+        self.seen_ids.append(&mut new_ids);
+        let Some(other_drawer) = other_drawer else {
+            counts.local_only += 1;
+            return true;
+        };
+        if let Some(local_drawer) = &local_drawer {
+            if local_drawer.has_same_id(&other_drawer) {
+                counts.identical += 1;
+                return true;
+            }
+        }
+        let place = if depth == 0 {
+            format!("drawer of passphrase {}", password_idx + 1)
+        } else {
+            format!("{location} › {}", password_idx + 1)
+        };
+        let label = local_drawer
+            .as_ref()
+            .unwrap_or(&other_drawer)
+            .content
+            .title
+            .clone()
+            .unwrap_or_else(|| place.clone());
+        let Some(local_drawer) = local_drawer else {
+            self.drawers.push(DrawerMerge {
+                label,
+                place,
+                local: None,
+                other: other_drawer,
+                ancestor: None,
+                entries: Vec::new(),
+                import_set: ImportSet::default(),
+                updated_count: 0,
+                sub_level: MergeLevel::default(),
+            });
+            return true;
+        };
+        let ancestor_drawer = sources
+            .ancestor
+            .and_then(|a| a.open_drawer(depth, password));
+        let (entries, import_set, updated_count) = merge_entries(
+            &local_drawer.content.entries,
+            &other_drawer.content.entries,
+            ancestor_drawer.as_ref().map(|d| &d.content.entries[..]),
+        );
+        let sub_sources = MergeSources {
+            local: &local_drawer.content.closet,
+            other: &other_drawer.content.closet,
+            ancestor: ancestor_drawer.as_ref().map(|d| &d.content.closet),
+        };
+        let sub_level = MergeLevel::new(depth + 1, &place, sub_sources, passwords, counts);
+        self.drawers.push(DrawerMerge {
+            label,
+            place,
+            local: Some(local_drawer),
+            other: other_drawer,
+            ancestor: ancestor_drawer,
+            entries,
+            import_set,
+            updated_count,
+            sub_level,
+        });
+        true
+    }
+    fn count(&self) -> usize {
+        self.drawers.iter().map(DrawerMerge::count).sum()
+    }
+}
+
+/// Merge, by name, the entries of the other version of a drawer
+/// into the local ones.
+///
+/// A value changed in only one copy, according to the ancestor, is
+/// taken from that copy. Other differing values are conflicts.
+///
+/// Return the local entries with the values changed only in the other
+/// copy, the import set of the entries only in the other copy and of
+/// the conflicts, and the number of values taken from the other copy.
+fn merge_entries(
+    local: &[Entry],
+    other: &[Entry],
+    ancestor: Option<&[Entry]>,
+) -> (Vec<Entry>, ImportSet, usize) {
+    let mut entries = local.to_vec();
+    let mut new_keys = Vec::new();
+    let mut conflicts = Vec::new();
+    let mut updated_count = 0;
+    for other_entry in other {
+        let Some(local_entry) = entries.iter_mut().find(|e| e.name == other_entry.name) else {
+            new_keys.push(other_entry.clone());
+            continue;
+        };
+        if local_entry.value == other_entry.value {
+            continue;
+        }
+        let ancestor_value = ancestor
+            .and_then(|entries| entries.iter().find(|e| e.name == other_entry.name))
+            .map(|e| &e.value);
+        if ancestor_value == Some(&other_entry.value) {
+            // changed only in the local closet
+            continue;
+        }
+        if ancestor_value == Some(&local_entry.value) {
+            // changed only in the other copy
+            *local_entry = other_entry.clone();
+            updated_count += 1;
+            continue;
+        }
+        // unless reviewed, the local value is kept
+        conflicts.push(Conflict {
+            entry: other_entry.clone(),
+            old_value: local_entry.value.clone(),
+            resolution: ConflictResolution::Keep,
+        });
+    }
+    (
+        entries,
+        ImportSet::from_parts(new_keys, conflicts),
+        updated_count,
+    )
+}
+
+impl DrawerMerge {
+    /// Tell whether the values of the drawer should be hidden
+    pub fn hides_values(&self) -> bool {
+        self.local
+            .as_ref()
+            .unwrap_or(&self.other)
+            .content
+            .settings
+            .hide_values
+    }
+    /// Try the last of the passphrases in the closets of the drawer,
+    /// when it's in both copies
+    fn add_password(
+        &mut self,
+        depth: usize,
+        passwords: &[String],
+        counts: &mut MergeCounts,
+    ) -> bool {
+        let Some(local) = &self.local else {
+            return false;
+        };
+        let sources = MergeSources {
+            local: &local.content.closet,
+            other: &self.other.content.closet,
+            ancestor: self.ancestor.as_ref().map(|d| &d.content.closet),
+        };
+        self.sub_level
+            .add_password(depth, &self.place, sources, passwords, counts)
+    }
+    fn count(&self) -> usize {
+        1 + self.sub_level.count()
+    }
+    fn visit<'m>(
+        &'m self,
+        list: &mut Vec<&'m DrawerMerge>,
+    ) {
+        list.push(self);
+        for sub_drawer in &self.sub_level.drawers {
+            sub_drawer.visit(list);
+        }
+    }
+    /// Find the drawer with conflicts of the given index,
+    /// in the order of `visit`
+    fn find_conflicting_mut(
+        &mut self,
+        idx: &mut usize,
+    ) -> Option<&mut DrawerMerge> {
+        if !self.import_set.conflicts().is_empty() {
+            if *idx == 0 {
+                return Some(self);
+            }
+            *idx -= 1;
+        }
+        self.sub_level
+            .drawers
+            .iter_mut()
+            .find_map(|sub_drawer| sub_drawer.find_conflicting_mut(idx))
+    }
+    /// Write the merged drawer into the closet
+    fn apply(
+        self,
+        closet: &mut Closet,
+        depth: usize,
+    ) -> Result<(), CoreError> {
+        let DrawerMerge {
+            local,
+            other,
+            entries,
+            import_set,
+            sub_level,
+            ..
+        } = self;
+        let Some(mut local) = local else {
+            return closet.import_drawer(depth, other);
+        };
+        for sub_drawer in sub_level.drawers {
+            sub_drawer.apply(&mut local.content.closet, depth + 1)?;
+        }
+        local.content.entries = entries;
+        import_set.merge_into(&mut local);
+        if !closet.close_drawer(local)? {
+            return Err(CoreError::InternalError(
+                "merged drawer not found".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl ClosetMerge {
+    /// Try to open the drawers of both copies, at all depths, with the
+    /// given passphrases, and merge their entries.
+    ///
+    /// The ancestor, when given, is a version of the closet from before
+    /// the copies diverged, telling which copy changed a value. Without
+    /// it, all differing values are conflicts.
+    pub fn new(
+        local: &Closet,
+        other: &Closet,
+        ancestor: Option<&Closet>,
+        passwords: &[String],
+    ) -> Self {
+        let mut counts = MergeCounts::default();
+        let sources = MergeSources {
+            local,
+            other,
+            ancestor,
+        };
+        let root = MergeLevel::new(0, "", sources, passwords, &mut counts);
+        Self {
+            local: local.clone(),
+            root,
+            counts,
+            with_ancestor: ancestor.is_some(),
+        }
+    }
+    /// Take into account a new passphrase, the last of the given ones
+    /// which must be the ones the plan was built with, followed by the
+    /// new one. The other copy and the ancestor must be the ones the
+    /// plan was built with.
+    ///
+    /// Return whether the passphrase opens a drawer which wasn't found.
+    pub fn add_password(
+        &mut self,
+        other: &Closet,
+        ancestor: Option<&Closet>,
+        passwords: &[String],
+    ) -> bool {
+        let sources = MergeSources {
+            local: &self.local,
+            other,
+            ancestor,
+        };
+        self.root
+            .add_password(0, "", sources, passwords, &mut self.counts)
+    }
+    /// Return the number of drawers the passphrases open, in either copy
+    pub fn found_drawers_count(&self) -> usize {
+        self.counts.identical + self.counts.local_only + self.root.count()
+    }
+    fn all_drawers(&self) -> Vec<&DrawerMerge> {
+        let mut list = Vec::new();
+        for drawer in &self.root.drawers {
+            drawer.visit(&mut list);
+        }
+        list
+    }
+    /// Return the number of drawers with conflicting values
+    pub fn conflicting_drawers_count(&self) -> usize {
+        self.all_drawers()
+            .iter()
+            .filter(|d| !d.import_set.conflicts().is_empty())
+            .count()
+    }
+    /// Return the drawer with conflicting values of the given index
+    pub fn conflicting_drawer_mut(
+        &mut self,
+        mut idx: usize,
+    ) -> Option<&mut DrawerMerge> {
+        self.root
+            .drawers
+            .iter_mut()
+            .find_map(|drawer| drawer.find_conflicting_mut(&mut idx))
+    }
+    pub fn confirm_string(&self) -> String {
+        let drawers = self.all_drawers();
+        let (merged, copied): (Vec<&DrawerMerge>, Vec<&DrawerMerge>) =
+            drawers.iter().partition(|d| d.local.is_some());
+        let mut lines = vec![format!(
+            "{} drawers are identical in both copies.",
+            self.counts.identical,
+        )];
+        if merged.is_empty() {
+            lines.push("No drawer was changed in both copies.".to_string());
+        } else {
+            lines.push(format!("{} drawers will be merged:", merged.len()));
+            for d in &merged {
+                let mut line = format!(
+                    "* *{}*: {} values taken from the other copy, {} new entries",
+                    d.label,
+                    d.updated_count,
+                    d.import_set.new_keys_count(),
+                );
+                if !d.import_set.conflicts().is_empty() {
+                    line.push_str(&format!(
+                        ", conflicting values: {}",
+                        d.import_set.resolutions_summary()
+                    ));
+                }
+                lines.push(line);
+            }
+        }
+        if !copied.is_empty() {
+            lines.push(format!(
+                "{} drawers found only in the other copy will be added.",
+                copied.len()
+            ));
+        }
+        if self.counts.local_only > 0 {
+            lines.push(format!(
+                "{} drawers found only in the local closet are kept as they are.",
+                self.counts.local_only
+            ));
+        }
+        if self.with_ancestor {
+            lines.push(
+                "Values changed in only one copy since the chosen ancestor are taken from this copy."
+                    .to_string(),
+            );
+        } else {
+            lines.push(
+                "Without common ancestor, all values differing between both copies are conflicts."
+                    .to_string(),
+            );
+        }
+        lines.join("\n")
+    }
+    /// Build the merged root closet and return it with a
+    /// displayable report
+    pub fn into_closet(self) -> Result<(Closet, String), CoreError> {
+        let drawers_count = self.root.count();
+        let mut closet = self.local;
+        for drawer in self.root.drawers {
+            drawer.apply(&mut closet, 0)?;
+        }
+        let report = format!(
+            "{} drawers merged or added, {} identical ones.",
+            drawers_count, self.counts.identical,
+        );
+        Ok((closet, report))
+    }
+}
+
+/// check the merge of two divergent copies of a closet, with and
+/// without common ancestor
+#[test]
+fn test_closet_merge() {
+    let kdf = KdfParams::for_tests();
+    let mut base = Closet::new(0, kdf, PaddingPolicy::default()).unwrap();
+    let mut drawer = base.create_drawer(0, "pwd".to_string(), 0).unwrap();
+    for (name, modified) in [("x", 10), ("y", 10), ("z", 10)] {
+        let mut entry = Entry::new(name, "1");
+        entry.modified = Some(modified);
+        drawer.content.entries.push(entry);
+    }
+    let deep = drawer
+        .content
+        .closet
+        .create_drawer(1, "deep".to_string(), 0)
+        .unwrap();
+    drawer.content.closet.close_drawer(deep).unwrap();
+    base.close_drawer(drawer).unwrap();
+    let kept = base.create_drawer(0, "kept".to_string(), 0).unwrap();
+    base.close_drawer(kept).unwrap();
+    let ancestor = base.clone();
+
+    // local: x changed, z changed, a added
+    let mut local = base.clone();
+    let mut drawer = local.open_drawer(0, "pwd").unwrap();
+    let entries = &mut drawer.content.entries;
+    entries[0].value = "2".to_string();
+    entries[0].modified = Some(20);
+    entries[2].value = "local".to_string();
+    entries[2].modified = Some(20);
+    entries.push(Entry::new("a", "1"));
+    local.close_drawer(drawer).unwrap();
+
+    // other: y changed, z changed, b added, a deep entry,
+    // and a new drawer
+    let mut other = base.clone();
+    let mut drawer = other.open_drawer(0, "pwd").unwrap();
+    let entries = &mut drawer.content.entries;
+    // the clock of the other computer was late: the ancestor decides,
+    // not the dates
+    entries[1].value = "2".to_string();
+    entries[1].modified = Some(5);
+    entries[2].value = "other".to_string();
+    entries[2].modified = Some(30);
+    entries.push(Entry::new("b", "1"));
+    let mut deep = drawer.content.closet.open_drawer(1, "deep").unwrap();
+    deep.content.entries.push(Entry::new("d", "1"));
+    drawer.content.closet.close_drawer(deep).unwrap();
+    other.close_drawer(drawer).unwrap();
+    let new = other.create_drawer(0, "new".to_string(), 0).unwrap();
+    other.close_drawer(new).unwrap();
+
+    let passwords: Vec<String> = ["kept", "pwd", "deep", "new"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let values = |closet: &Closet, depth: usize, password: &str| -> Vec<String> {
+        let drawer = match depth {
+            0 => closet.open_drawer(0, password).unwrap(),
+            _ => closet
+                .open_drawer(0, "pwd")
+                .unwrap()
+                .content
+                .closet
+                .open_drawer(1, password)
+                .unwrap(),
+        };
+        drawer
+            .content
+            .entries
+            .iter()
+            .map(|e| format!("{}={}", e.name, e.value))
+            .collect()
+    };
+
+    // with the ancestor, only z is a conflict
+    let mut merge = ClosetMerge::new(&local, &other, Some(&ancestor), &passwords);
+    assert_eq!(merge.found_drawers_count(), 4);
+    assert_eq!(merge.conflicting_drawers_count(), 1);
+    let drawer = merge.conflicting_drawer_mut(0).unwrap();
+    assert_eq!(drawer.import_set.conflicts().len(), 1);
+    drawer
+        .import_set
+        .set_all_resolutions(ConflictResolution::Replace);
+    assert!(merge.conflicting_drawer_mut(1).is_none());
+    let (merged, _) = merge.into_closet().unwrap();
+    assert_eq!(
+        values(&merged, 0, "pwd"),
+        vec!["x=2", "y=2", "z=other", "a=1", "b=1"]
+    );
+    assert_eq!(values(&merged, 1, "deep"), vec!["d=1"]);
+    assert!(merged.open_drawer(0, "kept").is_some());
+    assert!(merged.open_drawer(0, "new").is_some());
+
+    // passphrases can be added one at a time, a deep one being
+    // accepted only after the one of its parent
+    let mut merge = ClosetMerge::new(&local, &other, Some(&ancestor), &[]);
+    let mut typed: Vec<String> = Vec::new();
+    for (password, opens) in [
+        ("deep", false),
+        ("kept", true),
+        ("pwd", true),
+        ("unknown", false),
+        ("deep", true),
+        ("new", true),
+    ] {
+        typed.push(password.to_string());
+        assert_eq!(merge.add_password(&other, Some(&ancestor), &typed), opens);
+        if !opens {
+            typed.pop();
+        }
+    }
+    assert_eq!(merge.found_drawers_count(), 4);
+    assert_eq!(merge.conflicting_drawers_count(), 1);
+
+    // without ancestor, x, y and z are conflicts, whose local
+    // values are kept by default
+    let merge = ClosetMerge::new(&local, &other, None, &passwords);
+    assert!(merge.confirm_string().contains("3 kept"));
+    let (merged, _) = merge.into_closet().unwrap();
+    assert_eq!(
+        values(&merged, 0, "pwd"),
+        vec!["x=2", "y=1", "z=local", "a=1", "b=1"]
+    );
+
+    // merging a copy with itself changes nothing, and the
+    // drawers of identical drawers aren't looked at
+    let merge = ClosetMerge::new(&local, &local.clone(), None, &passwords);
+    assert_eq!(merge.found_drawers_count(), 2);
+    assert_eq!(merge.conflicting_drawers_count(), 0);
+    assert!(
+        merge
+            .confirm_string()
+            .starts_with("2 drawers are identical")
+    );
+}
@@ -0,0 +1,15 @@
+use {
+    super::*,
+    crate::core::*,
+};
+
+/// An entry of the source whose name is in the destination,
+/// with another value
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    /// the entry of the source
+    pub entry: Entry,
+    /// the value of the entry of the destination
+    pub old_value: String,
+    pub resolution: ConflictResolution,
+}
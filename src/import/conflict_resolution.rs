@@ -0,0 +1,44 @@
+use std::fmt;
+
+/// How to import an entry whose name is already in the destination,
+/// with another value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictResolution {
+    /// Keep the value of the destination, ignoring the new one
+    Keep,
+    /// Replace the value of the destination, which goes to the history
+    Replace,
+    /// Append the new value to the one of the destination, with a separator
+    #[default]
+    Append,
+    /// Import the entry as a new one, under another name
+    Rename,
+}
+
+impl ConflictResolution {
+    pub const ALL: [Self; 4] = [Self::Keep, Self::Replace, Self::Append, Self::Rename];
+
+    /// Return the resolution after this one, to cycle among them
+    pub fn next(self) -> Self {
+        match self {
+            Self::Keep => Self::Replace,
+            Self::Replace => Self::Append,
+            Self::Append => Self::Rename,
+            Self::Rename => Self::Keep,
+        }
+    }
+}
+
+impl fmt::Display for ConflictResolution {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::Keep => write!(f, "keep"),
+            Self::Replace => write!(f, "replace"),
+            Self::Append => write!(f, "append"),
+            Self::Rename => write!(f, "rename"),
+        }
+    }
+}
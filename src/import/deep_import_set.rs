@@ -0,0 +1,154 @@
+use crate::core::*;
+
+/// The drawers contained in a source drawer, to bring with its entries
+/// into the closet of the destination drawer
+#[derive(Default)]
+pub struct DeepImportSet {
+    /// deep drawers opened with the passphrases given by the user,
+    /// to be crypted again in the destination closet
+    opened: Vec<OpenDrawer>,
+    /// the other drawers of the source closet, copied as they are,
+    /// which is possible when both closets share their keys
+    closed: Vec<ClosedDrawer>,
+}
+
+impl DeepImportSet {
+    pub fn is_empty(&self) -> bool {
+        self.opened.is_empty() && self.closed.is_empty()
+    }
+    pub fn has_closed_copies(&self) -> bool {
+        !self.closed.is_empty()
+    }
+    pub fn confirm_string(&self) -> String {
+        let mut s = format!(
+            "{} deep drawers will be crypted again in the destination.",
+            self.opened.len()
+        );
+        if !self.closed.is_empty() {
+            s.push_str(&format!(
+                "\n{} closed drawers, some of them maybe decoys, will be copied as they are.",
+                self.closed.len()
+            ));
+        }
+        s
+    }
+    /// Tell whether the deep drawer is already in the set
+    pub fn contains(
+        &self,
+        drawer: &OpenDrawer,
+    ) -> bool {
+        self.opened.iter().any(|d| d.has_same_id(drawer))
+    }
+    /// Add a deep drawer of the source, opened with its passphrase
+    pub fn add_opened(
+        &mut self,
+        drawer: OpenDrawer,
+    ) {
+        self.closed
+            .retain(|d| d.get_id() != drawer.content.get_id());
+        self.opened.push(drawer);
+    }
+    /// Add the drawers of the source closet which aren't already in
+    /// the destination closet, nor opened, to copy them as they are.
+    ///
+    /// Return false if the closets don't share their keys, as the
+    /// copies couldn't be opened in the destination.
+    pub fn add_closed_copies(
+        &mut self,
+        src: &Closet,
+        dst: &Closet,
+    ) -> bool {
+        if !dst.shares_keys_with(src) {
+            return false;
+        }
+        let dst_ids = dst.drawer_ids();
+        self.closed = src
+            .drawers
+            .iter()
+            .filter(|d| !dst_ids.contains(d.get_id()))
+            .filter(|d| !self.opened.iter().any(|o| o.content.get_id() == d.get_id()))
+            .cloned()
+            .collect();
+        true
+    }
+    /// Add the drawers to the closet of the destination drawer and
+    /// return a displayable report.
+    ///
+    /// The destination isn't modified when an error is returned.
+    pub fn import_into(
+        self,
+        dst: &mut OpenDrawer,
+    ) -> Result<String, CoreError> {
+        let depth = dst.depth + 1;
+        let mut closet = dst.content.closet.clone();
+        let opened = self.opened.len();
+        for drawer in self.opened {
+            closet.import_drawer(depth, drawer)?;
+        }
+        let copied = closet.import_closed_drawers(self.closed);
+        dst.content.closet = closet;
+        let mut report = format!("{opened} deep drawers crypted again");
+        if copied > 0 {
+            report.push_str(&format!(" and {copied} closed drawers copied"));
+        }
+        report.push_str(" in the destination drawer.");
+        Ok(report)
+    }
+}
+
+/// check deep drawers are carried over either crypted again, or copied
+/// as they are when the closets share their keys
+#[test]
+fn test_deep_import_set() {
+    let kdf = KdfParams::for_tests();
+    let padding = PaddingPolicy::default();
+    let mut src_closet = Closet::new(0, kdf, padding).unwrap();
+    let mut src = src_closet.create_drawer(0, "src".to_string(), 0).unwrap();
+    let mut deep = src
+        .content
+        .closet
+        .create_drawer(1, "deep".to_string(), 0)
+        .unwrap();
+    deep.content.entries.push(Entry::new("a", "1"));
+    src.content.closet.close_drawer(deep).unwrap();
+    let mut other = src
+        .content
+        .closet
+        .create_drawer(1, "other".to_string(), 0)
+        .unwrap();
+    other.content.entries.push(Entry::new("b", "2"));
+    src.content.closet.close_drawer(other).unwrap();
+    let mut dst = src_closet.create_drawer(0, "dst".to_string(), 0).unwrap();
+
+    // the closets of src and dst don't share their keys: only the
+    // opened drawer can be imported
+    let mut set = DeepImportSet::default();
+    set.add_opened(src.content.closet.open_drawer(1, "deep").unwrap());
+    assert!(!set.add_closed_copies(&src.content.closet, &dst.content.closet));
+    set.import_into(&mut dst).unwrap();
+    let deep = dst.content.closet.open_drawer(1, "deep").unwrap();
+    assert_eq!(deep.content.entries, vec![Entry::new("a", "1")]);
+    assert!(dst.content.closet.open_drawer(1, "other").is_none());
+
+    // a passphrase already used in the destination is refused
+    let count = dst.content.closet.drawers.len();
+    let mut set = DeepImportSet::default();
+    set.add_opened(src.content.closet.open_drawer(1, "other").unwrap());
+    set.add_opened(src.content.closet.open_drawer(1, "deep").unwrap());
+    assert!(set.import_into(&mut dst).is_err());
+    assert_eq!(dst.content.closet.drawers.len(), count);
+
+    // a copy of the source drawer shares the keys of its closet
+    let src_content = src.content.clone();
+    src_closet.close_drawer(src).unwrap();
+    let mut copy = src_closet.open_drawer(0, "src").unwrap();
+    let src = src_content;
+    let mut set = DeepImportSet::default();
+    assert!(set.add_closed_copies(&src.closet, &copy.content.closet));
+    assert!(!set.has_closed_copies()); // all the drawers are already there
+    copy.content.closet.drawers.clear();
+    assert!(set.add_closed_copies(&src.closet, &copy.content.closet));
+    set.import_into(&mut copy).unwrap();
+    let other = copy.content.closet.open_drawer(1, "other").unwrap();
+    assert_eq!(other.content.entries, vec![Entry::new("b", "2")]);
+}
@@ -1,23 +1,65 @@
-use crate::core::*;
+use {
+    super::*,
+    crate::core::*,
+};
 
 /// The subset of the drawer or csv file with the things to import
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct ImportSet {
     new_keys: Vec<Entry>,
-    different_values: Vec<Entry>,
+    conflicts: Vec<Conflict>,
 }
 
 impl ImportSet {
     pub fn confirm_string(&self) -> String {
-        format!(
+        let mut s = format!(
             "The source contains {} new keys and {} new values.",
             self.new_keys.len(),
-            self.different_values.len(),
-        )
+            self.conflicts.len(),
+        );
+        if !self.conflicts.is_empty() {
+            s.push_str(&format!(
+                "\nConflicting values: {}.",
+                self.resolutions_summary()
+            ));
+        }
+        s
+    }
+    /// Return a displayable count of the conflicts per resolution,
+    /// like "2 kept, 1 replaced"
+    pub fn resolutions_summary(&self) -> String {
+        ConflictResolution::ALL
+            .iter()
+            .filter_map(|&resolution| {
+                let count = self
+                    .conflicts
+                    .iter()
+                    .filter(|c| c.resolution == resolution)
+                    .count();
+                if count == 0 {
+                    return None;
+                }
+                let verb = match resolution {
+                    ConflictResolution::Keep => "kept",
+                    ConflictResolution::Replace => "replaced",
+                    ConflictResolution::Append => "appended",
+                    ConflictResolution::Rename => "renamed",
+                };
+                Some(format!("{count} {verb}"))
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
     }
 }
 
 impl ImportSet {
+    /// Build the set of the entries of the source which aren't
+    /// already in the destination.
+    ///
+    /// Entries whose name is in the destination with another value
+    /// are conflicts, resolved by appending the value unless another
+    /// resolution is chosen (a value already contained in the current
+    /// one isn't appended again).
     pub fn new(
         mut src: Vec<Entry>,
         dst: &OpenDrawer,
@@ -27,8 +69,12 @@ impl ImportSet {
         for src_entry in src.drain(..) {
             let dst_entry = dst_entries.iter().find(|&se| se.name == src_entry.name);
             if let Some(dst_entry) = dst_entry {
-                if !dst_entry.value.contains(&src_entry.value) {
-                    report.different_values.push(src_entry);
+                if dst_entry.value != src_entry.value {
+                    report.conflicts.push(Conflict {
+                        entry: src_entry,
+                        old_value: dst_entry.value.clone(),
+                        resolution: ConflictResolution::default(),
+                    });
                 }
             } else {
                 report.new_keys.push(src_entry);
@@ -36,32 +82,177 @@ impl ImportSet {
         }
         report
     }
+    /// Build the set from already sorted entries
+    pub fn from_parts(
+        new_keys: Vec<Entry>,
+        conflicts: Vec<Conflict>,
+    ) -> Self {
+        Self {
+            new_keys,
+            conflicts,
+        }
+    }
     pub fn is_empty(&self) -> bool {
-        self.new_keys.is_empty() && self.different_values.is_empty()
+        self.new_keys.is_empty() && self.conflicts.is_empty()
+    }
+    pub fn new_keys_count(&self) -> usize {
+        self.new_keys.len()
+    }
+    pub fn conflicts(&self) -> &[Conflict] {
+        &self.conflicts
+    }
+    pub fn set_resolution(
+        &mut self,
+        idx: usize,
+        resolution: ConflictResolution,
+    ) {
+        if let Some(conflict) = self.conflicts.get_mut(idx) {
+            conflict.resolution = resolution;
+        }
+    }
+    pub fn set_all_resolutions(
+        &mut self,
+        resolution: ConflictResolution,
+    ) {
+        for conflict in &mut self.conflicts {
+            conflict.resolution = resolution;
+        }
     }
     /// Import the set into the destination drawer and
     /// return a displayable report
     pub fn import_into(
-        mut self,
+        self,
         dst: &mut OpenDrawer,
     ) -> String {
-        let report = format!(
-            "{} added entries and {} enriched entries.\n\
-            Nothing is saved on disk until you save.",
-            self.new_keys.len(),
-            self.different_values.len(),
-        );
+        let (added, enriched) = self.merge_into(dst);
+        format!(
+            "{added} added entries and {enriched} modified entries.\n\
+            Nothing is saved on disk until you save."
+        )
+    }
+    /// Import the set into the destination drawer and return
+    /// the numbers of added and modified entries
+    pub fn merge_into(
+        mut self,
+        dst: &mut OpenDrawer,
+    ) -> (usize, usize) {
+        let history_depth = dst.content.settings.history_depth;
         let dst_entries = &mut dst.content.entries;
-        for src_entry in self.different_values.drain(..) {
-            for dst_entry in dst_entries.iter_mut() {
-                if dst_entry.name == src_entry.name {
-                    dst_entry.value.push_str("\n---\n");
-                    dst_entry.value.push_str(&src_entry.value);
-                    break;
-                }
+        let mut enriched = 0;
+        for conflict in self.conflicts.drain(..) {
+            let Conflict {
+                mut entry,
+                resolution,
+                ..
+            } = conflict;
+            if resolution == ConflictResolution::Rename {
+                entry.name = unique_name(&entry.name, dst_entries, &self.new_keys);
+                self.new_keys.push(entry);
+                continue;
+            }
+            let Some(dst_entry) = dst_entries.iter_mut().find(|e| e.name == entry.name) else {
+                continue;
+            };
+            let value = match resolution {
+                ConflictResolution::Replace => entry.value,
+                ConflictResolution::Append if dst_entry.value.contains(&entry.value) => continue,
+                ConflictResolution::Append => format!("{}\n---\n{}", dst_entry.value, entry.value),
+                _ => continue,
+            };
+            dst_entry.set_value(value, history_depth);
+            enriched += 1;
+        }
+        let added = self.new_keys.len();
+        for entry in &mut self.new_keys {
+            // entries coming from a drawer keep their timestamps
+            if entry.modified.is_none() {
+                entry.touch();
             }
         }
         dst_entries.append(&mut self.new_keys);
-        report
+        (added, enriched)
+    }
+}
+
+/// Return a name, derived from the given one, which isn't
+/// used by any of the entries
+fn unique_name(
+    name: &str,
+    dst_entries: &[Entry],
+    new_keys: &[Entry],
+) -> String {
+    let is_used = |candidate: &str| {
+        dst_entries
+            .iter()
+            .chain(new_keys.iter())
+            .any(|e| e.name == candidate)
+    };
+    let mut candidate = format!("{name} (imported)");
+    let mut n = 2;
+    while is_used(&candidate) {
+        candidate = format!("{name} (imported {n})");
+        n += 1;
+    }
+    candidate
+}
+
+/// check each conflict is resolved according to its chosen resolution
+#[test]
+fn test_conflict_resolutions() {
+    let kdf = KdfParams::for_tests();
+    let content = DrawerContent::new(0, kdf, PaddingPolicy::default()).unwrap();
+    let mut dst = OpenDrawer::new(0, "pwd".to_string(), content);
+    for name in ["k", "r", "a", "n", "same"] {
+        dst.content.entries.push(Entry::new(name, "old"));
     }
+    dst.content.entries.push(Entry::new("longer", "old value"));
+    dst.content
+        .entries
+        .push(Entry::new("n (imported)", "other"));
+    let src = vec![
+        Entry::new("k", "new"),
+        Entry::new("r", "new"),
+        Entry::new("a", "new"),
+        Entry::new("n", "new"),
+        Entry::new("same", "old"),
+        Entry::new("added", "new"),
+        // a different value, even if contained in the current one, is a conflict
+        Entry::new("longer", "old"),
+    ];
+    let mut import_set = ImportSet::new(src, &dst);
+    assert_eq!(import_set.conflicts().len(), 5);
+    assert_eq!(import_set.resolutions_summary(), "5 appended");
+    import_set.set_all_resolutions(ConflictResolution::Keep);
+    import_set.set_resolution(1, ConflictResolution::Replace);
+    import_set.set_resolution(2, ConflictResolution::Append);
+    import_set.set_resolution(3, ConflictResolution::Rename);
+    // but it's not appended to a value containing it
+    import_set.set_resolution(4, ConflictResolution::Append);
+    assert_eq!(
+        import_set.resolutions_summary(),
+        "1 kept, 1 replaced, 2 appended, 1 renamed"
+    );
+    assert_eq!(import_set.merge_into(&mut dst), (2, 2));
+    let values: Vec<String> = dst
+        .content
+        .entries
+        .iter()
+        .map(|e| format!("{}={}", e.name, e.value))
+        .collect();
+    assert_eq!(
+        values,
+        vec![
+            "k=old",
+            "r=new",
+            "a=old\n---\nnew",
+            "n=old",
+            "same=old",
+            "longer=old value",
+            "n (imported)=other",
+            "added=new",
+            "n (imported 2)=new",
+        ]
+    );
+    // the replaced value is kept in the history
+    assert_eq!(dst.content.entries[1].history[0].value, "old");
 }
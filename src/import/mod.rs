@@ -1,3 +1,15 @@
+mod closet_merge;
+mod conflict;
+mod conflict_resolution;
+mod deep_import_set;
 mod import_set;
+mod transfer_plan;
 
-pub use import_set::*;
+pub use {
+    closet_merge::*,
+    conflict::*,
+    conflict_resolution::*,
+    deep_import_set::*,
+    import_set::*,
+    transfer_plan::*,
+};
@@ -0,0 +1,191 @@
+use {
+    super::*,
+    crate::core::*,
+    std::fmt,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferMode {
+    Copy,
+    Move,
+}
+
+impl fmt::Display for TransferMode {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::Copy => write!(f, "Copy"),
+            Self::Move => write!(f, "Move"),
+        }
+    }
+}
+
+/// The drawer receiving the entries of the taken drawer
+pub enum TransferTarget {
+    /// One of the upper open drawers, at this depth
+    Upper(usize),
+    /// A drawer of the closet containing the taken drawer
+    Sibling(OpenDrawer),
+    /// A drawer of the closet of the taken drawer
+    Deep(OpenDrawer),
+}
+
+/// Some entries of the taken (deepest) drawer to copy or move
+/// to another drawer.
+///
+/// Entries are added to the destination with the semantics of the
+/// import: new names are added and different values are appended
+/// to the existing ones, nothing is removed.
+pub struct TransferPlan {
+    pub mode: TransferMode,
+    /// indexes of the entries in the taken drawer
+    pub entries: Vec<usize>,
+    pub target: TransferTarget,
+}
+
+impl TransferPlan {
+    /// Apply the transfer in memory and return a displayable report.
+    ///
+    /// The taken drawer is left to the caller, who must save the
+    /// closet with both drawers, or keep the drawer on error.
+    pub fn execute(
+        self,
+        open_closet: &mut OpenCloset,
+        src: &mut OpenDrawer,
+    ) -> Result<String, CoreError> {
+        let entries: Vec<Entry> = self
+            .entries
+            .iter()
+            .filter_map(|&idx| src.content.entries.get(idx))
+            .cloned()
+            .collect();
+        let count = entries.len();
+        let (added, enriched) = match self.target {
+            TransferTarget::Upper(depth) => {
+                let dst = open_closet
+                    .open_drawers_mut()
+                    .get_mut(depth)
+                    .ok_or(CoreError::NoOpenDrawer)?;
+                ImportSet::new(entries, dst).merge_into(dst)
+            }
+            TransferTarget::Sibling(mut dst) => {
+                let counts = ImportSet::new(entries, &dst).merge_into(&mut dst);
+                if !open_closet.deepest_closet_mut().close_drawer(dst)? {
+                    return Err(CoreError::InvalidPushBack);
+                }
+                counts
+            }
+            TransferTarget::Deep(mut dst) => {
+                let counts = ImportSet::new(entries, &dst).merge_into(&mut dst);
+                if !src.content.closet.close_drawer(dst)? {
+                    return Err(CoreError::InvalidPushBack);
+                }
+                counts
+            }
+        };
+        if self.mode == TransferMode::Move {
+            let mut idxs = self.entries;
+            idxs.sort_unstable();
+            idxs.dedup();
+            for idx in idxs.into_iter().rev() {
+                if idx < src.content.entries.len() {
+                    src.content.entries.remove(idx);
+                }
+            }
+        }
+        let verb = match self.mode {
+            TransferMode::Copy => "copied",
+            TransferMode::Move => "moved",
+        };
+        let report = format!(
+            "{count} entries {verb}: {added} added and {enriched} enriched in the destination. Both drawers are saved."
+        );
+        Ok(report)
+    }
+}
+
+/// check entries can be moved to a deep drawer, copied to the upper
+/// drawer and to a drawer of the same level, with the import merge
+#[test]
+fn test_transfer_plan() {
+    let top_pwd = "the top level drawer";
+    let deep_pwd = "a drawer in the top one";
+    let other_pwd = "another top level drawer";
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("test-transfer.closet");
+    let mut open_closet = OpenCloset::create_for_tests(path.to_path_buf()).unwrap();
+    open_closet.create_drawer(other_pwd).unwrap();
+    open_closet.close_deepest_drawer().unwrap();
+    let top = open_closet.create_drawer(top_pwd).unwrap();
+    top.content.entries.push(Entry::new("a", "1"));
+    top.content.entries.push(Entry::new("b", "2"));
+    let deep = open_closet.create_drawer(deep_pwd).unwrap();
+    deep.content.entries.push(Entry::new("b", "3"));
+    open_closet.close_and_save().unwrap();
+    let names = |drawer: &OpenDrawer| -> Vec<String> {
+        drawer
+            .content
+            .entries
+            .iter()
+            .map(|e| format!("{}={}", e.name, e.value))
+            .collect()
+    };
+
+    // moving "a" from the top drawer to the deep one
+    let mut open_closet = OpenCloset::open(path.to_path_buf()).unwrap();
+    let mut src = open_closet.open_take_drawer(top_pwd).unwrap();
+    let dst = src.content.closet.open_drawer(1, deep_pwd).unwrap();
+    let plan = TransferPlan {
+        mode: TransferMode::Move,
+        entries: vec![0],
+        target: TransferTarget::Deep(dst),
+    };
+    plan.execute(&mut open_closet, &mut src).unwrap();
+    let src = open_closet.push_back_save_retake(src).unwrap();
+    assert_eq!(names(&src), vec!["b=2"]);
+    let mut open_closet = OpenCloset::open(path.to_path_buf()).unwrap();
+    open_closet.open_drawer(top_pwd).unwrap();
+    let mut deep = open_closet.open_take_drawer(deep_pwd).unwrap();
+    assert_eq!(names(&deep), vec!["b=3", "a=1"]);
+
+    // a failing transfer leaves the taken drawer untouched
+    let plan = TransferPlan {
+        mode: TransferMode::Move,
+        entries: vec![0],
+        target: TransferTarget::Upper(3),
+    };
+    assert!(plan.execute(&mut open_closet, &mut deep).is_err());
+    assert_eq!(names(&deep), vec!["b=3", "a=1"]);
+
+    // copying "b" from the deep drawer to the top one, which merges the values
+    let plan = TransferPlan {
+        mode: TransferMode::Copy,
+        entries: vec![0],
+        target: TransferTarget::Upper(0),
+    };
+    plan.execute(&mut open_closet, &mut deep).unwrap();
+    let deep = open_closet.push_back_save_retake(deep).unwrap();
+    assert_eq!(names(&deep), vec!["b=3", "a=1"]);
+    assert_eq!(names(&open_closet.open_drawers()[0]), vec!["b=2\n---\n3"]);
+
+    // copying "b" from the top drawer to another one of the same level
+    let mut open_closet = OpenCloset::open(path.to_path_buf()).unwrap();
+    let mut src = open_closet.open_take_drawer(top_pwd).unwrap();
+    let dst = open_closet
+        .deepest_closet()
+        .open_drawer(0, other_pwd)
+        .unwrap();
+    let plan = TransferPlan {
+        mode: TransferMode::Copy,
+        entries: vec![0],
+        target: TransferTarget::Sibling(dst),
+    };
+    plan.execute(&mut open_closet, &mut src).unwrap();
+    open_closet.push_back_save_retake(src).unwrap();
+    let mut open_closet = OpenCloset::open(path.to_path_buf()).unwrap();
+    let other = open_closet.open_take_drawer(other_pwd).unwrap();
+    assert_eq!(names(&other), vec!["b=2\n---\n3"]);
+    temp_dir.close().unwrap();
+}
@@ -0,0 +1,67 @@
+use crate::core::*;
+
+/// A filter on the time since the last change of entries, written
+/// in the search input like `>6m` (older than 6 months) or `<2w`
+/// (changed less than 2 weeks ago)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AgeFilter {
+    /// whether the entries must be older than the duration
+    pub older: bool,
+    /// the duration, in seconds
+    pub seconds: u64,
+}
+
+impl AgeFilter {
+    /// Parse a token like `>1y`, `<30d`, `>3w` or `<6m`
+    pub fn parse(token: &str) -> Option<Self> {
+        let older = match token.chars().next()? {
+            '>' => true,
+            '<' => false,
+            _ => return None,
+        };
+        let token = &token[1..];
+        let unit = token.chars().last()?;
+        let days_per_unit = match unit {
+            'd' => 1,
+            'w' => 7,
+            'm' => 30,
+            'y' => 365,
+            _ => return None,
+        };
+        let count: u64 = token[..token.len() - 1].parse().ok()?;
+        let seconds = count
+            .checked_mul(days_per_unit)?
+            .checked_mul(SECONDS_PER_DAY)?;
+        Some(Self { older, seconds })
+    }
+    /// Tell whether the entry passes the filter, entries whose
+    /// age is unknown being considered as old
+    pub fn accepts(
+        self,
+        entry: &Entry,
+        now: u64,
+    ) -> bool {
+        match entry.age(now) {
+            Some(age) => (age > self.seconds) == self.older,
+            None => self.older,
+        }
+    }
+}
+
+#[test]
+fn test_age_filter() {
+    let now = 1_000 * SECONDS_PER_DAY;
+    let mut entry = Entry::new("key", "value");
+    assert!(AgeFilter::parse(">1y").unwrap().accepts(&entry, now));
+    assert!(!AgeFilter::parse("<1y").unwrap().accepts(&entry, now));
+    entry.modified = Some(now - 10 * SECONDS_PER_DAY);
+    assert!(AgeFilter::parse(">1w").unwrap().accepts(&entry, now));
+    assert!(!AgeFilter::parse(">2w").unwrap().accepts(&entry, now));
+    assert!(AgeFilter::parse("<2w").unwrap().accepts(&entry, now));
+    assert!(!AgeFilter::parse("<1d").unwrap().accepts(&entry, now));
+    assert!(AgeFilter::parse("1y").is_none());
+    assert!(AgeFilter::parse(">y").is_none());
+    assert!(AgeFilter::parse(">2x").is_none());
+    assert!(AgeFilter::parse("<").is_none());
+    assert!(AgeFilter::parse(">99999999999999999d").is_none());
+}
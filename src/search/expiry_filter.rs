@@ -0,0 +1,47 @@
+use crate::core::*;
+
+/// A filter on the expiry of entries, written in the search
+/// input as `!expired` (expired entries) or `!due` (entries
+/// expired or expiring soon)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExpiryFilter {
+    /// whether the entries expiring soon are accepted too
+    pub soon: bool,
+}
+
+impl ExpiryFilter {
+    /// Parse a token like `!due`
+    pub fn parse(token: &str) -> Option<Self> {
+        match token {
+            "!expired" => Some(Self { soon: false }),
+            "!due" => Some(Self { soon: true }),
+            _ => None,
+        }
+    }
+    pub fn accepts(
+        self,
+        entry: &Entry,
+        now: u64,
+    ) -> bool {
+        match ExpiryStatus::of(entry, now) {
+            ExpiryStatus::Expired => true,
+            ExpiryStatus::Soon => self.soon,
+            ExpiryStatus::Valid => false,
+        }
+    }
+}
+
+#[test]
+fn test_expiry_filter() {
+    let now = 1_000 * SECONDS_PER_DAY;
+    let mut entry = Entry::new("key", "value");
+    let due = ExpiryFilter::parse("!due").unwrap();
+    let expired = ExpiryFilter::parse("!expired").unwrap();
+    assert!(!due.accepts(&entry, now));
+    entry.expiry = Some(Expiry::At(now + SECONDS_PER_DAY));
+    assert!(due.accepts(&entry, now));
+    assert!(!expired.accepts(&entry, now));
+    entry.expiry = Some(Expiry::At(now - SECONDS_PER_DAY));
+    assert!(expired.accepts(&entry, now));
+    assert!(ExpiryFilter::parse("!old").is_none());
+}
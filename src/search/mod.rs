@@ -1,9 +1,15 @@
+mod age_filter;
+mod expiry_filter;
 mod fuzzy_pattern;
 mod name_match;
 mod pos;
+mod tag_filter;
 
 pub use {
+    age_filter::AgeFilter,
+    expiry_filter::ExpiryFilter,
     fuzzy_pattern::FuzzyPattern,
     name_match::NameMatch,
     pos::*,
+    tag_filter::TagFilter,
 };
@@ -0,0 +1,40 @@
+use crate::core::*;
+
+/// A filter on the tags of entries, written in the search
+/// input like `#prod`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagFilter {
+    pub tag: String,
+}
+
+impl TagFilter {
+    /// Parse a token like `#prod`
+    pub fn parse(token: &str) -> Option<Self> {
+        let tag = token.strip_prefix('#')?;
+        if tag.is_empty() || tag.starts_with('#') {
+            return None;
+        }
+        Some(Self {
+            tag: tag.to_string(),
+        })
+    }
+    /// Tell whether the entry has the tag
+    pub fn accepts(
+        &self,
+        entry: &Entry,
+    ) -> bool {
+        entry.has_tag(&self.tag)
+    }
+}
+
+#[test]
+fn test_tag_filter() {
+    let mut entry = Entry::new("key", "value");
+    entry.set_tags("prod db");
+    assert!(TagFilter::parse("#prod").unwrap().accepts(&entry));
+    assert!(TagFilter::parse("#DB").unwrap().accepts(&entry));
+    assert!(!TagFilter::parse("#web").unwrap().accepts(&entry));
+    assert!(TagFilter::parse("prod").is_none());
+    assert!(TagFilter::parse("#").is_none());
+    assert!(TagFilter::parse("##").is_none());
+}
@@ -34,6 +34,13 @@ macro_rules! make_actions {
                 if key == key!(shift-'?') {
                     key = key!('?');
                 }
+                // same for '#' and '!', which are shifted on most layouts
+                if key == key!(shift-'#') {
+                    key = key!('#');
+                }
+                if key == key!(shift-'!') {
+                    key = key!('!');
+                }
                 $(
                     $(
                         if key == $key {
@@ -55,30 +62,48 @@ macro_rules! make_actions {
 // Define the actions that can be part of the menus
 make_actions! {
     Back "back" key!(esc),
+    ManageAttachments "*A*ttached Files" key!(ctrl-a),
+    AuditSecrets "Audit Secrets",
     CloseAllValues "*F*old All unselected Values" key!(ctrl-f),
     CloseDeepDrawer "go to *U*pper drawer" key!(ctrl-U),
     CloseShallowDrawer "Close drawer" key!(ctrl-U),
+    DeleteDrawer "Delete Drawer",
     ConfirmEntryRemoval "Confirm Entry Removal" key!(y),
     Copy "*C*opy" key!(ctrl-C),
     Cut "*C*ut" key!(ctrl-X),
     EditClosetComments "Edit Closet Comments",
+    EditFields "*E*dit Entry Fields" key!(ctrl-e),
+    EditExpiry "Set Entry Expir*y*" key!(ctrl-y),
+    EditTags "Edit Entry *T*ags" key!(ctrl-t),
+    EditDrawerTitle "Set Drawer Title",
+    ExtractDrawer "Extract Drawer to a New Closet",
+    FilterByTag "Filter by Tag" key!('#'),
     GroupMatchingEntries "Group Matching Entries",
+    GeneratePassword "*G*enerate Password" key!(ctrl-g),
     Help "Help" key!('?'),
     Import "Import",
+    ListDueEntries "List Expired Entries" key!('!'),
     SwapLineDown "Swap Line Down" key!(ctrl-down),
     SwapLineUp "Swap Line Up" key!(ctrl-up),
     NewDrawer "*N*ew Drawer" key!(ctrl-N),
     NewEntry "New Entry" key!(n),
     NewEntryAfterCurrent "New Entry After Current" key!(shift-n),
+    TransferEntries "Move or Copy Entries",
     OpenAllValues "Un*f*old All Values" key!(ctrl-F),
     OpenDrawer "*O*pen Drawer" key!(ctrl-O),
     OpenPasswordChangeDialog "Change Drawer Password",
+    RelocateDrawer "Move Drawer",
     Paste "Paste" key!(ctrl-V),
     Quit "*Q*uit" key!(ctrl-Q),
+    RekeyCloset "Rekey Closet",
+    MergeCloset "Merge Another Copy",
     RemoveLine "Remove Line" key!(d),
     SaveDrawer "*S*ave Drawer" key!(ctrl-S),
     Search "Search" key!('/'),
+    ShowValueHistory "Value Histo*r*y" key!(ctrl-r),
     Sort "Sort",
+    SortByAge "Sort by Age",
     ToggleHiding "Toggle *H*iding" key!(ctrl-H), // hiding either pwd chars or unselected values
     ToggleMarkdown "Toggle Markdown",
+    ToggleAges "Toggle Entry Ages",
 }
@@ -33,6 +33,7 @@ pub(super) fn run(
     let event_source = EventSource::new()?;
     let events = event_source.receiver();
     let (timer, timer_rx) = Timer::new(MAX_INACTIVITY);
+    let ticks = crossbeam::channel::tick(TOTP_REFRESH_PERIOD);
     loop {
         select! {
             // user events
@@ -79,6 +80,13 @@ pub(super) fn run(
                 }
             }
 
+            // refresh of the one-time codes
+            recv(ticks) -> _ => {
+                if state.shows_live_codes() {
+                    view.draw(w, &mut state, &skin)?;
+                }
+            }
+
             // timer (so that safecloset doesn't stay open
             // if you quit your PC)
             recv(timer_rx) -> ring => {
@@ -12,6 +12,7 @@ use {
         MouseEvent,
         MouseEventKind,
     },
+    std::path::PathBuf,
     termimad::InputField,
 };
 
@@ -27,8 +28,12 @@ pub struct AppState {
     pub message: Option<Message>,
     /// whether to hide unselected values
     pub hide_values: bool,
+    /// the local list of breached passwords checked by the audit, if any
+    pub breach_list: Option<PathBuf>,
     /// number of drawers created during this session
     pub created_drawers: usize,
+    /// the clickable parts of the title bar, updated when it's drawn
+    pub crumbs: Vec<Crumb>,
     /// tasks in progress or waiting to be launched.
     /// The current or next one is at index 0
     pub pending_tasks: Vec<Task>,
@@ -39,7 +44,13 @@ impl AppState {
         open_closet: OpenCloset,
         args: &Args,
     ) -> Self {
-        let dialog = if args.open && !open_closet.just_created() {
+        let dialog = if let Some(path) = &args.merge {
+            Dialog::Merge(Merge::new(
+                open_closet.path(),
+                Some(path.clone()),
+                args.ancestor.clone(),
+            ))
+        } else if args.open && !open_closet.just_created() {
             Dialog::Password(PasswordDialog::new(
                 PasswordDialogPurpose::OpenDrawer {
                     depth: open_closet.depth(),
@@ -55,7 +66,9 @@ impl AppState {
             dialog,
             message: None,
             hide_values: args.hide,
+            breach_list: args.breaches.clone(),
             created_drawers: 0,
+            crumbs: Vec::new(),
             pending_tasks: Vec::new(),
         }
     }
@@ -178,7 +191,14 @@ impl AppState {
                     self.set_info("string copied to the clipboard, be cautious");
                 }
             } else if let Some(ds) = &self.drawer_state {
-                if let Some(cell) = ds.current_cell() {
+                if let Some(totp) = ds.current_totp() {
+                    let (code, _) = totp.current_code();
+                    if let Err(e) = terminal_clipboard::set_string(code) {
+                        self.set_error(e.to_string());
+                    } else {
+                        self.set_info("one-time code copied to the clipboard");
+                    }
+                } else if let Some(cell) = ds.current_cell() {
                     if let Err(e) = terminal_clipboard::set_string(cell) {
                         self.set_error(e.to_string());
                     } else {
@@ -193,6 +213,52 @@ impl AppState {
         }
     }
 
+    /// Copy the value of a field of the selected entry (the current
+    /// code for a TOTP field)
+    pub fn copy_field(
+        &mut self,
+        field_idx: usize,
+    ) {
+        let Some(ds) = &self.drawer_state else {
+            self.set_error("you can only copy from an open drawer");
+            return;
+        };
+        let Some(field) = ds.current_field(field_idx) else {
+            self.set_error("the selected entry has no such field");
+            return;
+        };
+        let value = match field.totp() {
+            Some(totp) => totp.current_code().0,
+            None => field.value.clone(),
+        };
+        let info = format!(
+            "field '{}' copied to the clipboard, be cautious",
+            field.name
+        );
+        self.copy_string(value, info);
+    }
+
+    /// Copy a string which isn't displayed in an input
+    #[cfg_attr(not(feature = "clipboard"), allow(unused_variables))]
+    fn copy_string<S: Into<String>>(
+        &mut self,
+        s: String,
+        info: S,
+    ) {
+        #[cfg(not(feature = "clipboard"))]
+        {
+            self.set_error("Clipboard feature not enabled at compilation");
+        }
+        #[cfg(feature = "clipboard")]
+        {
+            if let Err(e) = terminal_clipboard::set_string(s) {
+                self.set_error(e.to_string());
+            } else {
+                self.set_info(info);
+            }
+        }
+    }
+
     /// Handle an event asking for cutting from SafeCloset
     pub fn cut(&mut self) {
         #[cfg(not(feature = "clipboard"))]
@@ -294,9 +360,104 @@ impl AppState {
                     self.dialog = Dialog::None;
                 }
             }
+            Dialog::Rekey(rekey) => {
+                rekey.on_mouse_event(mouse_event, double_click);
+                if rekey.is_finished() {
+                    self.end_rekey_dialog();
+                }
+                return Ok(());
+            }
+            Dialog::Merge(merge) => {
+                merge.on_mouse_event(mouse_event, double_click);
+                if merge.is_finished() {
+                    self.end_merge_dialog();
+                }
+                return Ok(());
+            }
+            Dialog::Transfer(transfer) => {
+                transfer.on_mouse_event(mouse_event, double_click, &self.open_closet);
+                if transfer.is_finished() {
+                    self.end_transfer_dialog();
+                }
+                return Ok(());
+            }
+            Dialog::ExtractDrawer(extract_drawer) => {
+                extract_drawer.on_mouse_event(mouse_event, double_click);
+                return Ok(());
+            }
+            Dialog::Relocate(relocate) => {
+                relocate.on_mouse_event(mouse_event, double_click);
+                if relocate.is_finished() {
+                    self.end_relocate_dialog();
+                }
+                return Ok(());
+            }
+            Dialog::PasswordGenerator(generator) => {
+                generator.on_mouse_event(mouse_event, double_click);
+                if generator.is_finished() {
+                    self.end_password_generator_dialog();
+                }
+                return Ok(());
+            }
+            Dialog::FieldsEditor(fields_editor) => {
+                fields_editor.on_mouse_event(mouse_event, double_click);
+                if fields_editor.is_finished() {
+                    self.end_fields_editor_dialog();
+                }
+                return Ok(());
+            }
+            Dialog::ValueHistory(value_history) => {
+                value_history.on_mouse_event(mouse_event, double_click);
+                if value_history.is_finished() {
+                    self.end_value_history_dialog();
+                }
+                return Ok(());
+            }
+            Dialog::TagsEditor(tags_editor) => {
+                tags_editor.on_mouse_event(mouse_event, double_click);
+                return Ok(());
+            }
+            Dialog::TitleEditor(title_editor) => {
+                title_editor.on_mouse_event(mouse_event, double_click);
+                return Ok(());
+            }
+            Dialog::TagFacets(tag_facets) => {
+                tag_facets.on_mouse_event(mouse_event, double_click);
+                if tag_facets.is_finished() {
+                    self.end_tag_facets_dialog();
+                }
+                return Ok(());
+            }
+            Dialog::Attachments(attachments) => {
+                attachments.on_mouse_event(mouse_event, double_click);
+                if attachments.is_finished() {
+                    self.end_attachments_dialog();
+                }
+                return Ok(());
+            }
+            Dialog::ExpiryEditor(expiry_editor) => {
+                expiry_editor.on_mouse_event(mouse_event, double_click);
+                return Ok(());
+            }
+            Dialog::Audit(audit) => {
+                audit.on_mouse_event(mouse_event, double_click);
+                if audit.is_finished() {
+                    self.end_audit_dialog();
+                }
+                return Ok(());
+            }
             Dialog::None => {}
         }
 
+        if mouse_event.row == 0 {
+            if mouse_event.kind == MouseEventKind::Up(MouseButton::Left) {
+                let column = mouse_event.column;
+                if let Some(crumb) = self.crumbs.iter().find(|c| c.contains(column)) {
+                    self.close_down_to(crumb.open_count);
+                }
+            }
+            return Ok(());
+        }
         if let Some(input) = self.drawer_input() {
             // if double-click select_non_space_around
             if input.focused() && double_click && SELECT_NON_SPACE_ON_DOUBLE_CLICK {
@@ -394,6 +555,295 @@ impl AppState {
         }
     }
 
+    /// Close the rekey dialog, queuing the rekey if it was confirmed
+    fn end_rekey_dialog(&mut self) {
+        let mut temp = Dialog::None;
+        std::mem::swap(&mut temp, &mut self.dialog);
+        if let Dialog::Rekey(mut rekey) = temp {
+            if let Some(plan) = rekey.take_confirmed_plan() {
+                self.queue_task(Task::Rekey(plan));
+            }
+        }
+    }
+
+    /// Close the merge dialog, queuing the merge if it was confirmed
+    fn end_merge_dialog(&mut self) {
+        let mut temp = Dialog::None;
+        std::mem::swap(&mut temp, &mut self.dialog);
+        if let Dialog::Merge(mut merge) = temp {
+            if let Some(plan) = merge.take_confirmed_plan() {
+                self.queue_task(Task::Merge(plan));
+            }
+        }
+    }
+
+    /// Close the transfer dialog, giving back the drawer and
+    /// queuing the transfer if it was confirmed
+    fn end_transfer_dialog(&mut self) {
+        let mut temp = Dialog::None;
+        std::mem::swap(&mut temp, &mut self.dialog);
+        if let Dialog::Transfer(transfer) = temp {
+            let (ds, plan) = transfer.take_back_drawer();
+            self.drawer_state = Some(ds);
+            if let Some(plan) = plan {
+                self.queue_task(Task::Transfer(plan));
+            }
+        }
+    }
+
+    /// Close the extraction dialog, queuing the writing of the
+    /// new closet if its path and passphrase were given
+    fn end_extract_drawer_dialog(&mut self) {
+        let mut temp = Dialog::None;
+        std::mem::swap(&mut temp, &mut self.dialog);
+        if let Dialog::ExtractDrawer(mut extract_drawer) = temp {
+            if let Some(request) = extract_drawer.take_request() {
+                self.queue_task(Task::ExtractDrawer(request));
+            }
+        }
+    }
+
+    /// Close the relocation dialog, giving back the drawer and
+    /// queuing its move if a place was chosen
+    fn end_relocate_dialog(&mut self) {
+        let mut temp = Dialog::None;
+        std::mem::swap(&mut temp, &mut self.dialog);
+        if let Dialog::Relocate(relocate) = temp {
+            let (ds, target) = relocate.take_back_drawer();
+            self.drawer_state = Some(ds);
+            if let Some(target) = target {
+                self.queue_task(Task::RelocateDrawer(target));
+            }
+        }
+    }
+
+    /// Close the password generator, keeping the policies it may
+    /// have saved and filling the entry value if a password was chosen
+    fn end_password_generator_dialog(&mut self) {
+        let mut temp = Dialog::None;
+        std::mem::swap(&mut temp, &mut self.dialog);
+        if let Dialog::PasswordGenerator(mut generator) = temp {
+            let Some(ds) = &mut self.drawer_state else {
+                return;
+            };
+            if ds.drawer.content.settings.password_policies != generator.policies() {
+                ds.drawer.content.settings.password_policies = generator.policies().to_vec();
+                ds.increment_edit_count();
+            }
+            let idx = generator.entry_idx();
+            if let Some(password) = generator.take_accepted_password() {
+                if let Some(entry) = ds.drawer.content.entries.get_mut(idx) {
+                    entry.set_value(password, ds.drawer.content.settings.history_depth);
+                    ds.increment_edit_count();
+                    self.set_info("Password generated, save the drawer to keep it");
+                }
+            }
+        }
+    }
+
+    /// Close the fields editor, applying the changes to the entry
+    fn end_fields_editor_dialog(&mut self) {
+        let mut temp = Dialog::None;
+        std::mem::swap(&mut temp, &mut self.dialog);
+        if let Dialog::FieldsEditor(mut fields_editor) = temp {
+            let Some(ds) = &mut self.drawer_state else {
+                return;
+            };
+            let idx = fields_editor.entry_idx();
+            if let Some(fields) = fields_editor.take_changed_fields() {
+                if let Some(entry) = ds.drawer.content.entries.get_mut(idx) {
+                    entry.fields = fields;
+                    entry.touch();
+                    ds.increment_edit_count();
+                }
+            }
+        }
+    }
+
+    /// Close the history dialog, applying the restoration, purges
+    /// and change of depth the user may have asked for
+    fn end_value_history_dialog(&mut self) {
+        let mut temp = Dialog::None;
+        std::mem::swap(&mut temp, &mut self.dialog);
+        if let Dialog::ValueHistory(value_history) = temp {
+            let Some(ds) = &mut self.drawer_state else {
+                return;
+            };
+            let content = &mut ds.drawer.content;
+            let depth = value_history.depth();
+            let mut changed = false;
+            if content.settings.history_depth != depth {
+                content.settings.history_depth = depth;
+                content.truncate_histories(depth);
+                changed = true;
+            }
+            if value_history.purge_drawer() {
+                content.truncate_histories(0);
+                changed = true;
+            }
+            if let Some(entry) = content.entries.get_mut(value_history.entry_idx()) {
+                if entry.history != value_history.history() {
+                    entry.history = value_history.history().to_vec();
+                    changed = true;
+                }
+                if let Some(idx) = value_history.restored() {
+                    entry.restore(idx, depth);
+                    changed = true;
+                }
+            }
+            if changed {
+                ds.increment_edit_count();
+            }
+            if value_history.restored().is_some() {
+                self.set_info("Previous value restored, save the drawer to keep it");
+            }
+        }
+    }
+
+    /// Close the tags editor, applying the tags if they were validated
+    fn end_tags_editor_dialog(&mut self) {
+        let mut temp = Dialog::None;
+        std::mem::swap(&mut temp, &mut self.dialog);
+        if let Dialog::TagsEditor(tags_editor) = temp {
+            let Some(ds) = &mut self.drawer_state else {
+                return;
+            };
+            let Some(tags) = tags_editor.accepted_tags() else {
+                return;
+            };
+            if let Some(entry) = ds.drawer.content.entries.get_mut(tags_editor.entry_idx()) {
+                if entry.set_tags(&tags) {
+                    entry.touch();
+                    ds.increment_edit_count();
+                    ds.update_search();
+                }
+            }
+        }
+    }
+
+    /// Close the title editor, applying the title if it was validated
+    fn end_title_editor_dialog(&mut self) {
+        let mut temp = Dialog::None;
+        std::mem::swap(&mut temp, &mut self.dialog);
+        if let Dialog::TitleEditor(title_editor) = temp {
+            let Some(ds) = &mut self.drawer_state else {
+                return;
+            };
+            let Some(title) = title_editor.accepted_title() else {
+                return;
+            };
+            if ds.drawer.content.set_title(&title) {
+                ds.increment_edit_count();
+            }
+        }
+    }
+
+    /// Save, then close the deepest drawers until only `open_count`
+    /// drawers stay open
+    fn close_down_to(
+        &mut self,
+        open_count: usize,
+    ) {
+        let depth = self.open_closet.depth() + usize::from(self.drawer_state.is_some());
+        if open_count >= depth {
+            return;
+        }
+        self.dialog = Dialog::None;
+        self.queue_task(Task::Save);
+        for _ in open_count..depth {
+            self.queue_task(Task::CloseDrawer);
+        }
+    }
+
+    /// Close the tag facet list, filtering the entries on the chosen tag
+    fn end_tag_facets_dialog(&mut self) {
+        let mut temp = Dialog::None;
+        std::mem::swap(&mut temp, &mut self.dialog);
+        if let Dialog::TagFacets(mut tag_facets) = temp {
+            let Some(ds) = &mut self.drawer_state else {
+                return;
+            };
+            if let Some(facet) = tag_facets.take_chosen() {
+                ds.search.filter_by_tag(facet.tag.as_deref(), &ds.drawer);
+                ds.focus = DrawerFocus::NoneSelected;
+                ds.scroll = 0;
+            }
+        }
+    }
+
+    /// Close the attachments dialog, applying the additions and removals
+    fn end_attachments_dialog(&mut self) {
+        let mut temp = Dialog::None;
+        std::mem::swap(&mut temp, &mut self.dialog);
+        if let Dialog::Attachments(mut attachments) = temp {
+            let Some(ds) = &mut self.drawer_state else {
+                return;
+            };
+            let idx = attachments.entry_idx();
+            if let Some(changed) = attachments.take_changed_attachments() {
+                if let Some(entry) = ds.drawer.content.entries.get_mut(idx) {
+                    entry.attachments = changed;
+                    entry.touch();
+                    ds.increment_edit_count();
+                }
+            }
+        }
+    }
+
+    /// Close the expiry editor, applying the expiry if it was validated
+    fn end_expiry_editor_dialog(&mut self) {
+        let mut temp = Dialog::None;
+        std::mem::swap(&mut temp, &mut self.dialog);
+        if let Dialog::ExpiryEditor(expiry_editor) = temp {
+            let Some(ds) = &mut self.drawer_state else {
+                return;
+            };
+            let Some(expiry) = expiry_editor.accepted_expiry() else {
+                return;
+            };
+            if let Some(entry) = ds.drawer.content.entries.get_mut(expiry_editor.entry_idx()) {
+                if entry.expiry != expiry {
+                    entry.expiry = expiry;
+                    ds.increment_edit_count();
+                    ds.update_search();
+                }
+            }
+        }
+    }
+
+    /// Close the audit dialog, selecting the entry the user chose, if any
+    fn end_audit_dialog(&mut self) {
+        let mut temp = Dialog::None;
+        std::mem::swap(&mut temp, &mut self.dialog);
+        if let Dialog::Audit(audit) = temp {
+            if let (Some(ds), Some(idx)) = (&mut self.drawer_state, audit.jump()) {
+                ds.select_entry(idx);
+            }
+        }
+    }
+
+    /// Tell the user, in the status, about the entries of the
+    /// open drawer which are expired or expiring soon
+    fn report_expiries(&mut self) {
+        let Some(ds) = &self.drawer_state else {
+            return;
+        };
+        let report = ExpiryReport::of(&ds.drawer.content.entries, unix_now());
+        if report.expired > 0 {
+            self.set_error(format!("{report} (hit *!* to list them)"));
+        } else if report.soon > 0 {
+            self.set_info(format!("{report} (hit *!* to list them)"));
+        }
+    }
+
+    /// Tell whether the open drawer displays one-time codes,
+    /// which must be redrawn as time passes
+    pub fn shows_live_codes(&self) -> bool {
+        self.drawer_state
+            .as_ref()
+            .map_or(false, |ds| ds.has_totp_entries())
+    }
+
     pub fn has_pending_task(&self) -> bool {
         !self.pending_tasks.is_empty()
     }
@@ -444,6 +894,7 @@ impl AppState {
                         }
                         self.drawer_state = Some(open_drawer.into());
                         self.dialog = Dialog::None;
+                        self.report_expiries();
                     }
                     None => {
                         self.drawer_state = self
@@ -477,6 +928,112 @@ impl AppState {
                     }
                 }
             }
+            Some(Task::Rekey(plan)) => {
+                let lost = plan.unknown_drawers_count();
+                match time!(self.open_closet.rekey(plan)) {
+                    Ok(()) => {
+                        self.set_info(format!(
+                            "Closet re-encrypted and saved. {lost} unopened drawers were replaced with new decoys."
+                        ));
+                    }
+                    Err(e) => {
+                        self.set_error(e.to_string());
+                    }
+                }
+            }
+            Some(Task::Merge(plan)) => {
+                let merged = time!(plan.into_closet()).and_then(|(closet, report)| {
+                    self.open_closet.replace_root_closet(closet)?;
+                    Ok(report)
+                });
+                match merged {
+                    Ok(report) => {
+                        self.set_info(format!("Closets merged and saved. {report}"));
+                    }
+                    Err(e) => {
+                        self.set_error(e.to_string());
+                    }
+                }
+            }
+            Some(Task::DeleteDrawer) => {
+                if let Some(ds) = self.drawer_state.take() {
+                    match self.open_closet.delete_drawer(&ds.drawer) {
+                        Ok(()) => {
+                            self.drawer_state = self
+                                .open_closet
+                                .take_deepest_open_drawer()
+                                .map(|open_drawer| open_drawer.into());
+                            self.save(true)?;
+                            self.set_info("Drawer deleted and closet saved");
+                        }
+                        Err(e) => {
+                            self.drawer_state = Some(ds);
+                            self.set_error(e.to_string());
+                        }
+                    }
+                }
+            }
+            Some(Task::Transfer(plan)) => {
+                if let Some(mut ds) = self.drawer_state.take() {
+                    match time!(plan.execute(&mut self.open_closet, &mut ds.drawer)) {
+                        Ok(report) => {
+                            self.drawer_state = Some(ds.drawer.into());
+                            self.save(true)?;
+                            self.set_info(report);
+                        }
+                        Err(e) => {
+                            self.drawer_state = Some(ds);
+                            self.set_error(e.to_string());
+                        }
+                    }
+                }
+            }
+            Some(Task::ExtractDrawer(request)) => {
+                if let Some(ds) = &self.drawer_state {
+                    let ExtractDrawerRequest {
+                        path,
+                        password,
+                        deep_drawers,
+                    } = request;
+                    let extracted = time!(self.open_closet.extract_drawer(
+                        &ds.drawer,
+                        &path,
+                        &password,
+                        deep_drawers,
+                    ));
+                    match extracted {
+                        Ok(()) => {
+                            self.set_info(format!(
+                                "Drawer copied into the new closet *{}*",
+                                path.to_string_lossy()
+                            ));
+                        }
+                        Err(e) => {
+                            self.set_error(e.to_string());
+                        }
+                    }
+                }
+            }
+            Some(Task::RelocateDrawer(target)) => {
+                if let Some(ds) = self.drawer_state.take() {
+                    if let Err(e) = self.open_closet.check_relocation(&ds.drawer, &target) {
+                        self.drawer_state = Some(ds);
+                        self.set_error(e.to_string());
+                    } else {
+                        let drawer = time!(self.open_closet.relocate_drawer(ds.drawer, target))?;
+                        let depth = drawer.depth;
+                        self.drawer_state = Some(drawer.into());
+                        if depth == 0 {
+                            self.set_info("Drawer moved to the top level, closet saved");
+                        } else {
+                            self.set_info(format!(
+                                "Drawer moved to level {}, closet saved",
+                                depth + 1
+                            ));
+                        }
+                    }
+                }
+            }
             None => {
                 warn!("unexpected lack of task");
             }
@@ -506,12 +1063,29 @@ impl AppState {
                 }
             }
             Action::NewDrawer => {
-                self.dialog = Dialog::Password(PasswordDialog::new(
+                let mut dialog = PasswordDialog::new(
                     PasswordDialogPurpose::NewDrawer {
                         depth: self.depth(),
                     },
                     false,
-                ));
+                );
+                dialog.set_min_score(self.open_closet.min_password_score());
+                self.dialog = Dialog::Password(dialog);
+            }
+            Action::DeleteDrawer => {
+                if let Some(ds) = &self.drawer_state {
+                    let content = &ds.drawer.content;
+                    let files: usize = content.entries.iter().map(|e| e.attachments.len()).sum();
+                    let mut dialog = PasswordDialog::new(PasswordDialogPurpose::DeleteDrawer, true);
+                    dialog.set_summary(format!(
+                        "*{}* entries, *{}* attached files, and the *{}* drawers it holds \
+                        (deep drawers and decoys alike) will vanish.",
+                        content.entries.len(),
+                        files,
+                        content.closet.drawers.len(),
+                    ));
+                    self.dialog = Dialog::Password(dialog);
+                }
             }
             Action::OpenDrawer => {
                 self.dialog = Dialog::Password(PasswordDialog::new(
@@ -521,6 +1095,34 @@ impl AppState {
                     true,
                 ));
             }
+            Action::TransferEntries => {
+                self.dialog = Dialog::None;
+                self.close_drawer_input(false);
+                let transferable = self.drawer_state.as_ref().map_or(false, |ds| {
+                    ds.focus.line().is_some() || ds.match_count() > 1
+                });
+                if !transferable {
+                    self.set_error("Select an entry, or search entries, to move or copy them");
+                } else if let Some(ds) = self.drawer_state.take() {
+                    self.dialog = Dialog::Transfer(Transfer::new(ds, self.open_closet.depth()));
+                }
+            }
+            Action::ExtractDrawer => {
+                self.dialog = Dialog::None;
+                self.close_drawer_input(false);
+                if let Some(ds) = &self.drawer_state {
+                    let min_score = self.open_closet.min_password_score();
+                    let closet = ds.drawer.content.closet.clone();
+                    self.dialog = Dialog::ExtractDrawer(ExtractDrawer::new(min_score, closet));
+                }
+            }
+            Action::RelocateDrawer => {
+                self.dialog = Dialog::None;
+                self.close_drawer_input(false);
+                if let Some(ds) = self.drawer_state.take() {
+                    self.dialog = Dialog::Relocate(Relocate::new(ds, self.open_closet.depth()));
+                }
+            }
             Action::Import => {
                 if let Some(ds) = self.drawer_state.take() {
                     self.dialog = Dialog::Import(Import::new(self.open_closet.path().into(), ds));
@@ -533,6 +1135,20 @@ impl AppState {
                     &self.open_closet.root_closet().comments,
                 ));
             }
+            Action::RekeyCloset => {
+                if self.drawer_state.is_none() {
+                    self.dialog = Dialog::Rekey(Rekey::default());
+                } else {
+                    warn!("What ? How was this option chosen ?");
+                }
+            }
+            Action::MergeCloset => {
+                if self.drawer_state.is_none() {
+                    self.dialog = Dialog::Merge(Merge::new(self.open_closet.path(), None, None));
+                } else {
+                    warn!("What ? How was this option chosen ?");
+                }
+            }
             Action::SaveDrawer => {
                 if self.drawer_state.is_some() {
                     self.dialog = Dialog::None;
@@ -547,6 +1163,11 @@ impl AppState {
                     ds.sort();
                 }
             }
+            Action::SortByAge => {
+                if let Some(ds) = &mut self.drawer_state {
+                    ds.sort_by_age();
+                }
+            }
             Action::CloseShallowDrawer | Action::CloseDeepDrawer => {
                 self.dialog = Dialog::None;
                 self.queue_task(Task::Save);
@@ -585,6 +1206,26 @@ impl AppState {
                     import.toggle_hide_chars();
                     return Ok(CmdResult::Stay);
                 }
+                if let Dialog::Rekey(rekey) = &mut self.dialog {
+                    rekey.toggle_hide_chars();
+                    return Ok(CmdResult::Stay);
+                }
+                if let Dialog::Merge(merge) = &mut self.dialog {
+                    merge.toggle_hide_chars();
+                    return Ok(CmdResult::Stay);
+                }
+                if let Dialog::Transfer(transfer) = &mut self.dialog {
+                    transfer.toggle_hide_chars();
+                    return Ok(CmdResult::Stay);
+                }
+                if let Dialog::ExtractDrawer(extract_drawer) = &mut self.dialog {
+                    extract_drawer.toggle_hide_chars();
+                    return Ok(CmdResult::Stay);
+                }
+                if let Dialog::Relocate(relocate) = &mut self.dialog {
+                    relocate.toggle_hide_chars();
+                    return Ok(CmdResult::Stay);
+                }
                 self.dialog = Dialog::None;
                 if let Some(ds) = &mut self.drawer_state {
                     ds.drawer.content.settings.hide_values ^= true;
@@ -596,6 +1237,12 @@ impl AppState {
                     ds.drawer.content.settings.values_as_markdown ^= true;
                 }
             }
+            Action::ToggleAges => {
+                self.dialog = Dialog::None;
+                if let Some(ds) = &mut self.drawer_state {
+                    ds.drawer.content.settings.show_ages ^= true;
+                }
+            }
             Action::OpenAllValues | Action::CloseAllValues => {
                 self.dialog = Dialog::None;
                 if let Some(ds) = &mut self.drawer_state {
@@ -662,12 +1309,162 @@ impl AppState {
                     ds.focus = SearchEdit { previous_idx };
                 }
             }
+            Action::GeneratePassword => {
+                self.dialog = Dialog::None;
+                self.close_drawer_input(false);
+                if let Some(ds) = &self.drawer_state {
+                    if let Some(idx) = ds.focus.line().and_then(|line| ds.listed_entry_idx(line)) {
+                        self.dialog = Dialog::PasswordGenerator(PasswordGenerator::new(
+                            ds.drawer.content.settings.password_policies.clone(),
+                            idx,
+                        ));
+                    } else {
+                        self.set_error("Select an entry to generate its value");
+                    }
+                }
+            }
+            Action::ShowValueHistory => {
+                self.dialog = Dialog::None;
+                self.close_drawer_input(false);
+                if let Some(ds) = &self.drawer_state {
+                    let selected = ds
+                        .focus
+                        .line()
+                        .and_then(|line| ds.listed_entry_idx(line))
+                        .and_then(|idx| ds.drawer.content.entries.get(idx).map(|e| (idx, e)));
+                    if let Some((idx, entry)) = selected {
+                        self.dialog = Dialog::ValueHistory(ValueHistory::new(
+                            entry,
+                            idx,
+                            &ds.drawer.content.settings,
+                        ));
+                    } else {
+                        self.set_error("Select an entry to see its previous values");
+                    }
+                }
+            }
+            Action::EditFields => {
+                self.dialog = Dialog::None;
+                self.close_drawer_input(false);
+                if let Some(ds) = &self.drawer_state {
+                    let selected = ds
+                        .focus
+                        .line()
+                        .and_then(|line| ds.listed_entry_idx(line))
+                        .and_then(|idx| ds.drawer.content.entries.get(idx).map(|e| (idx, e)));
+                    if let Some((idx, entry)) = selected {
+                        self.dialog = Dialog::FieldsEditor(FieldsEditor::new(entry, idx));
+                    } else {
+                        self.set_error("Select an entry to edit its fields");
+                    }
+                }
+            }
+            Action::EditTags => {
+                self.dialog = Dialog::None;
+                self.close_drawer_input(false);
+                if let Some(ds) = &self.drawer_state {
+                    let selected = ds
+                        .focus
+                        .line()
+                        .and_then(|line| ds.listed_entry_idx(line))
+                        .and_then(|idx| ds.drawer.content.entries.get(idx).map(|e| (idx, e)));
+                    if let Some((idx, entry)) = selected {
+                        self.dialog =
+                            Dialog::TagsEditor(TagsEditor::new(entry, idx, &ds.drawer.content));
+                    } else {
+                        self.set_error("Select an entry to edit its tags");
+                    }
+                }
+            }
+            Action::EditDrawerTitle => {
+                self.dialog = Dialog::None;
+                self.close_drawer_input(false);
+                if let Some(ds) = &self.drawer_state {
+                    self.dialog = Dialog::TitleEditor(TitleEditor::new(&ds.drawer.content));
+                }
+            }
+            Action::ManageAttachments => {
+                self.dialog = Dialog::None;
+                self.close_drawer_input(false);
+                if let Some(ds) = &self.drawer_state {
+                    let selected = ds
+                        .focus
+                        .line()
+                        .and_then(|line| ds.listed_entry_idx(line))
+                        .and_then(|idx| ds.drawer.content.entries.get(idx).map(|e| (idx, e)));
+                    if let Some((idx, entry)) = selected {
+                        self.dialog = Dialog::Attachments(Attachments::new(entry, idx));
+                    } else {
+                        self.set_error("Select an entry to manage its attached files");
+                    }
+                }
+            }
+            Action::EditExpiry => {
+                self.dialog = Dialog::None;
+                self.close_drawer_input(false);
+                if let Some(ds) = &self.drawer_state {
+                    let selected = ds
+                        .focus
+                        .line()
+                        .and_then(|line| ds.listed_entry_idx(line))
+                        .and_then(|idx| ds.drawer.content.entries.get(idx).map(|e| (idx, e)));
+                    if let Some((idx, entry)) = selected {
+                        self.dialog = Dialog::ExpiryEditor(ExpiryEditor::new(entry, idx));
+                    } else {
+                        self.set_error("Select an entry to set its expiry");
+                    }
+                }
+            }
+            Action::AuditSecrets => {
+                self.dialog = Dialog::None;
+                self.close_drawer_input(false);
+                if let Some(ds) = &self.drawer_state {
+                    let audit = self
+                        .breach_list
+                        .as_deref()
+                        .map(BreachList::open)
+                        .transpose()
+                        .and_then(|mut breach_list| {
+                            Audit::new(
+                                &ds.drawer,
+                                self.open_closet.open_drawers(),
+                                breach_list.as_mut(),
+                            )
+                        });
+                    match audit {
+                        Ok(audit) => {
+                            self.dialog = Dialog::Audit(audit);
+                        }
+                        Err(e) => {
+                            self.set_error(format!("Audit failed: {e}"));
+                        }
+                    }
+                }
+            }
+            Action::ListDueEntries => {
+                self.dialog = Dialog::None;
+                self.close_drawer_input(false);
+                if let Some(ds) = &mut self.drawer_state {
+                    ds.search.filter_due(&ds.drawer);
+                    ds.focus = DrawerFocus::NoneSelected;
+                    ds.scroll = 0;
+                }
+            }
+            Action::FilterByTag => {
+                self.dialog = Dialog::None;
+                self.close_drawer_input(false);
+                if let Some(ds) = &self.drawer_state {
+                    let searched = ds.search.searched_tag();
+                    self.dialog =
+                        Dialog::TagFacets(TagFacets::new(&ds.drawer.content, searched.as_deref()));
+                }
+            }
             Action::OpenPasswordChangeDialog => {
                 debug!("opening pwd change dialog");
-                self.dialog = Dialog::Password(PasswordDialog::new(
-                    PasswordDialogPurpose::ChangeDrawerPassword,
-                    false,
-                ));
+                let mut dialog =
+                    PasswordDialog::new(PasswordDialogPurpose::ChangeDrawerPassword, false);
+                dialog.set_min_score(self.open_closet.min_password_score());
+                self.dialog = Dialog::Password(dialog);
             }
         }
         Ok(CmdResult::Stay)
@@ -690,6 +1487,7 @@ impl AppState {
             }
             menu.add_action(Action::ToggleHiding);
             menu.add_action(Action::ToggleMarkdown);
+            menu.add_action(Action::ToggleAges);
             if ds.drawer.content.settings.open_all_values {
                 menu.add_action(Action::CloseAllValues);
             } else {
@@ -699,10 +1497,27 @@ impl AppState {
                 menu.add_action(Action::GroupMatchingEntries);
             }
             menu.add_action(Action::Sort);
+            menu.add_action(Action::SortByAge);
+            menu.add_action(Action::GeneratePassword);
+            menu.add_action(Action::EditFields);
+            menu.add_action(Action::ShowValueHistory);
+            menu.add_action(Action::EditTags);
+            menu.add_action(Action::ManageAttachments);
+            menu.add_action(Action::FilterByTag);
+            menu.add_action(Action::EditExpiry);
+            menu.add_action(Action::ListDueEntries);
+            menu.add_action(Action::AuditSecrets);
+            menu.add_action(Action::EditDrawerTitle);
             menu.add_action(Action::OpenPasswordChangeDialog);
+            menu.add_action(Action::TransferEntries);
+            menu.add_action(Action::ExtractDrawer);
+            menu.add_action(Action::RelocateDrawer);
+            menu.add_action(Action::DeleteDrawer);
             menu.add_action(Action::Import);
         } else {
             menu.add_action(Action::EditClosetComments);
+            menu.add_action(Action::RekeyCloset);
+            menu.add_action(Action::MergeCloset);
         }
         menu.add_action(Action::Help);
         menu.add_action(Action::Quit);
@@ -761,6 +1576,125 @@ impl AppState {
                     return Ok(CmdResult::Stay);
                 }
             }
+            Dialog::Relocate(relocate) => {
+                if relocate.on_key(key, &self.open_closet) {
+                    if relocate.is_finished() {
+                        self.end_relocate_dialog();
+                    }
+                    return Ok(CmdResult::Stay);
+                }
+            }
+            Dialog::ExtractDrawer(extract_drawer) => {
+                if extract_drawer.on_key(key) {
+                    if extract_drawer.is_finished() {
+                        self.end_extract_drawer_dialog();
+                    }
+                    return Ok(CmdResult::Stay);
+                }
+            }
+            Dialog::Transfer(transfer) => {
+                if transfer.on_key(key, &self.open_closet) {
+                    if transfer.is_finished() {
+                        self.end_transfer_dialog();
+                    }
+                    return Ok(CmdResult::Stay);
+                }
+            }
+            Dialog::Rekey(rekey) => {
+                if rekey.on_key(key, self.open_closet.root_closet()) {
+                    if rekey.is_finished() {
+                        self.end_rekey_dialog();
+                    }
+                    return Ok(CmdResult::Stay);
+                }
+            }
+            Dialog::Merge(merge) => {
+                if merge.on_key(key, self.open_closet.root_closet()) {
+                    if merge.is_finished() {
+                        self.end_merge_dialog();
+                    }
+                    return Ok(CmdResult::Stay);
+                }
+            }
+            Dialog::PasswordGenerator(generator) => {
+                if generator.on_key(key) {
+                    if generator.is_finished() {
+                        self.end_password_generator_dialog();
+                    }
+                    return Ok(CmdResult::Stay);
+                }
+            }
+            Dialog::FieldsEditor(fields_editor) => {
+                if fields_editor.on_key(key) {
+                    if fields_editor.is_finished() {
+                        self.end_fields_editor_dialog();
+                    }
+                    return Ok(CmdResult::Stay);
+                }
+            }
+            Dialog::ValueHistory(value_history) => {
+                if value_history.on_key(key) {
+                    let copy_request = value_history.take_copy_request();
+                    if value_history.is_finished() {
+                        self.end_value_history_dialog();
+                    }
+                    if let Some(value) = copy_request {
+                        self.copy_string(
+                            value,
+                            "previous value copied to the clipboard, be cautious",
+                        );
+                    }
+                    return Ok(CmdResult::Stay);
+                }
+            }
+            Dialog::TitleEditor(title_editor) => {
+                if title_editor.on_key(key) {
+                    if title_editor.is_finished() {
+                        self.end_title_editor_dialog();
+                    }
+                    return Ok(CmdResult::Stay);
+                }
+            }
+            Dialog::TagsEditor(tags_editor) => {
+                if tags_editor.on_key(key) {
+                    if tags_editor.is_finished() {
+                        self.end_tags_editor_dialog();
+                    }
+                    return Ok(CmdResult::Stay);
+                }
+            }
+            Dialog::TagFacets(tag_facets) => {
+                if tag_facets.on_key(key) {
+                    if tag_facets.is_finished() {
+                        self.end_tag_facets_dialog();
+                    }
+                    return Ok(CmdResult::Stay);
+                }
+            }
+            Dialog::Attachments(attachments) => {
+                if attachments.on_key(key) {
+                    if attachments.is_finished() {
+                        self.end_attachments_dialog();
+                    }
+                    return Ok(CmdResult::Stay);
+                }
+            }
+            Dialog::ExpiryEditor(expiry_editor) => {
+                if expiry_editor.on_key(key) {
+                    if expiry_editor.is_finished() {
+                        self.end_expiry_editor_dialog();
+                    }
+                    return Ok(CmdResult::Stay);
+                }
+            }
+            Dialog::Audit(audit) => {
+                if audit.on_key(key) {
+                    if audit.is_finished() {
+                        self.end_audit_dialog();
+                    }
+                    return Ok(CmdResult::Stay);
+                }
+            }
             Dialog::None => {}
         }
 
@@ -768,6 +1702,13 @@ impl AppState {
             return self.on_action(action);
         }
 
+        if let Some(field_idx) = field_copy_index(key) {
+            if self.dialog.is_none() {
+                self.copy_field(field_idx);
+            }
+            return Ok(CmdResult::Stay);
+        }
+
         if key == key!(enter) {
             match &mut self.dialog {
                 Dialog::Password(password_dialog) => {
@@ -782,6 +1723,21 @@ impl AppState {
                         PasswordDialogPurpose::ChangeDrawerPassword => {
                             self.queue_task(Task::ChangePassword(password));
                         }
+                        PasswordDialogPurpose::RekeyCloset => {} // managed in the rekey dialog
+                        PasswordDialogPurpose::MergeClosets => {} // managed in the merge dialog
+                        PasswordDialogPurpose::ExtractDeepDrawers => {} // managed in the extraction dialog
+                        PasswordDialogPurpose::DeleteDrawer => {
+                            let confirmed = self
+                                .drawer_state
+                                .as_ref()
+                                .map_or(false, |ds| ds.drawer.has_password(&password));
+                            if confirmed {
+                                self.dialog = Dialog::None;
+                                self.queue_task(Task::DeleteDrawer);
+                            } else {
+                                self.set_error("This isn't the passphrase of this drawer");
+                            }
+                        }
                     }
                 }
                 Dialog::Help(_) => {}
@@ -794,7 +1750,21 @@ impl AppState {
                 Dialog::None => {
                     self.close_drawer_input(false); // if there's an entry input
                 }
-                Dialog::Import(_) => {} // managed in the dialog
+                Dialog::Import(_) => {}            // managed in the dialog
+                Dialog::Rekey(_) => {}             // managed in the dialog
+                Dialog::Merge(_) => {}             // managed in the dialog
+                Dialog::PasswordGenerator(_) => {} // managed in the dialog
+                Dialog::FieldsEditor(_) => {}      // managed in the dialog
+                Dialog::ValueHistory(_) => {}      // managed in the dialog
+                Dialog::TagsEditor(_) => {}        // managed in the dialog
+                Dialog::TitleEditor(_) => {}       // managed in the dialog
+                Dialog::TagFacets(_) => {}         // managed in the dialog
+                Dialog::Attachments(_) => {}       // managed in the dialog
+                Dialog::ExpiryEditor(_) => {}      // managed in the dialog
+                Dialog::Audit(_) => {}             // managed in the dialog
+                Dialog::Transfer(_) => {}          // managed in the dialog
+                Dialog::ExtractDrawer(_) => {}     // managed in the dialog
+                Dialog::Relocate(_) => {}          // managed in the dialog
             }
             return Ok(CmdResult::Stay);
         }
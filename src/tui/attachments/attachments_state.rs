@@ -0,0 +1,274 @@
+use {
+    super::*,
+    crate::{
+        core::*,
+        tui::*,
+    },
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+        key,
+    },
+};
+
+/// Number of hexadecimal digits of the hash displayed in the list
+const HASH_PREFIX_LENGTH: usize = 16;
+
+#[allow(clippy::large_enum_variant)]
+pub enum AttachmentsStep {
+    Browse(Menu<AttachmentsItem>),
+    /// choosing the file to attach
+    Attach(FileSelector),
+    /// choosing the path of the extracted file
+    Extract {
+        idx: usize,
+        selector: FileSelector,
+    },
+    Finished,
+}
+
+pub struct AttachmentsState {
+    pub step: AttachmentsStep,
+    /// name of the entry, for the dialog's introduction
+    entry_name: String,
+    /// index of the entry whose attachments are managed
+    pub entry_idx: usize,
+    pub attachments: Vec<Attachment>,
+    /// whether attachments were added or removed
+    pub changed: bool,
+    pub message: Option<String>,
+}
+
+impl AttachmentsState {
+    pub fn new(
+        entry: &Entry,
+        entry_idx: usize,
+    ) -> Self {
+        let mut state = Self {
+            step: AttachmentsStep::Finished,
+            entry_name: entry.name.clone(),
+            entry_idx,
+            attachments: entry.attachments.clone(),
+            changed: false,
+            message: None,
+        };
+        state.browse(0);
+        state
+    }
+    pub fn is_finished(&self) -> bool {
+        matches!(self.step, AttachmentsStep::Finished)
+    }
+    /// Rebuild the menu with the given item selected
+    fn browse(
+        &mut self,
+        selection: usize,
+    ) {
+        let mut menu = Menu::new();
+        if self.attachments.is_empty() {
+            menu.set_intro(format!("No file attached to *{}*", &self.entry_name));
+        } else {
+            menu.set_intro(format!(
+                "Files attached to *{}*, with their type, size and SHA-256 hash:",
+                &self.entry_name,
+            ));
+        }
+        for (idx, attachment) in self.attachments.iter().enumerate() {
+            let hash = attachment.sha256();
+            let label = format!(
+                "{}  {}  {}  {}…",
+                attachment.name,
+                attachment.mime_type,
+                format_size(attachment.size()),
+                &hash[..HASH_PREFIX_LENGTH],
+            );
+            menu.add_item(
+                AttachmentsItem {
+                    action: AttachmentsAction::Extract(idx),
+                    label,
+                },
+                None,
+            );
+        }
+        menu.add_item(
+            AttachmentsItem {
+                action: AttachmentsAction::Attach,
+                label: "Attach a file".to_string(),
+            },
+            Some(key!(n)),
+        );
+        menu.add_item(
+            AttachmentsItem {
+                action: AttachmentsAction::Done,
+                label: "Done".to_string(),
+            },
+            None,
+        );
+        menu.state.select(selection);
+        self.step = AttachmentsStep::Browse(menu);
+    }
+    fn on_item(
+        &mut self,
+        action: AttachmentsAction,
+    ) {
+        self.message = None;
+        match action {
+            AttachmentsAction::Extract(idx) => {
+                if let Some(attachment) = self.attachments.get(idx) {
+                    let mut selector = FileSelector::new(
+                        format!(
+                            "Enter the path of the file to write *{}* into.",
+                            attachment.name
+                        ),
+                        FileType::NewFile,
+                    );
+                    selector.set_path(&attachment.name);
+                    self.step = AttachmentsStep::Extract { idx, selector };
+                }
+            }
+            AttachmentsAction::Attach => {
+                let selector = FileSelector::new(
+                    "Enter the path of the file to attach.".to_string(),
+                    FileType::File,
+                );
+                self.step = AttachmentsStep::Attach(selector);
+            }
+            AttachmentsAction::Done => {
+                self.step = AttachmentsStep::Finished;
+            }
+        }
+    }
+    fn on_file_to_attach(
+        &mut self,
+        selector: FileSelector,
+    ) {
+        let Some(path) = selector.get_selected_file() else {
+            self.step = AttachmentsStep::Attach(selector);
+            return;
+        };
+        match Attachment::from_path(path) {
+            Ok(attachment) => {
+                self.message = Some(format!(
+                    "*{}* attached, save the drawer to keep it",
+                    attachment.name
+                ));
+                self.attachments.push(attachment);
+                self.changed = true;
+                self.browse(self.attachments.len() - 1);
+            }
+            Err(e) => {
+                self.message = Some(e.to_string());
+                self.step = AttachmentsStep::Attach(selector);
+            }
+        }
+    }
+    fn on_extraction_path(
+        &mut self,
+        idx: usize,
+        selector: FileSelector,
+    ) {
+        let Some(path) = selector.get_selected_file() else {
+            self.step = AttachmentsStep::Extract { idx, selector };
+            return;
+        };
+        match self.attachments[idx].extract_to(path) {
+            Ok(()) => {
+                self.message = Some(format!("File written to *{}*", path.to_string_lossy()));
+                self.browse(idx);
+            }
+            Err(e) => {
+                self.message = Some(e.to_string());
+                self.step = AttachmentsStep::Extract { idx, selector };
+            }
+        }
+    }
+    pub fn apply_key_event(
+        &mut self,
+        key: KeyCombination,
+    ) -> bool {
+        let mut step = AttachmentsStep::Finished;
+        std::mem::swap(&mut step, &mut self.step);
+        match step {
+            AttachmentsStep::Browse(mut menu) => {
+                if key == key!(esc) {
+                    return true;
+                }
+                let selection = menu.state.selection;
+                if key == key!(d) && selection < self.attachments.len() {
+                    let attachment = self.attachments.remove(selection);
+                    self.message = Some(format!("*{}* removed", attachment.name));
+                    self.changed = true;
+                    self.browse(selection.min(self.attachments.len()));
+                    return true;
+                }
+                match menu.state.on_key(key) {
+                    Some(item) => {
+                        self.step = AttachmentsStep::Browse(menu);
+                        self.on_item(item.action);
+                        true
+                    }
+                    None => {
+                        self.step = AttachmentsStep::Browse(menu);
+                        false
+                    }
+                }
+            }
+            AttachmentsStep::Attach(mut selector) => {
+                self.message = None;
+                if key == key!(esc) {
+                    self.browse(self.attachments.len());
+                } else if key == key!(enter) {
+                    self.on_file_to_attach(selector);
+                } else {
+                    selector.apply_key_event(key);
+                    self.step = AttachmentsStep::Attach(selector);
+                }
+                true
+            }
+            AttachmentsStep::Extract { idx, mut selector } => {
+                self.message = None;
+                if key == key!(esc) {
+                    self.browse(idx);
+                } else if key == key!(enter) {
+                    self.on_extraction_path(idx, selector);
+                } else {
+                    selector.apply_key_event(key);
+                    self.step = AttachmentsStep::Extract { idx, selector };
+                }
+                true
+            }
+            AttachmentsStep::Finished => true,
+        }
+    }
+    /// handle a mouse event
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        match &mut self.step {
+            AttachmentsStep::Browse(menu) => {
+                if let Some(item) = menu.state.on_mouse_event(mouse_event, double_click) {
+                    self.on_item(item.action);
+                }
+            }
+            AttachmentsStep::Attach(selector) | AttachmentsStep::Extract { selector, .. } => {
+                selector.on_mouse_event(mouse_event, double_click);
+            }
+            AttachmentsStep::Finished => {}
+        }
+    }
+    pub fn status(&self) -> &str {
+        if let Some(message) = &self.message {
+            return message;
+        }
+        match &self.step {
+            AttachmentsStep::Browse(_) => {
+                "*enter* to extract, *n* to attach a file, *d* to remove, *esc* to close"
+            }
+            AttachmentsStep::Attach(selector) | AttachmentsStep::Extract { selector, .. } => {
+                selector.get_message()
+            }
+            AttachmentsStep::Finished => "",
+        }
+    }
+}
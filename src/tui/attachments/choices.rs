@@ -0,0 +1,25 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttachmentsAction {
+    /// extract the attachment, by index
+    Extract(usize),
+    Attach,
+    Done,
+}
+
+/// An item of the attachments menu
+#[derive(Debug, Clone)]
+pub struct AttachmentsItem {
+    pub action: AttachmentsAction,
+    pub label: String,
+}
+
+impl fmt::Display for AttachmentsItem {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
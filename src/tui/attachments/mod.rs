@@ -0,0 +1,92 @@
+mod attachments_state;
+mod choices;
+
+pub use {
+    attachments_state::*,
+    choices::*,
+};
+
+use {
+    super::*,
+    crate::core::*,
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+    },
+    termimad::Area,
+};
+
+/// A dialog managing the files attached to an entry: attaching
+/// new ones, extracting or removing them
+pub struct Attachments {
+    state: AttachmentsState,
+    available_area: Area,
+}
+
+impl Attachments {
+    pub fn new(
+        entry: &Entry,
+        entry_idx: usize,
+    ) -> Self {
+        let state = AttachmentsState::new(entry, entry_idx);
+        Self {
+            state,
+            available_area: Area::default(),
+        }
+    }
+    pub fn on_key(
+        &mut self,
+        key: KeyCombination,
+    ) -> bool {
+        self.state.apply_key_event(key)
+    }
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        self.state.on_mouse_event(mouse_event, double_click);
+    }
+    pub fn set_available_area(
+        &mut self,
+        area: Area,
+    ) {
+        self.available_area = area;
+    }
+    pub fn draw(
+        &mut self,
+        w: &mut W,
+        app_skin: &AppSkin,
+    ) -> Result<(), SafeClosetError> {
+        let area = self.available_area.clone();
+        match &mut self.state.step {
+            AttachmentsStep::Browse(menu) => {
+                menu.set_available_area(area);
+                menu.draw(w, app_skin)?;
+            }
+            AttachmentsStep::Attach(selector) | AttachmentsStep::Extract { selector, .. } => {
+                selector.view.set_available_area(area);
+                selector.draw(w, app_skin)?;
+            }
+            AttachmentsStep::Finished => {}
+        }
+        Ok(())
+    }
+    pub fn status(&self) -> &str {
+        self.state.status()
+    }
+    pub fn is_finished(&self) -> bool {
+        self.state.is_finished()
+    }
+    pub fn entry_idx(&self) -> usize {
+        self.state.entry_idx
+    }
+    /// Return the attachments, if the user added or removed some
+    pub fn take_changed_attachments(&mut self) -> Option<Vec<Attachment>> {
+        if self.state.changed {
+            Some(std::mem::take(&mut self.state.attachments))
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use {
+    super::*,
+    crate::{
+        core::*,
+        tui::menu::*,
+    },
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+        key,
+    },
+};
+
+pub struct AuditState {
+    pub menu: Menu<AuditItem>,
+    /// the findings in the current drawer
+    drawer_findings: Vec<AuditFinding>,
+    /// the findings in all open drawers, None when
+    /// there's no other open drawer
+    all_findings: Option<Vec<AuditFinding>>,
+    /// whether the findings of all open drawers are listed
+    all_drawers: bool,
+    /// depth of the current drawer
+    depth: usize,
+    /// index of the entry to select in the drawer
+    pub jump: Option<usize>,
+    pub finished: bool,
+    message: Option<&'static str>,
+}
+
+impl AuditState {
+    pub fn new(
+        drawer_findings: Vec<AuditFinding>,
+        all_findings: Option<Vec<AuditFinding>>,
+        depth: usize,
+    ) -> Self {
+        let mut state = Self {
+            menu: Menu::new(),
+            drawer_findings,
+            all_findings,
+            all_drawers: false,
+            depth,
+            jump: None,
+            finished: false,
+            message: None,
+        };
+        state.rebuild(0);
+        state
+    }
+    fn findings(&self) -> &[AuditFinding] {
+        match &self.all_findings {
+            Some(all_findings) if self.all_drawers => all_findings,
+            _ => &self.drawer_findings,
+        }
+    }
+    /// Rebuild the menu with the given item selected
+    fn rebuild(
+        &mut self,
+        selection: usize,
+    ) {
+        let mut menu = Menu::new();
+        let findings = self.findings();
+        let scope = if self.all_drawers {
+            "the open drawers"
+        } else {
+            "this drawer"
+        };
+        if findings.is_empty() {
+            menu.set_intro(format!("No problem found in {scope}"));
+        } else {
+            menu.set_intro(format!(
+                "{} problem{} found in {scope}, hit *enter* to go to the entry:",
+                findings.len(),
+                if findings.len() > 1 { "s" } else { "" },
+            ));
+        }
+        let mut items = Vec::new();
+        for (idx, finding) in findings.iter().enumerate() {
+            let mut label = String::new();
+            if finding.location.depth != self.depth {
+                label.push_str("[upper drawer] ");
+            }
+            label.push_str(&finding.name);
+            if let Some(field) = &finding.field {
+                label.push_str(&format!(" ({field})"));
+            }
+            label.push_str(&format!(": {}", finding.issue));
+            items.push(AuditItem {
+                action: AuditAction::Finding(idx),
+                label,
+            });
+        }
+        if self.all_findings.is_some() {
+            let label = if self.all_drawers {
+                "Audit only this drawer"
+            } else {
+                "Also audit the upper open drawers"
+            };
+            items.push(AuditItem {
+                action: AuditAction::ToggleScope,
+                label: label.to_string(),
+            });
+        }
+        items.push(AuditItem {
+            action: AuditAction::Close,
+            label: "Close".to_string(),
+        });
+        for item in items {
+            menu.add_item(item, None);
+        }
+        menu.state.select(selection);
+        self.menu = menu;
+    }
+    fn on_item(
+        &mut self,
+        action: AuditAction,
+    ) {
+        self.message = None;
+        match action {
+            AuditAction::Finding(idx) => {
+                let location = self.findings()[idx].location;
+                if location.depth == self.depth {
+                    self.jump = Some(location.idx);
+                    self.finished = true;
+                } else {
+                    self.message = Some("This entry is in an upper drawer, hit *^u* to go up");
+                }
+            }
+            AuditAction::ToggleScope => {
+                self.all_drawers ^= true;
+                let selection = self.findings().len();
+                self.rebuild(selection);
+            }
+            AuditAction::Close => {
+                self.finished = true;
+            }
+        }
+    }
+    pub fn apply_key_event(
+        &mut self,
+        key: KeyCombination,
+    ) -> bool {
+        if key == key!(esc) {
+            self.finished = true;
+            return true;
+        }
+        match self.menu.state.on_key(key) {
+            Some(item) => {
+                self.on_item(item.action);
+                true
+            }
+            None => false,
+        }
+    }
+    /// handle a mouse event
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        if let Some(item) = self.menu.state.on_mouse_event(mouse_event, double_click) {
+            self.on_item(item.action);
+        }
+    }
+    pub fn status(&self) -> &'static str {
+        self.message
+            .unwrap_or("Hit *enter* to go to the selected entry, *esc* to close")
+    }
+}
@@ -0,0 +1,25 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    /// a finding, by index in the listed findings
+    Finding(usize),
+    ToggleScope,
+    Close,
+}
+
+/// An item of the audit menu
+#[derive(Debug, Clone)]
+pub struct AuditItem {
+    pub action: AuditAction,
+    pub label: String,
+}
+
+impl fmt::Display for AuditItem {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
@@ -0,0 +1,94 @@
+mod audit_state;
+mod choices;
+
+pub use {
+    audit_state::*,
+    choices::*,
+};
+
+use {
+    super::*,
+    crate::core::*,
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+    },
+    termimad::Area,
+};
+
+/// A dialog listing the problems of the secrets of the open
+/// drawer: reused, nearly duplicated, short or weak secrets,
+/// and empty entries
+pub struct Audit {
+    state: AuditState,
+}
+
+impl Audit {
+    /// Audit the current drawer and, when there are some, the
+    /// upper open drawers, looking for the secrets in the list
+    /// of breached passwords if one is given
+    pub fn new(
+        drawer: &OpenDrawer,
+        upper_drawers: &[OpenDrawer],
+        mut breach_list: Option<&mut BreachList>,
+    ) -> Result<Self, CoreError> {
+        let mut audit = |drawers: &[(usize, &[Entry])]| -> Result<Vec<AuditFinding>, CoreError> {
+            let mut findings = audit_drawers(drawers);
+            if let Some(breach_list) = breach_list.as_deref_mut() {
+                findings.append(&mut check_breaches(drawers, breach_list)?);
+                findings.sort_by_key(|finding| (finding.location.depth, finding.location.idx));
+            }
+            Ok(findings)
+        };
+        let current = (drawer.depth, drawer.content.entries.as_slice());
+        let drawer_findings = audit(&[current])?;
+        let all_findings = if upper_drawers.is_empty() {
+            None
+        } else {
+            let mut drawers: Vec<(usize, &[Entry])> = upper_drawers
+                .iter()
+                .map(|d| (d.depth, d.content.entries.as_slice()))
+                .collect();
+            drawers.push(current);
+            Some(audit(&drawers)?)
+        };
+        let state = AuditState::new(drawer_findings, all_findings, drawer.depth);
+        Ok(Self { state })
+    }
+    pub fn on_key(
+        &mut self,
+        key: KeyCombination,
+    ) -> bool {
+        self.state.apply_key_event(key)
+    }
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        self.state.on_mouse_event(mouse_event, double_click);
+    }
+    pub fn set_available_area(
+        &mut self,
+        area: Area,
+    ) {
+        self.state.menu.set_available_area(area);
+    }
+    pub fn draw(
+        &mut self,
+        w: &mut W,
+        app_skin: &AppSkin,
+    ) -> Result<(), SafeClosetError> {
+        self.state.menu.draw(w, app_skin)
+    }
+    pub fn status(&self) -> &'static str {
+        self.state.status()
+    }
+    pub fn is_finished(&self) -> bool {
+        self.state.finished
+    }
+    /// Return the index of the entry the user wants to go to, if any
+    pub fn jump(&self) -> Option<usize> {
+        self.state.jump
+    }
+}
@@ -0,0 +1,213 @@
+use {
+    super::*,
+    crate::import::{
+        ConflictResolution,
+        ImportSet,
+    },
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+        key,
+    },
+    std::fmt,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictAction {
+    /// a conflict, by index in the import set
+    Conflict(usize),
+    /// a resolution to apply to all conflicts
+    All(ConflictResolution),
+    Done,
+}
+
+/// An item of the list of conflicts
+#[derive(Debug, Clone)]
+pub struct ConflictItem {
+    pub action: ConflictAction,
+    pub label: String,
+}
+impl fmt::Display for ConflictItem {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+/// The list of the conflicts of an import set, letting the user
+/// choose how each conflicting value is resolved
+pub struct ConflictReview {
+    pub menu: Menu<ConflictItem>,
+    import_set: ImportSet,
+    intro: String,
+    /// whether values must be hidden
+    masked: bool,
+    done: bool,
+}
+
+impl ConflictReview {
+    pub fn new(
+        import_set: ImportSet,
+        intro: String,
+        masked: bool,
+    ) -> Self {
+        let mut review = Self {
+            menu: Menu::new(),
+            import_set,
+            intro,
+            masked,
+            done: false,
+        };
+        review.rebuild_menu(0);
+        review
+    }
+    /// Tell whether the user is done with the review
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+    pub fn into_import_set(self) -> ImportSet {
+        self.import_set
+    }
+    /// Build the list of the conflicts, with the given item selected
+    fn rebuild_menu(
+        &mut self,
+        selection: usize,
+    ) {
+        let masked = self.masked;
+        let first_line = |value: &str| -> String {
+            if masked {
+                "▦▦▦▦▦▦".to_string()
+            } else {
+                value.split('\n').next().unwrap_or("").to_string()
+            }
+        };
+        let mut menu = Menu::new();
+        menu.set_intro(self.intro.clone());
+        for (idx, conflict) in self.import_set.conflicts().iter().enumerate() {
+            let label = format!(
+                "{:<7} *{}*: {} → {}",
+                conflict.resolution.to_string(),
+                &conflict.entry.name,
+                first_line(&conflict.old_value),
+                first_line(&conflict.entry.value),
+            );
+            menu.add_item(
+                ConflictItem {
+                    action: ConflictAction::Conflict(idx),
+                    label,
+                },
+                None,
+            );
+        }
+        let items = [
+            (ConflictResolution::Keep, "Keep all the current values"),
+            (
+                ConflictResolution::Replace,
+                "Replace all with the imported values",
+            ),
+            (ConflictResolution::Append, "Append all the imported values"),
+            (
+                ConflictResolution::Rename,
+                "Import all as new entries, renamed",
+            ),
+        ];
+        for (resolution, label) in items {
+            menu.add_item(
+                ConflictItem {
+                    action: ConflictAction::All(resolution),
+                    label: label.to_string(),
+                },
+                None,
+            );
+        }
+        menu.add_item(
+            ConflictItem {
+                action: ConflictAction::Done,
+                label: "Done".to_string(),
+            },
+            None,
+        );
+        menu.state.select(selection);
+        self.menu = menu;
+    }
+    fn selected_action(&self) -> Option<ConflictAction> {
+        self.menu
+            .state
+            .items
+            .get(self.menu.state.selection)
+            .map(|item| item.action.action)
+    }
+    fn on_conflict_item(
+        &mut self,
+        action: ConflictAction,
+    ) {
+        match action {
+            ConflictAction::Conflict(idx) => {
+                if let Some(conflict) = self.import_set.conflicts().get(idx) {
+                    let resolution = conflict.resolution.next();
+                    self.import_set.set_resolution(idx, resolution);
+                }
+            }
+            ConflictAction::All(resolution) => {
+                self.import_set.set_all_resolutions(resolution);
+            }
+            ConflictAction::Done => {
+                self.done = true;
+                return;
+            }
+        }
+        self.rebuild_menu(self.menu.state.selection);
+    }
+    /// Apply a key, with the keys choosing the resolution
+    /// of the selected conflict
+    pub fn apply_key_event(
+        &mut self,
+        key: KeyCombination,
+    ) -> bool {
+        if let Some(ConflictAction::Conflict(idx)) = self.selected_action() {
+            let resolution = if key == key!(k) {
+                Some(ConflictResolution::Keep)
+            } else if key == key!(r) {
+                Some(ConflictResolution::Replace)
+            } else if key == key!(a) {
+                Some(ConflictResolution::Append)
+            } else if key == key!(n) {
+                Some(ConflictResolution::Rename)
+            } else {
+                None
+            };
+            if let Some(resolution) = resolution {
+                self.import_set.set_resolution(idx, resolution);
+                self.rebuild_menu(self.menu.state.selection);
+                return true;
+            }
+        }
+        match self.menu.state.on_key(key) {
+            Some(item) => {
+                self.on_conflict_item(item.action);
+                true
+            }
+            None => false,
+        }
+    }
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        if let Some(item) = self.menu.state.on_mouse_event(mouse_event, double_click) {
+            self.on_conflict_item(item.action);
+        }
+    }
+    /// Return the hint to display when a conflict is selected
+    pub fn status(&self) -> Option<&'static str> {
+        match self.selected_action() {
+            Some(ConflictAction::Conflict(_)) => Some(
+                "*k*eep, *r*eplace, *a*ppend, or re*n*ame the imported value, *enter* to cycle",
+            ),
+            _ => None,
+        }
+    }
+}
@@ -1,6 +1,15 @@
 use {
     super::*,
-    crate::error::SafeClosetError,
+    crate::{
+        core::{
+            EntryField,
+            ExpiryStatus,
+            Totp,
+            format_date,
+            unix_now,
+        },
+        error::SafeClosetError,
+    },
     crokey::crossterm::{
         style::{
             Color,
@@ -15,6 +24,9 @@ use {
     termimad::*,
 };
 
+/// Width of the bar showing the remaining validity of a one-time code
+const TOTP_BAR_WIDTH: u64 = 10;
+
 /// Renders on most of the screen:
 /// - drawer creation and open dialogs
 /// - drawer content
@@ -92,6 +104,62 @@ impl View<AppState> for ContentView {
                 import.set_available_area(self.area.clone());
                 import.draw(w, app_skin)?;
             }
+            Dialog::Rekey(rekey) => {
+                rekey.set_available_area(self.area.clone());
+                rekey.draw(w, app_skin)?;
+            }
+            Dialog::Merge(merge) => {
+                merge.set_available_area(self.area.clone());
+                merge.draw(w, app_skin)?;
+            }
+            Dialog::PasswordGenerator(generator) => {
+                generator.set_available_area(self.area.clone());
+                generator.draw(w, app_skin)?;
+            }
+            Dialog::FieldsEditor(fields_editor) => {
+                fields_editor.set_available_area(self.area.clone());
+                fields_editor.draw(w, app_skin)?;
+            }
+            Dialog::ValueHistory(value_history) => {
+                value_history.set_available_area(self.area.clone());
+                value_history.draw(w, app_skin)?;
+            }
+            Dialog::TagsEditor(tags_editor) => {
+                tags_editor.set_available_area(self.area.clone());
+                tags_editor.draw(w, app_skin)?;
+            }
+            Dialog::TitleEditor(title_editor) => {
+                title_editor.set_available_area(self.area.clone());
+                title_editor.draw(w, app_skin)?;
+            }
+            Dialog::TagFacets(tag_facets) => {
+                tag_facets.set_available_area(self.area.clone());
+                tag_facets.draw(w, app_skin)?;
+            }
+            Dialog::Attachments(attachments) => {
+                attachments.set_available_area(self.area.clone());
+                attachments.draw(w, app_skin)?;
+            }
+            Dialog::ExpiryEditor(expiry_editor) => {
+                expiry_editor.set_available_area(self.area.clone());
+                expiry_editor.draw(w, app_skin)?;
+            }
+            Dialog::Audit(audit) => {
+                audit.set_available_area(self.area.clone());
+                audit.draw(w, app_skin)?;
+            }
+            Dialog::Transfer(transfer) => {
+                transfer.set_available_area(self.area.clone());
+                transfer.draw(w, app_skin)?;
+            }
+            Dialog::ExtractDrawer(extract_drawer) => {
+                extract_drawer.set_available_area(self.area.clone());
+                extract_drawer.draw(w, app_skin)?;
+            }
+            Dialog::Relocate(relocate) => {
+                relocate.set_available_area(self.area.clone());
+                relocate.draw(w, app_skin)?;
+            }
             Dialog::None => {}
         }
         Ok(())
@@ -161,12 +229,15 @@ impl ContentView {
         w.go_to(x, 1)?;
         tbl_style.queue_str(w, "─".repeat(name_width + 1))?;
         tbl_style.queue_str(w, "┬")?;
-        let value_header_width = if scrollbar.is_some() {
-            value_width + 1
+        let scrollbar_width = usize::from(scrollbar.is_some());
+        let age_width = layout.age_width as usize;
+        if age_width > 0 {
+            tbl_style.queue_str(w, "─".repeat(value_width))?;
+            tbl_style.queue_str(w, "┬")?;
+            tbl_style.queue_str(w, "─".repeat(age_width - 1 + scrollbar_width))?;
         } else {
-            value_width
-        };
-        tbl_style.queue_str(w, "─".repeat(value_header_width))?;
+            tbl_style.queue_str(w, "─".repeat(value_width + scrollbar_width))?;
+        }
         w.go_to(x, 2)?;
         if des.focus.is_search() {
             txt_style.queue_str(w, "/")?;
@@ -194,10 +265,25 @@ impl ContentView {
             value_width,
             Alignment::Center,
         )?;
+        if age_width > 0 {
+            tbl_style.queue_str(w, "│")?;
+            skin.styles(false, faded).md.write_composite_fill(
+                w,
+                Composite::from_inline("**changed**"),
+                age_width - 1,
+                Alignment::Center,
+            )?;
+        }
         w.go_to(x, 3)?;
         tbl_style.queue_str(w, "─".repeat(name_width + 1))?;
         tbl_style.queue_str(w, "┼")?;
-        tbl_style.queue_str(w, "─".repeat(value_width + 1))?;
+        if age_width > 0 {
+            tbl_style.queue_str(w, "─".repeat(value_width))?;
+            tbl_style.queue_str(w, "┼")?;
+            tbl_style.queue_str(w, "─".repeat(age_width))?;
+        } else {
+            tbl_style.queue_str(w, "─".repeat(value_width + 1))?;
+        }
         // -- entries
         let global_scrollbar_style =
             skin.scrollbar_style(false, faded || des.focus.is_entry_edit());
@@ -205,15 +291,20 @@ impl ContentView {
         let mut empty_lines = 0; // number of names to skip
         let area = &layout.lines_area;
         let unsel_styles = skin.styles(false, faded);
+        let now = unix_now();
         for y in area.top..=area.bottom() {
             w.go_to(x, y)?;
+            // the content of the age column, if the line is in an entry
+            let mut age_cell = None;
             if empty_lines > 0 {
+                age_cell = Some(String::new());
                 SPACE_FILLING.queue_styled(w, tbl_style, name_width + 1)?;
                 tbl_style.queue_str(w, "│")?;
                 empty_lines -= 1;
                 // we skip the value area, to not overwrite it
             } else if let Some((idx, name_match)) = des.listed_entry(line) {
                 let entry = &des.drawer.content.entries[idx];
+                age_cell = Some(entry.modified.map(format_date).unwrap_or_default());
                 let value_height = layout.value_heights_by_line[line];
                 let is_best = des.has_best_search(line);
                 let focus = &mut des.focus;
@@ -242,6 +333,28 @@ impl ContentView {
                         skin.match_style(selected, faded),
                     );
                     ms.queue_on(&mut cw)?;
+                    if !entry.tags.is_empty() {
+                        // tags are dimmed after the name
+                        let tags = format!(" {}", entry.tags_string());
+                        cw.queue_str(skin.txt_style(selected, true), &tags)?;
+                    }
+                    match ExpiryStatus::of(entry, now) {
+                        ExpiryStatus::Expired => {
+                            cw.queue_str(skin.match_style(selected, faded), " (expired)")?;
+                        }
+                        ExpiryStatus::Soon => {
+                            cw.queue_str(skin.txt_style(selected, true), " (expiring)")?;
+                        }
+                        ExpiryStatus::Valid => {}
+                    }
+                    match entry.attachments.len() {
+                        0 => {}
+                        1 => cw.queue_str(skin.txt_style(selected, true), " (1 file)")?,
+                        n => {
+                            let files = format!(" ({n} files)");
+                            cw.queue_str(skin.txt_style(selected, true), &files)?;
+                        }
+                    }
                     cw.fill_with_space(field_txt_style)?;
                 }
                 // - separator
@@ -273,35 +386,47 @@ impl ContentView {
                     if hidden {
                         skin.txt_style(false, true)
                             .queue_str(w, "▦".repeat(value_width))?;
-                    } else if open {
-                        let styles = skin.styles(selected, faded);
-                        let tw = Some(value_area.width as usize - 1);
-                        let text = if des.values_as_markdown() {
-                            FmtText::from(&styles.md, &entry.value, tw)
-                        } else {
-                            FmtText::raw_str(&styles.md, &entry.value, tw)
-                        };
-                        let mut text_view = TextView::from(&value_area, &text);
-                        text_view.show_scrollbar = true;
-                        text_view.write_on(w)?;
                     } else {
                         let styles = skin.styles(selected, faded);
-                        let first_line = entry.value.split('\n').next().unwrap();
-                        let first_line = if des.values_as_markdown() {
-                            Composite::from_inline(first_line)
+                        // the fields of an open entry take the last lines of the value area
+                        let fields_height = if open {
+                            let text_height = DrawerState::value_text_height(entry, value_width);
+                            entry.fields.len().min(value_height - text_height.min(1))
                         } else {
-                            Composite::raw_str(first_line)
+                            0
                         };
-                        styles.md.write_composite_fill(
-                            w,
-                            first_line,
-                            value_width,
-                            Alignment::Left,
-                        )?;
+                        let text_area = Area::new(
+                            value_left as u16,
+                            y,
+                            value_width as u16,
+                            (value_height - fields_height) as u16,
+                        );
+                        self.draw_value_text(w, des, idx, open, &text_area, styles)?;
+                        for (i, field) in entry.fields.iter().take(fields_height).enumerate() {
+                            let field_y = y + text_area.height + i as u16;
+                            if field_y > area.bottom() {
+                                break;
+                            }
+                            w.go_to(value_left as u16, field_y)?;
+                            styles.md.write_composite_fill(
+                                w,
+                                Composite::raw_str(&field_text(field)),
+                                value_width,
+                                Alignment::Left,
+                            )?;
+                        }
                     }
                 }
                 line += 1;
             }
+            // - age column
+            if let Some(age_cell) = age_cell.filter(|_| age_width > 0) {
+                w.go_to((value_left + value_width) as u16, y)?;
+                tbl_style.queue_str(w, "│")?;
+                let (fitted, width) = StrFit::make_string(&age_cell, age_width - 1);
+                txt_style.queue_str(w, fitted)?;
+                SPACE_FILLING.queue_styled(w, txt_style, age_width - 1 - width)?;
+            }
             // - scrollbar
             if let Some((stop, sbottom)) = scrollbar {
                 w.go_to(area.width, y)?;
@@ -314,4 +439,83 @@ impl ContentView {
         }
         Ok(())
     }
+    /// Draw the value of a visible entry, apart from its fields
+    fn draw_value_text(
+        &self,
+        w: &mut W,
+        des: &DrawerState,
+        idx: usize,
+        open: bool,
+        area: &Area,
+        styles: &Styles,
+    ) -> Result<(), SafeClosetError> {
+        let entry = &des.drawer.content.entries[idx];
+        let value_width = area.width as usize;
+        if area.height == 0 {
+            // the value is empty and the fields are displayed
+        } else if let Some(totp) = entry.totp() {
+            let text = totp_text(&totp);
+            styles.md.write_composite_fill(
+                w,
+                Composite::raw_str(&text),
+                value_width,
+                Alignment::Left,
+            )?;
+        } else if open {
+            let tw = Some(value_width - 1);
+            let text = if des.values_as_markdown() {
+                FmtText::from(&styles.md, &entry.value, tw)
+            } else {
+                FmtText::raw_str(&styles.md, &entry.value, tw)
+            };
+            let mut text_view = TextView::from(area, &text);
+            text_view.show_scrollbar = true;
+            text_view.write_on(w)?;
+        } else if entry.value.is_empty() && !entry.fields.is_empty() {
+            // a folded entry made only of fields is summed up by their names
+            let names: Vec<&str> = entry.fields.iter().map(|f| f.name.as_str()).collect();
+            styles.md.write_composite_fill(
+                w,
+                Composite::raw_str(&format!("[{}]", names.join(", "))),
+                value_width,
+                Alignment::Left,
+            )?;
+        } else {
+            let first_line = entry.value.split('\n').next().unwrap();
+            let first_line = if des.values_as_markdown() {
+                Composite::from_inline(first_line)
+            } else {
+                Composite::raw_str(first_line)
+            };
+            styles
+                .md
+                .write_composite_fill(w, first_line, value_width, Alignment::Left)?;
+        }
+        Ok(())
+    }
+}
+
+/// Build the line displayed in place of a TOTP seed: the current code,
+/// split in two groups for readability, and a countdown bar
+fn totp_text(totp: &Totp) -> String {
+    let (code, remaining) = totp.current_code();
+    let (left, right) = code.split_at(code.len() / 2);
+    let filled = (remaining * TOTP_BAR_WIDTH + totp.period - 1) / totp.period;
+    format!(
+        "{left} {right}  {}{} {remaining}s",
+        "█".repeat(filled as usize),
+        "░".repeat((TOTP_BAR_WIDTH - filled) as usize),
+    )
+}
+
+/// Build the line displaying a field of an open entry, secrets being masked
+fn field_text(field: &EntryField) -> String {
+    let value = if let Some(totp) = field.totp() {
+        totp_text(&totp)
+    } else if field.kind.is_masked() {
+        "▦▦▦▦▦▦".to_string()
+    } else {
+        field.value.split('\n').next().unwrap_or("").to_string()
+    };
+    format!("{}: {}", field.name, value)
 }
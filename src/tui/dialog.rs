@@ -9,6 +9,20 @@ pub enum Dialog {
     Password(PasswordDialog),
     CommentsEditor(CommentsEditor),
     Import(Import),
+    Rekey(Rekey),
+    Merge(Merge),
+    PasswordGenerator(PasswordGenerator),
+    FieldsEditor(FieldsEditor),
+    ValueHistory(ValueHistory),
+    TagsEditor(TagsEditor),
+    TitleEditor(TitleEditor),
+    TagFacets(TagFacets),
+    Attachments(Attachments),
+    ExpiryEditor(ExpiryEditor),
+    Audit(Audit),
+    Transfer(Transfer),
+    ExtractDrawer(ExtractDrawer),
+    Relocate(Relocate),
 }
 
 impl Dialog {
@@ -1,5 +1,9 @@
 use termimad::Area;
 
+/// Width of the column of the dates of last change, including
+/// its left border
+pub const AGE_COLUMN_WIDTH: u16 = 11;
+
 #[derive(Clone, Default, Debug)]
 pub struct DrawerDrawingLayout {
     /// the area containing the lines, without the header
@@ -7,6 +11,9 @@ pub struct DrawerDrawingLayout {
 
     pub name_width: u16,
 
+    /// width of the column of the dates of last change, 0 when it's hidden
+    pub age_width: u16,
+
     /// heights of values, excluding entries filtered out by search
     pub value_heights_by_line: Vec<usize>,
 
@@ -25,6 +32,6 @@ impl DrawerDrawingLayout {
     pub fn value_width(&self) -> usize {
         let name_width = self.name_width as usize;
         let value_left = name_width + 2; // 1 for selection mark, one for '|'
-        self.lines_area.width as usize - value_left
+        self.lines_area.width as usize - value_left - self.age_width as usize
     }
 }
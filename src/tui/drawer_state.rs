@@ -72,6 +72,19 @@ impl DrawerState {
     ///
     /// If the list is filtered, only matches are moved
     pub fn sort(&mut self) {
+        self.sort_by_key(|e| e.name.to_lowercase());
+    }
+    /// Sort entries by time of last change, oldest first (entries
+    /// whose age is unknown coming before all others)
+    ///
+    /// If the list is filtered, only matches are moved
+    pub fn sort_by_age(&mut self) {
+        self.sort_by_key(|e| e.modified);
+    }
+    fn sort_by_key<K: Ord, F: FnMut(&Entry) -> K>(
+        &mut self,
+        mut key: F,
+    ) {
         let entries = &mut self.drawer.content.entries;
         if let Some(result) = self.search.result.as_ref() {
             // We sort among filtered entries, not moving the other ones.
@@ -80,7 +93,7 @@ impl DrawerState {
             for (i, m) in matches.iter().enumerate() {
                 entries.swap(i, m.idx);
             }
-            entries[0..matches.len()].sort_by_key(|e| e.name.to_lowercase());
+            entries[0..matches.len()].sort_by_key(&mut key);
             for (i, m) in matches.iter().enumerate().rev() {
                 entries.swap(i, m.idx);
             }
@@ -88,7 +101,7 @@ impl DrawerState {
         } else {
             // We sort all entries
             self.focus = DrawerFocus::NoneSelected;
-            entries.sort_by_key(|e| e.name.to_lowercase());
+            entries.sort_by_key(key);
         }
     }
     /// Swap the focused line with either the one before or
@@ -267,6 +280,11 @@ impl DrawerState {
         self.layout.lines_area.height = page_height;
         self.layout.name_width = (self.layout.lines_area.width / 3).min(30);
         let dc = &self.drawer.content;
+        self.layout.age_width = if dc.settings.show_ages {
+            AGE_COLUMN_WIDTH
+        } else {
+            0
+        };
         let open_all_values = dc.settings.open_all_values && !dc.settings.hide_values;
         let value_width = self.layout.value_width();
         let lines_count = self.listed_entries_count();
@@ -286,16 +304,13 @@ impl DrawerState {
                     input.content().line_count().min(max_value_height)
                 }
                 _ => {
+                    let entry = &self.drawer.content.entries[idx];
                     let open = open_all_values || self.focus.is_value_selected(l);
                     if open {
-                        // we compute the number of lines the text would be for
-                        // the available width, taking wrapping into account
-                        let text = FmtText::from(
-                            termimad::get_default_skin(),
-                            &self.drawer.content.entries[idx].value,
-                            Some(value_width),
-                        );
-                        text.lines.len().min(max_value_height).max(1)
+                        // the fields, if any, are displayed one per line below the value
+                        (Self::value_text_height(entry, value_width) + entry.fields.len())
+                            .min(max_value_height)
+                            .max(1)
                     } else {
                         // this line's value is neither open nor selected, we display
                         // just the first line of the value (or a line of squares if
@@ -311,6 +326,29 @@ impl DrawerState {
         self.layout.has_scrollbar = self.content_height() > self.page_height();
     }
 
+    /// Return the number of lines the value of an open entry would
+    /// take, not counting its fields
+    pub fn value_text_height(
+        entry: &Entry,
+        value_width: usize,
+    ) -> usize {
+        if entry.value.is_empty() && !entry.fields.is_empty() {
+            0
+        } else if entry.totp().is_some() {
+            // the current one-time code is displayed instead of the seed
+            1
+        } else {
+            // we compute the number of lines the text would be for
+            // the available width, taking wrapping into account
+            let text = FmtText::from(
+                termimad::get_default_skin(),
+                &entry.value,
+                Some(value_width),
+            );
+            text.lines.len().max(1)
+        }
+    }
+
     pub fn values_as_markdown(&self) -> bool {
         self.drawer.content.settings.values_as_markdown
     }
@@ -400,6 +438,37 @@ impl DrawerState {
         }
     }
 
+    /// Return the TOTP generator of the selected value, if it's the
+    /// seed of one-time codes
+    pub fn current_totp(&self) -> Option<Totp> {
+        match &self.focus {
+            DrawerFocus::ValueSelected { line } => self
+                .listed_entry_idx(*line)
+                .and_then(|idx| self.drawer.content.entries.get(idx))
+                .and_then(Entry::totp),
+            _ => None,
+        }
+    }
+
+    /// Return the field of given index of the selected entry
+    pub fn current_field(
+        &self,
+        field_idx: usize,
+    ) -> Option<&EntryField> {
+        self.focus
+            .line()
+            .and_then(|line| self.listed_entry_idx(line))
+            .and_then(|idx| self.drawer.content.entries.get(idx))
+            .and_then(|entry| entry.fields.get(field_idx))
+    }
+
+    /// Tell whether some entries display one-time codes, which
+    /// must be refreshed
+    pub fn has_totp_entries(&self) -> bool {
+        !self.drawer.content.settings.hide_values
+            && self.drawer.content.entries.iter().any(Entry::has_totp)
+    }
+
     pub fn entry_line(
         &self,
         idx: usize,
@@ -416,6 +485,20 @@ impl DrawerState {
         None
     }
 
+    /// Select the name of the entry, removing the search if
+    /// it filters the entry out
+    pub fn select_entry(
+        &mut self,
+        idx: usize,
+    ) {
+        if self.entry_line(idx).is_none() {
+            self.search.clear();
+        }
+        if let Some(line) = self.entry_line(idx) {
+            self.focus = DrawerFocus::NameSelected { line };
+        }
+    }
+
     /// Give the index of the entry from its line among the listed
     /// entries (either all entries or only the ones matching if there's
     /// a search)
@@ -670,6 +753,7 @@ impl DrawerState {
                         self.decrement_edit_count();
                     } else {
                         self.drawer.content.entries[idx].name = new_name;
+                        self.drawer.content.entries[idx].touch();
                     }
                 }
                 self.focus = DrawerFocus::NameSelected { line };
@@ -686,7 +770,8 @@ impl DrawerState {
                     if new_value == self.drawer.content.entries[idx].value {
                         self.decrement_edit_count();
                     } else {
-                        self.drawer.content.entries[idx].value = new_value;
+                        let depth = self.drawer.content.settings.history_depth;
+                        self.drawer.content.entries[idx].set_value(new_value, depth);
                     }
                 }
                 self.focus = DrawerFocus::ValueSelected { line };
@@ -0,0 +1,96 @@
+use {
+    crate::{
+        core::*,
+        tui::ContentSkin,
+    },
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+        key,
+    },
+    termimad::InputField,
+};
+
+pub struct ExpiryEditorState {
+    pub input: InputField,
+    /// name of the entry, for the dialog's introduction
+    pub entry_name: String,
+    /// index of the entry whose expiry is edited
+    pub entry_idx: usize,
+    /// the current state of the entry, like "expired on 2024-03-05"
+    pub current: String,
+    pub accepted: Option<Option<Expiry>>,
+    pub finished: bool,
+    pub message: Option<&'static str>,
+}
+
+impl ExpiryEditorState {
+    pub fn new(
+        entry: &Entry,
+        entry_idx: usize,
+    ) -> Self {
+        let mut input = ContentSkin::make_input();
+        if let Some(expiry) = entry.expiry {
+            input.set_str(expiry.to_string());
+            input.move_to_end();
+        }
+        let current = match entry.expiry {
+            Some(expiry) => match expiry.time(entry) {
+                Some(time) => {
+                    let date = format_date(time);
+                    match ExpiryStatus::of(entry, unix_now()) {
+                        ExpiryStatus::Expired => format!("Expired since *{date}*"),
+                        _ => format!("Expires on *{date}*"),
+                    }
+                }
+                None => format!("Rotation {expiry}, from an unknown last change"),
+            },
+            None => "No expiry".to_string(),
+        };
+        Self {
+            input,
+            entry_name: entry.name.clone(),
+            entry_idx,
+            current,
+            accepted: None,
+            finished: false,
+            message: None,
+        }
+    }
+    pub fn apply_key_event(
+        &mut self,
+        key: KeyCombination,
+    ) -> bool {
+        self.message = None;
+        if key == key!(esc) {
+            self.finished = true;
+        } else if key == key!(enter) {
+            let content = self.input.get_content();
+            if content.trim().is_empty() {
+                self.accepted = Some(None);
+                self.finished = true;
+            } else if let Some(expiry) = Expiry::parse(&content) {
+                self.accepted = Some(Some(expiry));
+                self.finished = true;
+            } else {
+                self.message = Some("Type a date like *2025-12-31* or an interval like *90d*");
+            }
+        } else {
+            return self.input.apply_key_combination(key);
+        }
+        true
+    }
+    /// handle a mouse event
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        self.input.apply_mouse_event(mouse_event, double_click);
+    }
+    pub fn status(&self) -> &'static str {
+        self.message.unwrap_or(
+            "Hit *esc* to cancel, *enter* to validate (an empty input removes the expiry)",
+        )
+    }
+}
@@ -0,0 +1,67 @@
+use {
+    super::*,
+    crate::tui::*,
+    termimad::*,
+};
+
+const INTERNAL_HEIGHT: u16 = 1  // current expiry: 1
+    + 1  // blank line
+    + 3  // explanations: 3
+    + 1; // input: 1
+
+static MD_EXPLANATIONS: &str = r"Type a date like *2025-12-31*, or a rotation interval counted from the last change of the entry, in days, weeks, months or years, like *90d*, *12w*, *6m* or *1y*:";
+
+#[derive(Default)]
+pub struct ExpiryEditorView {
+    available_area: Area,
+}
+
+impl ExpiryEditorView {
+    fn dialog_area(&self) -> Area {
+        let mut area = self.available_area.clone();
+        if area.width > 60 && area.height > 12 {
+            let hw = area.width / 2;
+            let dhw = (hw * 3 / 4).min(hw - 2);
+            area.left = hw - dhw;
+            area.width = 2 * dhw;
+            let h = INTERNAL_HEIGHT + 2;
+            area.top += (area.height - h) / 3;
+            area.height = h;
+        }
+        area
+    }
+}
+
+impl View<ExpiryEditorState> for ExpiryEditorView {
+    fn set_available_area(
+        &mut self,
+        area: Area,
+    ) {
+        self.available_area = area;
+    }
+
+    /// Render the view in its area
+    fn draw(
+        &mut self,
+        w: &mut W,
+        state: &mut ExpiryEditorState, // mutable to allow adapt to terminal size changes
+        skin: &AppSkin,
+    ) -> Result<(), SafeClosetError> {
+        let area = self.dialog_area();
+        let border_colors = skin.dialog.md.table.compound_style.clone();
+        let mut rect = Rect::new(area.clone(), border_colors);
+        rect.set_fill(true);
+        rect.set_border_style(BORDER_STYLE_BLAND);
+        rect.draw(w)?;
+        let mut area = Area::new(area.left + 1, area.top + 1, area.width - 2, 1);
+        let current = format!("*{}*: {}", &state.entry_name, &state.current);
+        skin.dialog.md.write_in_area_on(w, &current, &area)?;
+        area.top += 2;
+        area.height = 3;
+        skin.dialog.md.write_in_area_on(w, MD_EXPLANATIONS, &area)?;
+        area.top += 3;
+        state.input.change_area(area.left, area.top, area.width);
+        state.input.display_on(w)?;
+        Ok(())
+    }
+}
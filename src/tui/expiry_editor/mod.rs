@@ -0,0 +1,74 @@
+mod expiry_editor_state;
+mod expiry_editor_view;
+
+pub use {
+    expiry_editor_state::*,
+    expiry_editor_view::*,
+};
+
+use {
+    super::*,
+    crate::core::*,
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+    },
+    termimad::Area,
+};
+
+/// A dialog setting the expiry date or rotation interval of an entry
+pub struct ExpiryEditor {
+    state: ExpiryEditorState,
+    view: ExpiryEditorView,
+}
+
+impl ExpiryEditor {
+    pub fn new(
+        entry: &Entry,
+        entry_idx: usize,
+    ) -> Self {
+        let state = ExpiryEditorState::new(entry, entry_idx);
+        let view = ExpiryEditorView::default();
+        Self { state, view }
+    }
+    pub fn on_key(
+        &mut self,
+        key: KeyCombination,
+    ) -> bool {
+        self.state.apply_key_event(key)
+    }
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        self.state.on_mouse_event(mouse_event, double_click);
+    }
+    pub fn set_available_area(
+        &mut self,
+        area: Area,
+    ) {
+        self.view.set_available_area(area);
+    }
+    pub fn draw(
+        &mut self,
+        w: &mut W,
+        app_skin: &AppSkin,
+    ) -> Result<(), SafeClosetError> {
+        self.view.draw(w, &mut self.state, app_skin)
+    }
+    pub fn status(&self) -> &'static str {
+        self.state.status()
+    }
+    pub fn is_finished(&self) -> bool {
+        self.state.finished
+    }
+    pub fn entry_idx(&self) -> usize {
+        self.state.entry_idx
+    }
+    /// Return the expiry the user validated, if any
+    /// (`Some(None)` when the expiry was removed)
+    pub fn accepted_expiry(&self) -> Option<Option<Expiry>> {
+        self.state.accepted
+    }
+}
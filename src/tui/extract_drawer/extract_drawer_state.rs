@@ -0,0 +1,242 @@
+use {
+    super::*,
+    crate::core::{
+        Closet,
+        RekeyPlan,
+    },
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+        key,
+    },
+    std::path::PathBuf,
+};
+
+#[allow(clippy::large_enum_variant)]
+pub enum ExtractDrawerStep {
+    SelectFile(FileSelector),
+    TypePassword {
+        path: PathBuf,
+        dialog: PasswordDialog,
+    },
+    TypeDeepPassword {
+        path: PathBuf,
+        password: String,
+        dialog: PasswordDialog,
+    },
+    Finished,
+}
+
+/// The request of an extraction, once the user gave all its parameters
+pub struct ExtractDrawerRequest {
+    /// the path of the new closet
+    pub path: PathBuf,
+    /// the passphrase of the copy in the new closet
+    pub password: String,
+    /// the deep drawers to copy with the drawer
+    pub deep_drawers: RekeyPlan,
+}
+
+pub struct ExtractDrawerState {
+    pub step: ExtractDrawerStep,
+    pub request: Option<ExtractDrawerRequest>,
+    /// the closet of the drawer to extract
+    closet: Closet,
+    /// the passphrases of the deep drawers to copy
+    deep_passwords: Vec<String>,
+    deep_drawers: RekeyPlan,
+    message: Option<&'static str>,
+    min_score: u8,
+    hide_chars: bool,
+}
+
+impl ExtractDrawerState {
+    pub fn new(
+        min_score: u8,
+        closet: Closet,
+    ) -> Self {
+        let selector = FileSelector::new(
+            "Enter the path of the new closet file, which will hold a copy of this drawer."
+                .to_string(),
+            FileType::NewFile,
+        );
+        let deep_drawers = RekeyPlan::for_drawer_copy(&closet);
+        Self {
+            step: ExtractDrawerStep::SelectFile(selector),
+            request: None,
+            closet,
+            deep_passwords: Vec::new(),
+            deep_drawers,
+            message: None,
+            min_score,
+            hide_chars: false,
+        }
+    }
+    pub fn toggle_hide_chars(&mut self) {
+        self.hide_chars = !self.hide_chars;
+        match &mut self.step {
+            ExtractDrawerStep::TypePassword { dialog, .. }
+            | ExtractDrawerStep::TypeDeepPassword { dialog, .. } => {
+                dialog.set_hide_chars(self.hide_chars);
+            }
+            _ => {}
+        }
+    }
+    pub fn is_finished(&self) -> bool {
+        matches!(self.step, ExtractDrawerStep::Finished)
+    }
+    fn on_file_selected(
+        &mut self,
+        selector: FileSelector,
+    ) {
+        let Some(path) = selector.get_selected_file() else {
+            self.step = ExtractDrawerStep::SelectFile(selector);
+            return;
+        };
+        let mut dialog = PasswordDialog::new(
+            PasswordDialogPurpose::NewDrawer { depth: 0 },
+            self.hide_chars,
+        );
+        dialog.set_min_score(self.min_score);
+        self.step = ExtractDrawerStep::TypePassword {
+            path: path.to_path_buf(),
+            dialog,
+        };
+    }
+    fn ask_deep_password(
+        &mut self,
+        path: PathBuf,
+        password: String,
+    ) {
+        let dialog =
+            PasswordDialog::new(PasswordDialogPurpose::ExtractDeepDrawers, self.hide_chars);
+        self.step = ExtractDrawerStep::TypeDeepPassword {
+            path,
+            password,
+            dialog,
+        };
+    }
+    /// Handle a passphrase of a deep drawer, or the end of them
+    /// when it's empty
+    fn on_deep_password(
+        &mut self,
+        path: PathBuf,
+        password: String,
+        deep_password: String,
+    ) {
+        if deep_password.is_empty() {
+            let mut deep_drawers = RekeyPlan::for_drawer_copy(&self.closet);
+            std::mem::swap(&mut deep_drawers, &mut self.deep_drawers);
+            self.request = Some(ExtractDrawerRequest {
+                path,
+                password,
+                deep_drawers,
+            });
+            return;
+        }
+        if self.deep_passwords.contains(&deep_password) {
+            self.message = Some("This passphrase was already typed");
+        } else {
+            self.deep_passwords.push(deep_password);
+            if self
+                .deep_drawers
+                .add_password(&self.closet, &self.deep_passwords)
+            {
+                self.message = Some(
+                    "Deep drawer added. Type another passphrase or hit *enter* to write the closet",
+                );
+            } else {
+                self.deep_passwords.pop();
+                self.message = Some(
+                    "No deep drawer found (for a deeper drawer, type its parent's passphrase first)",
+                );
+            }
+        }
+        self.ask_deep_password(path, password);
+    }
+    pub fn apply_key_event(
+        &mut self,
+        key: KeyCombination,
+    ) -> bool {
+        let mut step = ExtractDrawerStep::Finished;
+        std::mem::swap(&mut step, &mut self.step);
+        if key == key!(esc) {
+            return true;
+        }
+        match step {
+            ExtractDrawerStep::SelectFile(mut selector) => {
+                if key == key!(enter) {
+                    self.on_file_selected(selector);
+                    true
+                } else {
+                    let handled = selector.apply_key_event(key);
+                    self.step = ExtractDrawerStep::SelectFile(selector);
+                    handled
+                }
+            }
+            ExtractDrawerStep::TypePassword { path, mut dialog } => {
+                if dialog.apply_key_event(key) {
+                    self.step = ExtractDrawerStep::TypePassword { path, dialog };
+                    true
+                } else if key == key!(enter) {
+                    self.ask_deep_password(path, dialog.get_password());
+                    true
+                } else {
+                    self.step = ExtractDrawerStep::TypePassword { path, dialog };
+                    false
+                }
+            }
+            ExtractDrawerStep::TypeDeepPassword {
+                path,
+                password,
+                mut dialog,
+            } => {
+                if dialog.apply_key_event(key) {
+                    self.step = ExtractDrawerStep::TypeDeepPassword {
+                        path,
+                        password,
+                        dialog,
+                    };
+                    true
+                } else if key == key!(enter) {
+                    self.on_deep_password(path, password, dialog.get_password());
+                    true
+                } else {
+                    self.step = ExtractDrawerStep::TypeDeepPassword {
+                        path,
+                        password,
+                        dialog,
+                    };
+                    false
+                }
+            }
+            ExtractDrawerStep::Finished => true,
+        }
+    }
+    /// handle a mouse event
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        match &mut self.step {
+            ExtractDrawerStep::SelectFile(selector) => {
+                selector.on_mouse_event(mouse_event, double_click);
+            }
+            ExtractDrawerStep::TypePassword { dialog, .. }
+            | ExtractDrawerStep::TypeDeepPassword { dialog, .. } => {
+                dialog.on_mouse_event(mouse_event, double_click);
+            }
+            ExtractDrawerStep::Finished => {}
+        }
+    }
+    pub fn status(&self) -> &'static str {
+        match &self.step {
+            ExtractDrawerStep::SelectFile(selector) => selector.get_message(),
+            ExtractDrawerStep::TypeDeepPassword { .. } => self.message.unwrap_or(
+                "Type the passphrase of a deep drawer to copy, or *enter* to write the closet",
+            ),
+            _ => "Type the passphrase of the drawer in the new closet, *esc* to cancel",
+        }
+    }
+}
@@ -0,0 +1,85 @@
+mod extract_drawer_state;
+
+pub use extract_drawer_state::*;
+
+use {
+    super::*,
+    crate::core::Closet,
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+    },
+    termimad::Area,
+};
+
+/// A dialog asking for the path and passphrase of a new closet
+/// in which to copy the current drawer, and for the passphrases
+/// of the deep drawers to copy with it
+pub struct ExtractDrawer {
+    state: ExtractDrawerState,
+    available_area: Area,
+}
+
+impl ExtractDrawer {
+    pub fn new(
+        min_score: u8,
+        closet: Closet,
+    ) -> Self {
+        Self {
+            state: ExtractDrawerState::new(min_score, closet),
+            available_area: Area::default(),
+        }
+    }
+    pub fn toggle_hide_chars(&mut self) {
+        self.state.toggle_hide_chars();
+    }
+    pub fn on_key(
+        &mut self,
+        key: KeyCombination,
+    ) -> bool {
+        self.state.apply_key_event(key)
+    }
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        self.state.on_mouse_event(mouse_event, double_click);
+    }
+    pub fn set_available_area(
+        &mut self,
+        area: Area,
+    ) {
+        self.available_area = area;
+    }
+    pub fn draw(
+        &mut self,
+        w: &mut W,
+        app_skin: &AppSkin,
+    ) -> Result<(), SafeClosetError> {
+        let area = self.available_area.clone();
+        match &mut self.state.step {
+            ExtractDrawerStep::SelectFile(selector) => {
+                selector.view.set_available_area(area);
+                selector.draw(w, app_skin)?;
+            }
+            ExtractDrawerStep::TypePassword { dialog, .. }
+            | ExtractDrawerStep::TypeDeepPassword { dialog, .. } => {
+                dialog.view.set_available_area(area);
+                dialog.draw(w, app_skin)?;
+            }
+            ExtractDrawerStep::Finished => {}
+        }
+        Ok(())
+    }
+    pub fn status(&self) -> &'static str {
+        self.state.status()
+    }
+    pub fn is_finished(&self) -> bool {
+        self.state.is_finished()
+    }
+    /// Return the parameters of the extraction, if the user gave them
+    pub fn take_request(&mut self) -> Option<ExtractDrawerRequest> {
+        self.state.request.take()
+    }
+}
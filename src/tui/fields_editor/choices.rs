@@ -0,0 +1,25 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldsAction {
+    Edit(usize),
+    Add,
+    Done,
+}
+
+/// An item of the fields menu, whose label shows
+/// the field (masked if it's a secret)
+#[derive(Debug, Clone)]
+pub struct FieldsItem {
+    pub action: FieldsAction,
+    pub label: String,
+}
+
+impl fmt::Display for FieldsItem {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
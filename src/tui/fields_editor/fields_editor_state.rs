@@ -0,0 +1,298 @@
+use {
+    super::*,
+    crate::{
+        core::*,
+        tui::menu::*,
+    },
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+        key,
+    },
+    termimad::InputField,
+};
+
+#[allow(clippy::large_enum_variant)]
+pub enum FieldsStep {
+    Browse(Menu<FieldsItem>),
+    EditField(FieldForm),
+    Finished,
+}
+
+/// The inputs of the field being edited or added
+pub struct FieldForm {
+    /// index of the edited field, None for a new one
+    pub idx: Option<usize>,
+    pub kind: FieldKind,
+    pub name: InputField,
+    pub value: InputField,
+}
+
+impl FieldForm {
+    fn new(
+        idx: Option<usize>,
+        field: &EntryField,
+    ) -> Self {
+        let mut name = ContentSkin::make_input();
+        name.set_str(&field.name);
+        name.move_to_end();
+        let mut value = ContentSkin::make_input();
+        value.set_str(&field.value);
+        value.move_to_end();
+        value.set_focus(false);
+        let mut form = Self {
+            idx,
+            kind: field.kind,
+            name,
+            value,
+        };
+        form.set_kind(field.kind);
+        form
+    }
+    fn set_kind(
+        &mut self,
+        kind: FieldKind,
+    ) {
+        self.kind = kind;
+        self.value.password_mode = kind == FieldKind::Secret;
+    }
+    fn toggle_focus(&mut self) {
+        let on_name = self.name.focused();
+        self.name.set_focus(!on_name);
+        self.value.set_focus(on_name);
+    }
+}
+
+pub struct FieldsEditorState {
+    pub step: FieldsStep,
+    /// name of the entry, for the dialog's introduction
+    pub entry_name: String,
+    /// index of the entry whose fields are edited
+    pub entry_idx: usize,
+    pub fields: Vec<EntryField>,
+    /// whether the fields were modified
+    pub changed: bool,
+    pub message: Option<&'static str>,
+}
+
+impl FieldsEditorState {
+    pub fn new(
+        entry: &Entry,
+        entry_idx: usize,
+    ) -> Self {
+        let mut state = Self {
+            step: FieldsStep::Finished,
+            entry_name: entry.name.clone(),
+            entry_idx,
+            fields: entry.fields.clone(),
+            changed: false,
+            message: None,
+        };
+        state.browse(0);
+        state
+    }
+    fn finish(&mut self) {
+        self.step = FieldsStep::Finished;
+    }
+    pub fn is_finished(&self) -> bool {
+        matches!(self.step, FieldsStep::Finished)
+    }
+    /// Rebuild the menu with the given item selected
+    fn browse(
+        &mut self,
+        selection: usize,
+    ) {
+        let mut menu = Menu::new();
+        menu.set_intro(format!("Fields of *{}*:", &self.entry_name));
+        for (idx, field) in self.fields.iter().enumerate() {
+            let value = if field.kind.is_masked() {
+                "▦▦▦▦▦▦"
+            } else {
+                field.value.split('\n').next().unwrap_or("")
+            };
+            let label = format!("[{}] {}: {}", field.kind, field.name, value);
+            menu.add_item(
+                FieldsItem {
+                    action: FieldsAction::Edit(idx),
+                    label,
+                },
+                None,
+            );
+        }
+        menu.add_item(
+            FieldsItem {
+                action: FieldsAction::Add,
+                label: "Add a field".to_string(),
+            },
+            Some(key!(n)),
+        );
+        menu.add_item(
+            FieldsItem {
+                action: FieldsAction::Done,
+                label: "Done".to_string(),
+            },
+            None,
+        );
+        menu.state.select(selection);
+        self.step = FieldsStep::Browse(menu);
+    }
+    fn on_item(
+        &mut self,
+        action: FieldsAction,
+    ) {
+        self.message = None;
+        match action {
+            FieldsAction::Edit(idx) => {
+                if let Some(field) = self.fields.get(idx) {
+                    self.step = FieldsStep::EditField(FieldForm::new(Some(idx), field));
+                }
+            }
+            FieldsAction::Add => {
+                self.step = FieldsStep::EditField(FieldForm::new(None, &EntryField::default()));
+            }
+            FieldsAction::Done => {
+                self.finish();
+            }
+        }
+    }
+    /// Apply a key on the browsed fields, returning whether it was handled
+    fn apply_browse_key(
+        &mut self,
+        key: KeyCombination,
+        selection: usize,
+    ) -> bool {
+        if selection >= self.fields.len() {
+            return false;
+        }
+        if key == key!(d) {
+            self.fields.remove(selection);
+            self.changed = true;
+            self.browse(selection.min(self.fields.len()));
+        } else if key == key!(t) {
+            let kind = &mut self.fields[selection].kind;
+            *kind = kind.next();
+            self.changed = true;
+            self.browse(selection);
+        } else if key == key!(ctrl - up) && selection > 0 {
+            self.fields.swap(selection, selection - 1);
+            self.changed = true;
+            self.browse(selection - 1);
+        } else if key == key!(ctrl - down) && selection + 1 < self.fields.len() {
+            self.fields.swap(selection, selection + 1);
+            self.changed = true;
+            self.browse(selection + 1);
+        } else {
+            return false;
+        }
+        true
+    }
+    /// Save the field of the form, return false if it can't be
+    fn validate_form(
+        &mut self,
+        form: &FieldForm,
+    ) -> bool {
+        let name = form.name.get_content().trim().to_string();
+        if name.is_empty() {
+            self.message = Some("The field needs a name");
+            return false;
+        }
+        let field = EntryField::new(name, form.kind, form.value.get_content());
+        let selection = match form.idx {
+            Some(idx) => {
+                if self.fields[idx] != field {
+                    self.fields[idx] = field;
+                    self.changed = true;
+                }
+                idx
+            }
+            None => {
+                self.fields.push(field);
+                self.changed = true;
+                self.fields.len() - 1
+            }
+        };
+        self.browse(selection);
+        true
+    }
+    pub fn apply_key_event(
+        &mut self,
+        key: KeyCombination,
+    ) -> bool {
+        let mut step = FieldsStep::Finished;
+        std::mem::swap(&mut step, &mut self.step);
+        match step {
+            FieldsStep::Browse(mut menu) => {
+                if key == key!(esc) {
+                    return true;
+                }
+                let selection = menu.state.selection;
+                if self.apply_browse_key(key, selection) {
+                    self.message = None;
+                    return true;
+                }
+                match menu.state.on_key(key) {
+                    Some(item) => {
+                        self.step = FieldsStep::Browse(menu);
+                        self.on_item(item.action);
+                        true
+                    }
+                    None => {
+                        self.step = FieldsStep::Browse(menu);
+                        false
+                    }
+                }
+            }
+            FieldsStep::EditField(mut form) => {
+                if key == key!(esc) {
+                    self.message = None;
+                    self.browse(form.idx.unwrap_or(self.fields.len()));
+                } else if key == key!(enter) {
+                    if !self.validate_form(&form) {
+                        self.step = FieldsStep::EditField(form);
+                    }
+                } else {
+                    if key == key!(tab) {
+                        form.toggle_focus();
+                    } else if key == key!(ctrl - t) {
+                        form.set_kind(form.kind.next());
+                    } else if !form.name.apply_key_combination(key) {
+                        form.value.apply_key_combination(key);
+                    }
+                    self.step = FieldsStep::EditField(form);
+                }
+                true
+            }
+            FieldsStep::Finished => true,
+        }
+    }
+    /// handle a mouse event
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        match &mut self.step {
+            FieldsStep::Browse(menu) => {
+                if let Some(item) = menu.state.on_mouse_event(mouse_event, double_click) {
+                    self.on_item(item.action);
+                }
+            }
+            FieldsStep::EditField(form) => {
+                if form.name.apply_mouse_event(mouse_event, double_click) {
+                    form.value.set_focus(false);
+                } else if form.value.apply_mouse_event(mouse_event, double_click) {
+                    form.name.set_focus(false);
+                }
+            }
+            FieldsStep::Finished => {}
+        }
+    }
+    pub fn status(&self) -> &'static str {
+        self.message.unwrap_or(match self.step {
+            FieldsStep::EditField(_) => {
+                "*tab* to switch input, *^t* to change the type, *enter* to validate, *esc* to cancel"
+            }
+            _ => "*enter* to edit, *n* to add, *d* to remove, *t* to change the type, *esc* to close",
+        })
+    }
+}
@@ -0,0 +1,77 @@
+use {
+    super::*,
+    crate::tui::*,
+    termimad::*,
+};
+
+const FORM_INTERNAL_HEIGHT: u16 = 2  // kind: 2
+    + 2  // name label and input: 2
+    + 2; // value label and input: 2
+
+#[derive(Default)]
+pub struct FieldsEditorView {
+    available_area: Area,
+}
+
+impl FieldsEditorView {
+    fn form_area(&self) -> Area {
+        let mut area = self.available_area.clone();
+        if area.width > 60 && area.height > 10 {
+            let hw = area.width / 2;
+            let dhw = (hw * 3 / 4).min(hw - 2);
+            area.left = hw - dhw;
+            area.width = 2 * dhw;
+            let h = FORM_INTERNAL_HEIGHT + 2;
+            area.top += (area.height - h) / 3;
+            area.height = h;
+        }
+        area
+    }
+}
+
+impl View<FieldsEditorState> for FieldsEditorView {
+    fn set_available_area(
+        &mut self,
+        area: Area,
+    ) {
+        self.available_area = area;
+    }
+
+    /// Render the view in its area
+    fn draw(
+        &mut self,
+        w: &mut W,
+        state: &mut FieldsEditorState, // mutable to allow adapt to terminal size changes
+        skin: &AppSkin,
+    ) -> Result<(), SafeClosetError> {
+        match &mut state.step {
+            FieldsStep::Browse(menu) => {
+                menu.set_available_area(self.available_area.clone());
+                menu.draw(w, skin)?;
+            }
+            FieldsStep::EditField(form) => {
+                let area = self.form_area();
+                let border_colors = skin.dialog.md.table.compound_style.clone();
+                let mut rect = Rect::new(area.clone(), border_colors);
+                rect.set_fill(true);
+                rect.set_border_style(BORDER_STYLE_BLAND);
+                rect.draw(w)?;
+                let mut area = Area::new(area.left + 1, area.top + 1, area.width - 2, 1);
+                let kind = format!("Type: *{}* (*^t* to change)", form.kind);
+                skin.dialog.md.write_in_area_on(w, &kind, &area)?;
+                area.top += 2;
+                skin.dialog.md.write_in_area_on(w, "Name:", &area)?;
+                area.top += 1;
+                form.name.change_area(area.left, area.top, area.width);
+                form.name.display_on(w)?;
+                area.top += 1;
+                skin.dialog.md.write_in_area_on(w, "Value:", &area)?;
+                area.top += 1;
+                form.value.change_area(area.left, area.top, area.width);
+                form.value.display_on(w)?;
+            }
+            FieldsStep::Finished => {}
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+mod choices;
+mod fields_editor_state;
+mod fields_editor_view;
+
+pub use {
+    choices::*,
+    fields_editor_state::*,
+    fields_editor_view::*,
+};
+
+use {
+    super::*,
+    crate::core::*,
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+    },
+    termimad::Area,
+};
+
+/// A dialog editing the typed fields of an entry
+pub struct FieldsEditor {
+    state: FieldsEditorState,
+    view: FieldsEditorView,
+}
+
+impl FieldsEditor {
+    pub fn new(
+        entry: &Entry,
+        entry_idx: usize,
+    ) -> Self {
+        let state = FieldsEditorState::new(entry, entry_idx);
+        let view = FieldsEditorView::default();
+        Self { state, view }
+    }
+    pub fn on_key(
+        &mut self,
+        key: KeyCombination,
+    ) -> bool {
+        self.state.apply_key_event(key)
+    }
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        self.state.on_mouse_event(mouse_event, double_click);
+    }
+    pub fn set_available_area(
+        &mut self,
+        area: Area,
+    ) {
+        self.view.set_available_area(area);
+    }
+    pub fn draw(
+        &mut self,
+        w: &mut W,
+        app_skin: &AppSkin,
+    ) -> Result<(), SafeClosetError> {
+        self.view.draw(w, &mut self.state, app_skin)
+    }
+    pub fn status(&self) -> &'static str {
+        self.state.status()
+    }
+    pub fn is_finished(&self) -> bool {
+        self.state.is_finished()
+    }
+    pub fn entry_idx(&self) -> usize {
+        self.state.entry_idx
+    }
+    /// Return the edited fields, if the user changed them
+    pub fn take_changed_fields(&mut self) -> Option<Vec<EntryField>> {
+        if self.state.changed {
+            Some(std::mem::take(&mut self.state.fields))
+        } else {
+            None
+        }
+    }
+}
@@ -36,6 +36,15 @@ impl FileSelectorState {
             message,
         }
     }
+    /// Fill the input with a proposed path
+    pub fn set_path(
+        &mut self,
+        path: &str,
+    ) {
+        self.input.set_str(path);
+        self.input.move_to_end();
+        self.update_path();
+    }
     pub fn get_selected_file(&self) -> Option<&Path> {
         self.path.as_deref()
     }
@@ -8,7 +8,10 @@ pub struct FileCheck {
 
 #[derive(Debug, Clone, Copy)]
 pub enum FileType {
+    /// an existing file, to read
     File,
+    /// a file to create
+    NewFile,
 }
 
 impl FileCheck {
@@ -25,13 +28,33 @@ impl FileType {
         self,
         path: &Path,
     ) -> FileCheck {
-        if path.components().count() == 0 {
-            return FileCheck::new(false, "Type the path to the file to open");
+        match self {
+            Self::File => {
+                if path.components().count() == 0 {
+                    return FileCheck::new(false, "Type the path to the file to open");
+                }
+                if !path.exists() || !path.is_file() {
+                    return FileCheck::new(false, "Type the path of a file");
+                }
+                // we don't check the extension because people can name files how they want
+                FileCheck::new(true, "Type *enter* to select this file")
+            }
+            Self::NewFile => {
+                if path.components().count() == 0 {
+                    return FileCheck::new(false, "Type the path of the file to create");
+                }
+                if path.exists() {
+                    return FileCheck::new(false, "There's already a file at this path");
+                }
+                let parent_exists = match path.parent() {
+                    Some(parent) => parent.as_os_str().is_empty() || parent.is_dir(),
+                    None => false,
+                };
+                if !parent_exists {
+                    return FileCheck::new(false, "The directory doesn't exist");
+                }
+                FileCheck::new(true, "Type *enter* to write the file")
+            }
         }
-        if !path.exists() || !path.is_file() {
-            return FileCheck::new(false, "Type the path of a file");
-        }
-        // we don't check the extension because people can name files how they want
-        FileCheck::new(true, "Type *enter* to select this file")
     }
 }
@@ -31,6 +31,12 @@ impl FileSelector {
         let view = FileSelectorView::default();
         Self { state, view }
     }
+    pub fn set_path(
+        &mut self,
+        path: &str,
+    ) {
+        self.state.set_path(path);
+    }
     pub fn get_selected_file(&self) -> Option<&Path> {
         self.state.get_selected_file()
     }
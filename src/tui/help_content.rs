@@ -29,6 +29,7 @@ The *^* symbol in SafeCloset means that the *control* key must be pressed.
 | *N* | Create a new entry immediately after the selected one
 | *^h* | Toggle hiding either password chars or unselected values
 | *^f* | Toggle folding all values
+| *^g* | Generate a password for the selected value, or a passphrase when creating a drawer
 | */* | Start searching the current drawer (do *Enter* or use the down or up arrow key to freeze it)
 | */* then *esc* | Remove the current filtering
 | *esc* | Cancel current field edition or open a menu
@@ -40,6 +41,14 @@ The *^* symbol in SafeCloset means that the *control* key must be pressed.
 | *a* | Start editing the selected name or value, cursor at end
 | *d* | Remove the selected entry (with confirmation)
 | *^c* | Copy the selection (or the entire field if not edited)
+| *^r* | Browse the previous values of the selected entry, to restore or copy one
+| *^e* | Edit the typed fields (username, password, URL, etc.) of the selected entry
+| *alt*-*1* to *alt*-*9* | Copy a field of the selected entry
+| *^t* | Edit the tags of the selected entry
+| *^a* | Manage the files attached to the selected entry
+| *^y* | Set the expiry date or rotation interval of the selected entry
+| *!* | List the entries which are expired or expiring soon
+| *#* | List the tags of the drawer, to filter the entries on one of them
 | *^x* | Cut the selection
 | *^v* | Paste
 | *Enter* | Validate the current edition
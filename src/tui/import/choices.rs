@@ -20,6 +20,7 @@ impl fmt::Display for OriginKind {
 #[derive(Debug, Clone, Copy)]
 pub enum ConfirmCsv {
     Confirm,
+    ReviewConflicts,
     Cancel,
 }
 impl fmt::Display for ConfirmCsv {
@@ -29,6 +30,7 @@ impl fmt::Display for ConfirmCsv {
     ) -> fmt::Result {
         match self {
             Self::Confirm => write!(f, "Import these entries"),
+            Self::ReviewConflicts => write!(f, "Review the conflicting values"),
             Self::Cancel => write!(f, "Cancel"),
         }
     }
@@ -37,6 +39,8 @@ impl fmt::Display for ConfirmCsv {
 #[derive(Debug, Clone, Copy)]
 pub enum ConfirmDrawer {
     Confirm,
+    ReviewConflicts,
+    WithDeepDrawers,
     GoDeeper,
     Cancel,
 }
@@ -47,8 +51,33 @@ impl fmt::Display for ConfirmDrawer {
     ) -> fmt::Result {
         match self {
             Self::Confirm => write!(f, "Import this drawer"),
+            Self::ReviewConflicts => write!(f, "Review the conflicting values"),
+            Self::WithDeepDrawers => write!(f, "Import this drawer with its deep drawers"),
             Self::GoDeeper => write!(f, "Open a deeper drawer"),
             Self::Cancel => write!(f, "Cancel"),
         }
     }
 }
+
+#[derive(Debug, Clone, Copy)]
+pub enum ConfirmDeepImport {
+    Confirm,
+    ReviewConflicts,
+    AddDeepDrawer,
+    CopyClosedDrawers,
+    Cancel,
+}
+impl fmt::Display for ConfirmDeepImport {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::Confirm => write!(f, "Import the entries and the deep drawers"),
+            Self::ReviewConflicts => write!(f, "Review the conflicting values"),
+            Self::AddDeepDrawer => write!(f, "Add a deep drawer (type its passphrase)"),
+            Self::CopyClosedDrawers => write!(f, "Copy the other deep drawers as they are"),
+            Self::Cancel => write!(f, "Cancel"),
+        }
+    }
+}
@@ -3,7 +3,10 @@ use {
     crate::{
         core::*,
         csv::Csv,
-        import::ImportSet,
+        import::{
+            DeepImportSet,
+            ImportSet,
+        },
         tui::menu::*,
     },
     crokey::{
@@ -17,6 +20,7 @@ use {
     },
 };
 
+#[allow(clippy::large_enum_variant)]
 pub enum Step {
     DecideOriginKind(Menu<OriginKind>),
     FileSelector(FileSelector),
@@ -33,9 +37,38 @@ pub enum Step {
         menu: Menu<ConfirmDrawer>,
         import_set: ImportSet,
     },
+    TypeDeepDrawerPassword {
+        open_closet: OpenCloset,
+        import_set: ImportSet,
+        deep_set: DeepImportSet,
+        dialog: PasswordDialog,
+    },
+    ConfirmDeepImport {
+        open_closet: OpenCloset,
+        menu: Menu<ConfirmDeepImport>,
+        import_set: ImportSet,
+        deep_set: DeepImportSet,
+    },
+    ReviewConflicts {
+        source: ImportSource,
+        review: ConflictReview,
+    },
     InformEnd(InformMenu),
     Finished,
 }
+
+/// Where the imported entries come from, with what's needed
+/// to go on with the import after the review of the conflicts
+pub enum ImportSource {
+    Csv,
+    Drawer {
+        open_closet: OpenCloset,
+    },
+    DeepDrawers {
+        open_closet: OpenCloset,
+        deep_set: DeepImportSet,
+    },
+}
 impl Default for Step {
     fn default() -> Self {
         let mut menu = Menu::new();
@@ -72,8 +105,14 @@ impl ImportState {
     }
     pub fn toggle_hide_chars(&mut self) {
         self.hide_chars = !self.hide_chars;
-        if let Step::TypeDrawerPassword { dialog, .. } = &mut self.step {
-            dialog.set_hide_chars(self.hide_chars);
+        match &mut self.step {
+            Step::TypeDrawerPassword { dialog, .. } => {
+                dialog.set_hide_chars(self.hide_chars);
+            }
+            Step::TypeDeepDrawerPassword { dialog, .. } => {
+                dialog.set_hide_chars(self.hide_chars);
+            }
+            _ => {}
         }
     }
     fn end<S: Into<String>>(
@@ -126,11 +165,7 @@ impl ImportState {
                         self.end("There's nothing new in this CSV file");
                         return;
                     }
-                    let mut menu = Menu::new();
-                    menu.set_intro(import_set.confirm_string());
-                    menu.add_item(ConfirmCsv::Confirm, None);
-                    menu.add_item(ConfirmCsv::Cancel, None);
-                    self.step = Step::ConfirmImportCsv { menu, import_set };
+                    self.confirm_csv_import(import_set);
                 }
                 Err(e) => {
                     self.end(format!("Error while trying to read CSV file: {e}"));
@@ -173,24 +208,7 @@ impl ImportState {
         if let Some(src_drawer) = open_closet.open_drawer(&password) {
             let src = src_drawer.content.entries.clone();
             let import_set = ImportSet::new(src, &self.dst_drawer_state.drawer);
-            let mut menu = Menu::new();
-            if import_set.is_empty() {
-                menu.set_intro(
-                    "The selected drawer contains nothing which isn't already\
-                    in the destination drawer."
-                        .to_string(),
-                );
-            } else {
-                menu.set_intro(import_set.confirm_string());
-                menu.add_item(ConfirmDrawer::Confirm, None);
-            }
-            menu.add_item(ConfirmDrawer::GoDeeper, None);
-            menu.add_item(ConfirmDrawer::Cancel, None);
-            self.step = Step::ConfirmImportDrawer {
-                open_closet,
-                menu,
-                import_set,
-            };
+            self.confirm_drawer_import(open_closet, import_set);
         } else {
             info!("wrong pass");
             self.message = Some("Wrong passphrase");
@@ -200,6 +218,239 @@ impl ImportState {
             };
         }
     }
+    fn confirm_csv_import(
+        &mut self,
+        import_set: ImportSet,
+    ) {
+        let mut menu = Menu::new();
+        menu.set_intro(import_set.confirm_string());
+        menu.add_item(ConfirmCsv::Confirm, None);
+        if !import_set.conflicts().is_empty() {
+            menu.add_item(ConfirmCsv::ReviewConflicts, None);
+        }
+        menu.add_item(ConfirmCsv::Cancel, None);
+        self.step = Step::ConfirmImportCsv { menu, import_set };
+    }
+    fn confirm_drawer_import(
+        &mut self,
+        open_closet: OpenCloset,
+        import_set: ImportSet,
+    ) {
+        let mut menu = Menu::new();
+        if import_set.is_empty() {
+            menu.set_intro(
+                "The selected drawer contains nothing which isn't already\
+                in the destination drawer."
+                    .to_string(),
+            );
+        } else {
+            menu.set_intro(import_set.confirm_string());
+            menu.add_item(ConfirmDrawer::Confirm, None);
+        }
+        if !import_set.conflicts().is_empty() {
+            menu.add_item(ConfirmDrawer::ReviewConflicts, None);
+        }
+        menu.add_item(ConfirmDrawer::WithDeepDrawers, None);
+        menu.add_item(ConfirmDrawer::GoDeeper, None);
+        menu.add_item(ConfirmDrawer::Cancel, None);
+        self.step = Step::ConfirmImportDrawer {
+            open_closet,
+            menu,
+            import_set,
+        };
+    }
+    /// Go back to the confirmation of the import, after
+    /// the review of the conflicts
+    fn confirm(
+        &mut self,
+        source: ImportSource,
+        import_set: ImportSet,
+    ) {
+        match source {
+            ImportSource::Csv => {
+                self.confirm_csv_import(import_set);
+            }
+            ImportSource::Drawer { open_closet } => {
+                self.confirm_drawer_import(open_closet, import_set);
+            }
+            ImportSource::DeepDrawers {
+                open_closet,
+                deep_set,
+            } => {
+                self.confirm_deep_import(open_closet, import_set, deep_set);
+            }
+        }
+    }
+    fn review_conflicts(
+        &mut self,
+        source: ImportSource,
+        import_set: ImportSet,
+    ) {
+        let review = ConflictReview::new(
+            import_set,
+            "Choose how to import each conflicting value.\n\
+            For each entry: current value → imported value"
+                .to_string(),
+            self.dst_drawer_state.drawer.content.settings.hide_values,
+        );
+        self.step = Step::ReviewConflicts { source, review };
+    }
+    /// Go back to the confirmation once the review is done
+    fn on_review_conflicts(
+        &mut self,
+        source: ImportSource,
+        review: ConflictReview,
+    ) {
+        if review.is_done() {
+            self.confirm(source, review.into_import_set());
+        } else {
+            self.step = Step::ReviewConflicts { source, review };
+        }
+    }
+    /// Show what would be imported with the deep drawers, and
+    /// propose to add more of them
+    fn confirm_deep_import(
+        &mut self,
+        open_closet: OpenCloset,
+        import_set: ImportSet,
+        deep_set: DeepImportSet,
+    ) {
+        let mut menu = Menu::new();
+        menu.set_intro(format!(
+            "{}\n{}",
+            import_set.confirm_string(),
+            deep_set.confirm_string()
+        ));
+        if !(import_set.is_empty() && deep_set.is_empty()) {
+            menu.add_item(ConfirmDeepImport::Confirm, None);
+        }
+        if !import_set.conflicts().is_empty() {
+            menu.add_item(ConfirmDeepImport::ReviewConflicts, None);
+        }
+        menu.add_item(ConfirmDeepImport::AddDeepDrawer, None);
+        let shares_keys = open_closet.open_drawers().last().map_or(false, |src| {
+            src.content
+                .closet
+                .shares_keys_with(&self.dst_drawer_state.drawer.content.closet)
+        });
+        if shares_keys && !deep_set.has_closed_copies() {
+            menu.add_item(ConfirmDeepImport::CopyClosedDrawers, None);
+        }
+        menu.add_item(ConfirmDeepImport::Cancel, None);
+        self.step = Step::ConfirmDeepImport {
+            open_closet,
+            menu,
+            import_set,
+            deep_set,
+        };
+    }
+    fn on_deep_import_choice(
+        &mut self,
+        choice: ConfirmDeepImport,
+        open_closet: OpenCloset,
+        import_set: ImportSet,
+        mut deep_set: DeepImportSet,
+    ) {
+        match choice {
+            ConfirmDeepImport::Confirm => {
+                self.execute_deep_import(import_set, deep_set);
+            }
+            ConfirmDeepImport::ReviewConflicts => {
+                self.review_conflicts(
+                    ImportSource::DeepDrawers {
+                        open_closet,
+                        deep_set,
+                    },
+                    import_set,
+                );
+            }
+            ConfirmDeepImport::AddDeepDrawer => {
+                let depth = open_closet.depth();
+                let mut dialog =
+                    PasswordDialog::new(PasswordDialogPurpose::OpenDrawer { depth }, true);
+                dialog.set_hide_chars(self.hide_chars);
+                self.step = Step::TypeDeepDrawerPassword {
+                    open_closet,
+                    import_set,
+                    deep_set,
+                    dialog,
+                };
+            }
+            ConfirmDeepImport::CopyClosedDrawers => {
+                if let Some(src) = open_closet.open_drawers().last() {
+                    deep_set.add_closed_copies(
+                        &src.content.closet,
+                        &self.dst_drawer_state.drawer.content.closet,
+                    );
+                }
+                self.confirm_deep_import(open_closet, import_set, deep_set);
+            }
+            ConfirmDeepImport::Cancel => {
+                info!("import canceled");
+                self.finish();
+            }
+        }
+    }
+    /// Open a drawer of the closet of the source drawer, and add it to
+    /// the deep drawers to import, or stay in the password dialog
+    /// if it's not possible
+    fn on_deep_password(
+        &mut self,
+        dialog: PasswordDialog,
+        open_closet: OpenCloset,
+        import_set: ImportSet,
+        mut deep_set: DeepImportSet,
+    ) {
+        let password = dialog.get_password();
+        let depth = open_closet.depth();
+        let deep_drawer = open_closet
+            .open_drawers()
+            .last()
+            .and_then(|src| src.content.closet.open_drawer(depth, &password));
+        let dst_closet = &self.dst_drawer_state.drawer.content.closet;
+        match deep_drawer {
+            Some(deep_drawer) if deep_set.contains(&deep_drawer) => {
+                self.message = Some("This drawer is already in the import");
+            }
+            Some(_) if dst_closet.is_password_taken(depth, &password) => {
+                self.message = Some("A deep drawer of the destination already has this passphrase");
+            }
+            Some(deep_drawer) => {
+                self.message = None;
+                deep_set.add_opened(deep_drawer);
+                self.confirm_deep_import(open_closet, import_set, deep_set);
+                return;
+            }
+            None => {
+                info!("wrong pass");
+                self.message = Some("Wrong passphrase");
+            }
+        }
+        self.step = Step::TypeDeepDrawerPassword {
+            open_closet,
+            import_set,
+            deep_set,
+            dialog,
+        };
+    }
+    fn execute_deep_import(
+        &mut self,
+        import_set: ImportSet,
+        deep_set: DeepImportSet,
+    ) {
+        let dst = &mut self.dst_drawer_state.drawer;
+        match deep_set.import_into(dst) {
+            Ok(deep_report) => {
+                let report = import_set.import_into(dst);
+                info!("import done");
+                self.end(format!("{deep_report}\n{report}"));
+            }
+            Err(e) => {
+                warn!("deep import failed: {e}");
+                self.end(format!("Nothing was imported: {e}"));
+            }
+        }
+    }
     fn execute_import(
         &mut self,
         import_set: ImportSet,
@@ -272,6 +523,9 @@ impl ImportState {
                         ConfirmCsv::Confirm => {
                             self.execute_import(import_set);
                         }
+                        ConfirmCsv::ReviewConflicts => {
+                            self.review_conflicts(ImportSource::Csv, import_set);
+                        }
                         ConfirmCsv::Cancel => {
                             info!("import canceled");
                             self.finish();
@@ -294,6 +548,16 @@ impl ImportState {
                         ConfirmDrawer::Confirm => {
                             self.execute_import(import_set);
                         }
+                        ConfirmDrawer::ReviewConflicts => {
+                            self.review_conflicts(ImportSource::Drawer { open_closet }, import_set);
+                        }
+                        ConfirmDrawer::WithDeepDrawers => {
+                            self.confirm_deep_import(
+                                open_closet,
+                                import_set,
+                                DeepImportSet::default(),
+                            );
+                        }
                         ConfirmDrawer::GoDeeper => {
                             self.ask_password(open_closet);
                         }
@@ -313,6 +577,51 @@ impl ImportState {
                     false
                 }
             },
+            Step::TypeDeepDrawerPassword {
+                open_closet,
+                import_set,
+                deep_set,
+                mut dialog,
+            } => {
+                let mut b = true;
+                if key == key!(enter) {
+                    self.on_deep_password(dialog, open_closet, import_set, deep_set);
+                } else {
+                    b = dialog.apply_key_event(key);
+                    self.step = Step::TypeDeepDrawerPassword {
+                        open_closet,
+                        import_set,
+                        deep_set,
+                        dialog,
+                    };
+                }
+                b
+            }
+            Step::ConfirmDeepImport {
+                open_closet,
+                mut menu,
+                import_set,
+                deep_set,
+            } => match menu.state.on_key(key) {
+                Some(choice) => {
+                    self.on_deep_import_choice(choice, open_closet, import_set, deep_set);
+                    true
+                }
+                None => {
+                    self.step = Step::ConfirmDeepImport {
+                        open_closet,
+                        menu,
+                        import_set,
+                        deep_set,
+                    };
+                    false
+                }
+            },
+            Step::ReviewConflicts { source, mut review } => {
+                let b = review.apply_key_event(key);
+                self.on_review_conflicts(source, review);
+                b
+            }
             Step::InformEnd(mut menu) => {
                 if menu.state.on_key(key).is_some() {
                     self.finish();
@@ -361,6 +670,9 @@ impl ImportState {
                     ConfirmCsv::Confirm => {
                         self.execute_import(import_set);
                     }
+                    ConfirmCsv::ReviewConflicts => {
+                        self.review_conflicts(ImportSource::Csv, import_set);
+                    }
                     ConfirmCsv::Cancel => {
                         info!("import canceled");
                         self.finish();
@@ -379,6 +691,12 @@ impl ImportState {
                     ConfirmDrawer::Confirm => {
                         self.execute_import(import_set);
                     }
+                    ConfirmDrawer::ReviewConflicts => {
+                        self.review_conflicts(ImportSource::Drawer { open_closet }, import_set);
+                    }
+                    ConfirmDrawer::WithDeepDrawers => {
+                        self.confirm_deep_import(open_closet, import_set, DeepImportSet::default());
+                    }
                     ConfirmDrawer::GoDeeper => {
                         self.ask_password(open_closet);
                     }
@@ -395,6 +713,42 @@ impl ImportState {
                     };
                 }
             },
+            Step::TypeDeepDrawerPassword {
+                open_closet,
+                import_set,
+                deep_set,
+                mut dialog,
+            } => {
+                dialog.on_mouse_event(mouse_event, double_click);
+                self.step = Step::TypeDeepDrawerPassword {
+                    open_closet,
+                    import_set,
+                    deep_set,
+                    dialog,
+                };
+            }
+            Step::ConfirmDeepImport {
+                open_closet,
+                mut menu,
+                import_set,
+                deep_set,
+            } => match menu.state.on_mouse_event(mouse_event, double_click) {
+                Some(choice) => {
+                    self.on_deep_import_choice(choice, open_closet, import_set, deep_set);
+                }
+                None => {
+                    self.step = Step::ConfirmDeepImport {
+                        open_closet,
+                        menu,
+                        import_set,
+                        deep_set,
+                    };
+                }
+            },
+            Step::ReviewConflicts { source, mut review } => {
+                review.on_mouse_event(mouse_event, double_click);
+                self.on_review_conflicts(source, review);
+            }
             Step::InformEnd(mut menu) => {
                 if menu
                     .state
@@ -410,6 +764,11 @@ impl ImportState {
         }
     }
     pub fn status(&self) -> &'static str {
+        if let Step::ReviewConflicts { review, .. } = &self.step {
+            if let Some(status) = review.status() {
+                return status;
+            }
+        }
         self.message.unwrap_or("Import wizard")
     }
 }
@@ -32,6 +32,15 @@ impl ImportView {
             Step::ConfirmImportDrawer { menu, .. } => {
                 menu.set_available_area(self.available_area.clone());
             }
+            Step::TypeDeepDrawerPassword { dialog, .. } => {
+                dialog.view.set_available_area(self.available_area.clone());
+            }
+            Step::ConfirmDeepImport { menu, .. } => {
+                menu.set_available_area(self.available_area.clone());
+            }
+            Step::ReviewConflicts { review, .. } => {
+                review.menu.set_available_area(self.available_area.clone());
+            }
             Step::InformEnd(menu) => {
                 menu.set_available_area(self.available_area.clone());
             }
@@ -73,6 +82,15 @@ impl View<ImportState> for ImportView {
             Step::ConfirmImportDrawer { menu, .. } => {
                 menu.draw(w, skin)?;
             }
+            Step::TypeDeepDrawerPassword { dialog, .. } => {
+                dialog.draw(w, skin)?;
+            }
+            Step::ConfirmDeepImport { menu, .. } => {
+                menu.draw(w, skin)?;
+            }
+            Step::ReviewConflicts { review, .. } => {
+                review.menu.draw(w, skin)?;
+            }
             Step::InformEnd(menu) => {
                 menu.draw(w, skin)?;
             }
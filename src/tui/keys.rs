@@ -1,5 +1,11 @@
 use {
-    crokey::*,
+    crokey::{
+        crossterm::event::{
+            KeyCode,
+            KeyModifiers,
+        },
+        *,
+    },
     once_cell::sync::Lazy,
 };
 
@@ -8,3 +14,9 @@ pub static KEY_FORMAT: Lazy<KeyCombinationFormat> = Lazy::new(|| {
         .with_implicit_shift()
         .with_control("^")
 });
+
+/// Return the index of the entry field to copy, for the
+/// keys *alt-1* to *alt-9*
+pub fn field_copy_index(key: KeyCombination) -> Option<usize> {
+    ('1'..='9').position(|c| key == KeyCombination::new(KeyCode::Char(c), KeyModifiers::ALT))
+}
@@ -0,0 +1,42 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy)]
+pub enum ConfirmMerge {
+    Confirm,
+    ReviewConflicts,
+    AddPassword,
+    Cancel,
+}
+impl fmt::Display for ConfirmMerge {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::Confirm => write!(f, "Merge and save the closet"),
+            Self::ReviewConflicts => write!(f, "Review the conflicting values"),
+            Self::AddPassword => write!(f, "Type another passphrase"),
+            Self::Cancel => write!(f, "Cancel"),
+        }
+    }
+}
+
+/// Which version of the closet tells what each copy changed
+#[derive(Debug, Clone, Copy)]
+pub enum AncestorChoice {
+    None,
+    Backup,
+    File,
+}
+impl fmt::Display for AncestorChoice {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::None => write!(f, "No common ancestor: review every differing value"),
+            Self::Backup => write!(f, "The backup of the open closet (the '.old' file)"),
+            Self::File => write!(f, "Another file"),
+        }
+    }
+}
@@ -0,0 +1,528 @@
+use {
+    super::*,
+    crate::{
+        core::*,
+        import::{
+            ClosetMerge,
+            ImportSet,
+        },
+        tui::menu::*,
+    },
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+        key,
+    },
+    std::path::{
+        Path,
+        PathBuf,
+    },
+};
+
+#[allow(clippy::large_enum_variant)]
+pub enum MergeStep {
+    ChooseFile(FileSelector),
+    ChooseAncestor(Menu<AncestorChoice>),
+    ChooseAncestorFile(FileSelector),
+    TypePassword(PasswordDialog),
+    ReviewConflicts {
+        /// index of the drawer among the ones with conflicts
+        idx: usize,
+        review: ConflictReview,
+    },
+    Confirm(Menu<ConfirmMerge>),
+    InformEnd(InformMenu),
+    Finished,
+}
+
+/// State of the wizard merging another copy of the closet file,
+/// which diverged, into the open closet
+pub struct MergeState {
+    pub step: MergeStep,
+    /// the path of the open closet, to exclude it and find its backup
+    local_path: PathBuf,
+    /// the path of the copy to merge into the open closet
+    other_path: Option<PathBuf>,
+    /// the copy to merge into the open closet
+    other: Option<Closet>,
+    /// a version of the closet from before the copies diverged
+    ancestor: Option<Closet>,
+    /// whether the user chose the ancestor, or to go without
+    ancestor_chosen: bool,
+    /// the passphrases typed until now, all of them opening a drawer
+    passwords: Vec<String>,
+    /// the merge being reviewed
+    plan: Option<ClosetMerge>,
+    /// the merge, once confirmed, to be executed by the app state
+    pub confirmed_plan: Option<ClosetMerge>,
+    pub message: Option<&'static str>,
+    pub hide_chars: bool,
+}
+
+impl MergeState {
+    pub fn new(
+        local_path: &Path,
+        other_path: Option<PathBuf>,
+        ancestor_path: Option<PathBuf>,
+    ) -> Self {
+        let mut state = Self {
+            step: MergeStep::Finished,
+            local_path: local_path.to_path_buf(),
+            other_path: None,
+            other: None,
+            ancestor: None,
+            ancestor_chosen: false,
+            passwords: Vec::new(),
+            plan: None,
+            confirmed_plan: None,
+            message: None,
+            hide_chars: true,
+        };
+        if let Some(path) = ancestor_path {
+            if !state.on_ancestor_file_selected(path) {
+                return state;
+            }
+        }
+        match other_path {
+            Some(path) => {
+                state.on_file_selected(path);
+            }
+            None => {
+                let selector = FileSelector::new(
+                    "Enter the path of the other copy of the closet file.\n\
+                    Its drawers will be merged into the open closet."
+                        .to_string(),
+                    FileType::File,
+                );
+                state.message = Some(selector.get_message());
+                state.step = MergeStep::ChooseFile(selector);
+            }
+        }
+        state
+    }
+    pub fn toggle_hide_chars(&mut self) {
+        self.hide_chars = !self.hide_chars;
+        if let MergeStep::TypePassword(dialog) = &mut self.step {
+            dialog.set_hide_chars(self.hide_chars);
+        }
+    }
+    fn end<S: Into<String>>(
+        &mut self,
+        s: S,
+    ) {
+        self.message = None;
+        self.step = MergeStep::InformEnd(inform(s));
+    }
+    fn finish(&mut self) {
+        self.step = MergeStep::Finished;
+    }
+    // take the current step, putting Finished instead
+    fn take_step(&mut self) -> MergeStep {
+        let mut step = MergeStep::Finished;
+        std::mem::swap(&mut step, &mut self.step);
+        step
+    }
+    fn on_file_selected(
+        &mut self,
+        path: PathBuf,
+    ) {
+        if is_same_file(&path, &self.local_path) {
+            self.end("This is the open closet. Choose another copy of it.");
+            return;
+        }
+        match Closet::from_file(&path) {
+            Ok(closet) => {
+                self.other_path = Some(path);
+                self.other = Some(closet);
+                if self.ancestor_chosen {
+                    self.ask_password();
+                } else {
+                    self.choose_ancestor();
+                }
+            }
+            Err(e) => {
+                warn!("error opening file: {e}");
+                self.end("This file can't be read as a closet");
+            }
+        }
+    }
+    /// Ask which version of the closet, if any, predates the divergence
+    /// of the copies
+    fn choose_ancestor(&mut self) {
+        let mut menu = Menu::new();
+        menu.set_intro(
+            "A common ancestor is a version of the closet from before the copies diverged. \
+            A value changed in only one copy since the ancestor is then taken from this copy, \
+            without question.\n\
+            The backup of the open closet is only its version of one save ago: use it \
+            only if the open closet was saved just once since the copies diverged.\n\
+            Without ancestor, you review every differing value."
+                .to_string(),
+        );
+        menu.add_item(AncestorChoice::None, None);
+        if self.local_path.with_extension("old").exists() {
+            menu.add_item(AncestorChoice::Backup, None);
+        }
+        menu.add_item(AncestorChoice::File, None);
+        self.message = None;
+        self.step = MergeStep::ChooseAncestor(menu);
+    }
+    fn on_ancestor_choice(
+        &mut self,
+        choice: AncestorChoice,
+    ) {
+        match choice {
+            AncestorChoice::None => {
+                info!("merge without ancestor");
+                self.ancestor_chosen = true;
+                self.ask_password();
+            }
+            AncestorChoice::Backup => {
+                let backup_path = self.local_path.with_extension("old");
+                if self.on_ancestor_file_selected(backup_path) {
+                    self.ask_password();
+                }
+            }
+            AncestorChoice::File => {
+                let selector = FileSelector::new(
+                    "Enter the path of a version of the closet from before the copies diverged."
+                        .to_string(),
+                    FileType::File,
+                );
+                self.message = Some(selector.get_message());
+                self.step = MergeStep::ChooseAncestorFile(selector);
+            }
+        }
+    }
+    /// Load the ancestor, and return whether it's usable
+    fn on_ancestor_file_selected(
+        &mut self,
+        path: PathBuf,
+    ) -> bool {
+        let is_copy = is_same_file(&path, &self.local_path)
+            || self
+                .other_path
+                .as_ref()
+                .map_or(false, |other_path| is_same_file(&path, other_path));
+        if is_copy {
+            self.end("The ancestor must be an older version, not one of the copies.");
+            return false;
+        }
+        match Closet::from_file(&path) {
+            Ok(closet) => {
+                self.ancestor = Some(closet);
+                self.ancestor_chosen = true;
+                true
+            }
+            Err(e) => {
+                warn!("error opening ancestor: {e}");
+                self.end("This file can't be read as a closet");
+                false
+            }
+        }
+    }
+    fn ask_password(&mut self) {
+        let mut dialog = PasswordDialog::new(PasswordDialogPurpose::MergeClosets, true);
+        dialog.set_hide_chars(self.hide_chars);
+        self.step = MergeStep::TypePassword(dialog);
+    }
+    fn on_password(
+        &mut self,
+        dialog: PasswordDialog,
+        closet: &Closet,
+    ) {
+        let password = dialog.get_password();
+        if password.is_empty() {
+            if self.passwords.is_empty() {
+                self.message = Some("Type the passphrase of at least one drawer");
+                self.step = MergeStep::TypePassword(dialog);
+            } else {
+                self.propose_plan();
+            }
+            return;
+        }
+        if self.passwords.contains(&password) {
+            self.message = Some("This passphrase was already typed");
+            self.ask_password();
+            return;
+        }
+        let Some(other) = &self.other else {
+            self.finish();
+            return;
+        };
+        let ancestor = self.ancestor.as_ref();
+        let plan = self
+            .plan
+            .get_or_insert_with(|| ClosetMerge::new(closet, other, ancestor, &[]));
+        // The passphrase is accepted only if it opens a new drawer in
+        // either copy, which, for a deep drawer, requires the passphrase
+        // of its parent
+        let mut passwords = self.passwords.clone();
+        passwords.push(password);
+        if plan.add_password(other, ancestor, &passwords) {
+            info!(
+                "merge passphrase accepted, {} drawers found",
+                plan.found_drawers_count()
+            );
+            self.passwords = passwords;
+            self.message = Some("Passphrase accepted. Type another one or hit *enter* to continue");
+        } else {
+            info!("merge passphrase opens nothing");
+            self.message = Some(
+                "No drawer to merge found (for a deep drawer, type its parent's passphrase first)",
+            );
+        }
+        self.ask_password();
+    }
+    fn propose_plan(&mut self) {
+        let Some(plan) = &self.plan else {
+            self.finish();
+            return;
+        };
+        let mut menu = Menu::new();
+        menu.set_intro(plan.confirm_string());
+        menu.add_item(ConfirmMerge::Confirm, None);
+        if plan.conflicting_drawers_count() > 0 {
+            menu.add_item(ConfirmMerge::ReviewConflicts, None);
+        }
+        menu.add_item(ConfirmMerge::AddPassword, None);
+        menu.add_item(ConfirmMerge::Cancel, None);
+        self.message = None;
+        self.step = MergeStep::Confirm(menu);
+    }
+    /// Start the review of the conflicts of a drawer
+    fn review_conflicts(
+        &mut self,
+        idx: usize,
+    ) {
+        let Some(drawer) = self
+            .plan
+            .as_mut()
+            .and_then(|plan| plan.conflicting_drawer_mut(idx))
+        else {
+            self.propose_plan();
+            return;
+        };
+        let review = ConflictReview::new(
+            drawer.import_set.clone(),
+            format!(
+                "Choose how to merge each conflicting value of *{}*.\n\
+                For each entry: local value → value of the other copy",
+                drawer.label,
+            ),
+            drawer.hides_values(),
+        );
+        self.step = MergeStep::ReviewConflicts { idx, review };
+    }
+    fn on_review_conflicts(
+        &mut self,
+        idx: usize,
+        review: ConflictReview,
+    ) {
+        if !review.is_done() {
+            self.step = MergeStep::ReviewConflicts { idx, review };
+            return;
+        }
+        let import_set: ImportSet = review.into_import_set();
+        if let Some(drawer) = self
+            .plan
+            .as_mut()
+            .and_then(|plan| plan.conflicting_drawer_mut(idx))
+        {
+            drawer.import_set = import_set;
+        }
+        self.review_conflicts(idx + 1);
+    }
+    fn on_confirm_choice(
+        &mut self,
+        choice: ConfirmMerge,
+    ) {
+        match choice {
+            ConfirmMerge::Confirm => {
+                info!("merge confirmed");
+                self.confirmed_plan = self.plan.take();
+                self.finish();
+            }
+            ConfirmMerge::ReviewConflicts => {
+                self.review_conflicts(0);
+            }
+            ConfirmMerge::AddPassword => {
+                self.ask_password();
+            }
+            ConfirmMerge::Cancel => {
+                info!("merge canceled");
+                self.finish();
+            }
+        }
+    }
+    pub fn is_finished(&self) -> bool {
+        matches!(self.step, MergeStep::Finished)
+    }
+    pub fn apply_key_event(
+        &mut self,
+        key: KeyCombination,
+        closet: &Closet,
+    ) -> bool {
+        if key == key!(esc) {
+            self.step = MergeStep::Finished;
+            return true;
+        }
+        let step = self.take_step();
+        match step {
+            MergeStep::ChooseFile(mut selector) => {
+                let mut b = true;
+                if key == key!(enter) {
+                    if let Some(path) = selector.get_selected_file() {
+                        self.on_file_selected(path.to_path_buf());
+                    } else {
+                        self.message = Some(selector.get_message());
+                        self.step = MergeStep::ChooseFile(selector);
+                    }
+                } else {
+                    b = selector.apply_key_event(key);
+                    self.message = Some(selector.get_message());
+                    self.step = MergeStep::ChooseFile(selector);
+                }
+                b
+            }
+            MergeStep::ChooseAncestor(mut menu) => match menu.state.on_key(key) {
+                Some(choice) => {
+                    self.on_ancestor_choice(choice);
+                    true
+                }
+                None => {
+                    self.step = MergeStep::ChooseAncestor(menu);
+                    false
+                }
+            },
+            MergeStep::ChooseAncestorFile(mut selector) => {
+                let mut b = true;
+                if key == key!(enter) {
+                    if let Some(path) = selector.get_selected_file() {
+                        if self.on_ancestor_file_selected(path.to_path_buf()) {
+                            self.ask_password();
+                        }
+                    } else {
+                        self.message = Some(selector.get_message());
+                        self.step = MergeStep::ChooseAncestorFile(selector);
+                    }
+                } else {
+                    b = selector.apply_key_event(key);
+                    self.message = Some(selector.get_message());
+                    self.step = MergeStep::ChooseAncestorFile(selector);
+                }
+                b
+            }
+            MergeStep::TypePassword(mut dialog) => {
+                let mut b = true;
+                if key == key!(enter) {
+                    self.on_password(dialog, closet);
+                } else {
+                    b = dialog.apply_key_event(key);
+                    self.step = MergeStep::TypePassword(dialog);
+                }
+                b
+            }
+            MergeStep::ReviewConflicts { idx, mut review } => {
+                let b = review.apply_key_event(key);
+                self.on_review_conflicts(idx, review);
+                b
+            }
+            MergeStep::Confirm(mut menu) => match menu.state.on_key(key) {
+                Some(choice) => {
+                    self.on_confirm_choice(choice);
+                    true
+                }
+                None => {
+                    self.step = MergeStep::Confirm(menu);
+                    false
+                }
+            },
+            MergeStep::InformEnd(mut menu) => {
+                if menu.state.on_key(key).is_some() {
+                    self.finish();
+                } else {
+                    self.step = MergeStep::InformEnd(menu);
+                }
+                true
+            }
+            MergeStep::Finished => true,
+        }
+    }
+    /// handle a mouse event
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        let step = self.take_step();
+        match step {
+            MergeStep::ChooseFile(mut selector) => {
+                selector.on_mouse_event(mouse_event, double_click);
+                self.step = MergeStep::ChooseFile(selector);
+            }
+            MergeStep::ChooseAncestor(mut menu) => {
+                match menu.state.on_mouse_event(mouse_event, double_click) {
+                    Some(choice) => {
+                        self.on_ancestor_choice(choice);
+                    }
+                    None => {
+                        self.step = MergeStep::ChooseAncestor(menu);
+                    }
+                }
+            }
+            MergeStep::ChooseAncestorFile(mut selector) => {
+                selector.on_mouse_event(mouse_event, double_click);
+                self.step = MergeStep::ChooseAncestorFile(selector);
+            }
+            MergeStep::TypePassword(mut dialog) => {
+                dialog.on_mouse_event(mouse_event, double_click);
+                self.step = MergeStep::TypePassword(dialog);
+            }
+            MergeStep::ReviewConflicts { idx, mut review } => {
+                review.on_mouse_event(mouse_event, double_click);
+                self.on_review_conflicts(idx, review);
+            }
+            MergeStep::Confirm(mut menu) => {
+                match menu.state.on_mouse_event(mouse_event, double_click) {
+                    Some(choice) => {
+                        self.on_confirm_choice(choice);
+                    }
+                    None => {
+                        self.step = MergeStep::Confirm(menu);
+                    }
+                }
+            }
+            MergeStep::InformEnd(mut menu) => {
+                if menu
+                    .state
+                    .on_mouse_event(mouse_event, double_click)
+                    .is_some()
+                {
+                    self.finish();
+                } else {
+                    self.step = MergeStep::InformEnd(menu);
+                }
+            }
+            MergeStep::Finished => {}
+        }
+    }
+    pub fn status(&self) -> &'static str {
+        if let MergeStep::ReviewConflicts { review, .. } = &self.step {
+            if let Some(status) = review.status() {
+                return status;
+            }
+        }
+        self.message.unwrap_or("Merge wizard")
+    }
+}
+
+fn is_same_file(
+    a: &Path,
+    b: &Path,
+) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
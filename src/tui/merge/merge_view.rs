@@ -0,0 +1,83 @@
+use {
+    super::*,
+    crate::tui::*,
+    termimad::*,
+};
+
+#[derive(Default)]
+pub struct MergeView {
+    available_area: Area,
+}
+
+impl MergeView {
+    fn set_view_available_area(
+        &self,
+        state: &mut MergeState,
+    ) {
+        match &mut state.step {
+            MergeStep::ChooseFile(selector) | MergeStep::ChooseAncestorFile(selector) => {
+                selector
+                    .view
+                    .set_available_area(self.available_area.clone());
+            }
+            MergeStep::ChooseAncestor(menu) => {
+                menu.set_available_area(self.available_area.clone());
+            }
+            MergeStep::TypePassword(dialog) => {
+                dialog.view.set_available_area(self.available_area.clone());
+            }
+            MergeStep::ReviewConflicts { review, .. } => {
+                review.menu.set_available_area(self.available_area.clone());
+            }
+            MergeStep::Confirm(menu) => {
+                menu.set_available_area(self.available_area.clone());
+            }
+            MergeStep::InformEnd(menu) => {
+                menu.set_available_area(self.available_area.clone());
+            }
+            MergeStep::Finished => {}
+        }
+    }
+}
+
+impl View<MergeState> for MergeView {
+    fn set_available_area(
+        &mut self,
+        area: Area,
+    ) {
+        self.available_area = area;
+    }
+
+    /// Render the view in its area
+    fn draw(
+        &mut self,
+        w: &mut W,
+        state: &mut MergeState, // mutable to allow adapt to terminal size changes
+        skin: &AppSkin,
+    ) -> Result<(), SafeClosetError> {
+        self.set_view_available_area(state);
+
+        match &mut state.step {
+            MergeStep::ChooseFile(selector) | MergeStep::ChooseAncestorFile(selector) => {
+                selector.draw(w, skin)?;
+            }
+            MergeStep::ChooseAncestor(menu) => {
+                menu.draw(w, skin)?;
+            }
+            MergeStep::TypePassword(dialog) => {
+                dialog.draw(w, skin)?;
+            }
+            MergeStep::ReviewConflicts { review, .. } => {
+                review.menu.draw(w, skin)?;
+            }
+            MergeStep::Confirm(menu) => {
+                menu.draw(w, skin)?;
+            }
+            MergeStep::InformEnd(menu) => {
+                menu.draw(w, skin)?;
+            }
+            MergeStep::Finished => {}
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+mod choices;
+mod merge_state;
+mod merge_view;
+
+pub use {
+    choices::*,
+    merge_state::*,
+    merge_view::*,
+};
+
+use {
+    super::*,
+    crate::{
+        core::*,
+        import::ClosetMerge,
+    },
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+    },
+    std::path::{
+        Path,
+        PathBuf,
+    },
+    termimad::Area,
+};
+
+pub struct Merge {
+    state: MergeState,
+    view: MergeView,
+}
+
+impl Merge {
+    /// Start the wizard merging another copy into the closet at
+    /// `local_path`, asking for the path of the copy if not given,
+    /// and for the common ancestor if not given
+    pub fn new(
+        local_path: &Path,
+        other_path: Option<PathBuf>,
+        ancestor_path: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            state: MergeState::new(local_path, other_path, ancestor_path),
+            view: MergeView::default(),
+        }
+    }
+    pub fn toggle_hide_chars(&mut self) {
+        self.state.toggle_hide_chars();
+    }
+    pub fn on_key(
+        &mut self,
+        key: KeyCombination,
+        closet: &Closet,
+    ) -> bool {
+        self.state.apply_key_event(key, closet)
+    }
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        self.state.on_mouse_event(mouse_event, double_click);
+    }
+    pub fn set_available_area(
+        &mut self,
+        area: Area,
+    ) {
+        self.view.set_available_area(area);
+    }
+    pub fn draw(
+        &mut self,
+        w: &mut W,
+        app_skin: &AppSkin,
+    ) -> Result<(), SafeClosetError> {
+        self.view.draw(w, &mut self.state, app_skin)
+    }
+    pub fn status(&self) -> &'static str {
+        self.state.status()
+    }
+    pub fn is_finished(&self) -> bool {
+        self.state.is_finished()
+    }
+    /// Return the merge, if the user confirmed it
+    pub fn take_confirmed_plan(&mut self) -> Option<ClosetMerge> {
+        self.state.confirmed_plan.take()
+    }
+}
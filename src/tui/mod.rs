@@ -1,13 +1,19 @@
 mod action;
 mod app;
 mod app_state;
+mod attachments;
+mod audit;
 mod cmd_result;
 mod comments_editor;
+mod conflict_review;
 mod content_view;
 mod dialog;
 mod drawer_drawing_layout;
 mod drawer_focus;
 mod drawer_state;
+mod expiry_editor;
+mod extract_drawer;
+mod fields_editor;
 mod file_selector;
 mod global_view;
 mod help;
@@ -16,14 +22,23 @@ mod import;
 mod keys;
 mod matched_string;
 mod menu;
+mod merge;
 mod message;
 mod password_dialog;
+mod password_generator;
+mod rekey;
+mod relocate;
 mod scroll;
 mod search_state;
 mod skin;
 mod status_view;
+mod tag_facets;
+mod tags_editor;
 mod task;
+mod title_editor;
 mod title_view;
+mod transfer;
+mod value_history;
 mod view;
 
 use {
@@ -53,13 +68,19 @@ use {
 pub(crate) use {
     action::*,
     app_state::*,
+    attachments::*,
+    audit::*,
     cmd_result::*,
     comments_editor::*,
+    conflict_review::*,
     content_view::*,
     dialog::*,
     drawer_drawing_layout::*,
     drawer_focus::*,
     drawer_state::*,
+    expiry_editor::*,
+    extract_drawer::*,
+    fields_editor::*,
     file_selector::*,
     global_view::*,
     help::*,
@@ -68,19 +89,31 @@ pub(crate) use {
     keys::*,
     matched_string::*,
     menu::*,
+    merge::*,
     message::*,
     password_dialog::*,
+    password_generator::*,
+    rekey::*,
+    relocate::*,
     scroll::*,
     search_state::*,
     skin::*,
     status_view::*,
+    tag_facets::*,
+    tags_editor::*,
     task::*,
+    title_editor::*,
     title_view::*,
+    transfer::*,
+    value_history::*,
     view::*,
 };
 
 pub const MAX_INACTIVITY: Duration = Duration::from_secs(120);
 
+/// Delay between redraws of the one-time codes of TOTP entries
+pub const TOTP_REFRESH_PERIOD: Duration = Duration::from_secs(1);
+
 pub trait ScreenWriter {
     fn go_to(
         &mut self,
@@ -27,17 +27,35 @@ impl PasswordDialog {
         hide_chars: bool,
     ) -> Self {
         let state = PasswordDialogState::new(purpose, hide_chars);
-        let view = PasswordDialogView::default();
+        let view = PasswordDialogView::new(purpose);
         Self { state, view }
     }
     pub fn toggle_hide_chars(&mut self) {
-        self.state.password.password_mode ^= true;
+        let hide = !self.state.password.password_mode;
+        self.state.set_hide_chars(hide);
     }
     pub fn set_hide_chars(
         &mut self,
         hide: bool,
     ) {
-        self.state.password.password_mode = hide;
+        self.state.set_hide_chars(hide);
+    }
+    /// Set the minimal strength score of the passphrase, below
+    /// which it's refused (only for the purposes requiring a
+    /// confirmation)
+    pub fn set_min_score(
+        &mut self,
+        min_score: u8,
+    ) {
+        self.state.min_score = min_score;
+    }
+    /// Set the description of what will be lost, shown
+    /// before the input
+    pub fn set_summary(
+        &mut self,
+        summary: String,
+    ) {
+        self.state.summary = Some(summary);
     }
     pub fn get_password(&self) -> String {
         self.state.get_password()
@@ -3,4 +3,8 @@ pub enum PasswordDialogPurpose {
     NewDrawer { depth: usize },
     OpenDrawer { depth: usize },
     ChangeDrawerPassword,
+    RekeyCloset,
+    MergeClosets,
+    ExtractDeepDrawers,
+    DeleteDrawer,
 }
@@ -1,9 +1,16 @@
 use {
     super::*,
-    crate::tui::ContentSkin,
+    crate::{
+        core::{
+            Diceware,
+            PasswordStrength,
+        },
+        tui::ContentSkin,
+    },
     crokey::{
         KeyCombination,
         crossterm::event::MouseEvent,
+        key,
     },
     termimad::*,
 };
@@ -11,6 +18,19 @@ use {
 pub struct PasswordDialogState {
     pub purpose: PasswordDialogPurpose,
     pub password: InputField,
+    /// the second input, for the purposes where a typo in the
+    /// passphrase would make the drawer unreachable
+    pub confirmation: Option<InputField>,
+    /// the minimal strength score of the passphrase, when
+    /// a confirmation is required
+    pub min_score: u8,
+    /// the settings of the passphrase generator, when the
+    /// dialog proposes one
+    pub diceware: Option<Diceware>,
+    /// the last generated passphrase
+    pub generated: Option<String>,
+    /// what will be lost, when the passphrase confirms a deletion
+    pub summary: Option<String>,
 }
 
 impl PasswordDialogState {
@@ -20,15 +40,158 @@ impl PasswordDialogState {
     ) -> Self {
         let mut password = ContentSkin::make_input();
         password.password_mode = hide_chars;
-        Self { purpose, password }
+        let confirmation = match purpose {
+            PasswordDialogPurpose::NewDrawer { .. }
+            | PasswordDialogPurpose::ChangeDrawerPassword => {
+                let mut confirmation = ContentSkin::make_input();
+                confirmation.password_mode = hide_chars;
+                confirmation.set_focus(false);
+                Some(confirmation)
+            }
+            _ => None,
+        };
+        let diceware = match purpose {
+            PasswordDialogPurpose::NewDrawer { .. } => Some(Diceware::default()),
+            _ => None,
+        };
+        Self {
+            purpose,
+            password,
+            confirmation,
+            min_score: 0,
+            diceware,
+            generated: None,
+            summary: None,
+        }
     }
     pub fn get_password(&self) -> String {
         self.password.get_content()
     }
+    pub fn set_hide_chars(
+        &mut self,
+        hide: bool,
+    ) {
+        self.password.password_mode = hide;
+        if let Some(confirmation) = &mut self.confirmation {
+            confirmation.password_mode = hide;
+        }
+    }
+    pub fn strength(&self) -> PasswordStrength {
+        PasswordStrength::estimate(&self.get_password())
+    }
+    /// Tell whether the passphrase must be refused because
+    /// it's empty or below the minimal strength
+    pub fn is_too_weak(&self) -> bool {
+        self.password.is_empty() || self.strength().score() < self.min_score
+    }
+    /// Tell whether the confirmation, if required, doesn't
+    /// match the passphrase
+    pub fn is_mismatched(&self) -> bool {
+        self.confirmation.as_ref().map_or(false, |confirmation| {
+            !confirmation.is_content(&self.get_password())
+        })
+    }
+    pub fn is_confirmation_focused(&self) -> bool {
+        self.confirmation
+            .as_ref()
+            .map_or(false, |confirmation| confirmation.focused())
+    }
+    fn focus_confirmation(
+        &mut self,
+        focus: bool,
+    ) {
+        if let Some(confirmation) = &mut self.confirmation {
+            confirmation.set_focus(focus);
+            self.password.set_focus(!focus);
+        }
+    }
+    /// Handle enter and tab when a confirmation is required: move from
+    /// one input to the other, and refuse passphrases which are too weak
+    /// or not confirmed.
+    ///
+    /// Return true when the event is consumed, false when the
+    /// passphrase is accepted (and the enter key must be handled
+    /// by the application).
+    fn apply_confirmation_key(
+        &mut self,
+        key: KeyCombination,
+    ) -> bool {
+        let on_confirmation = self.is_confirmation_focused();
+        if key == key!(tab) {
+            self.focus_confirmation(!on_confirmation);
+            return true;
+        }
+        if self.is_too_weak() {
+            self.focus_confirmation(false);
+            return true;
+        }
+        if !on_confirmation {
+            self.focus_confirmation(true);
+            return true;
+        }
+        self.is_mismatched()
+    }
+    /// Return the settings of the generator if the input
+    /// still contains the passphrase it generated
+    pub fn shown_diceware(&self) -> Option<&Diceware> {
+        self.diceware.as_ref().filter(|_| {
+            self.generated
+                .as_ref()
+                .map_or(false, |generated| self.password.is_content(generated))
+        })
+    }
+    /// Fill the input with a new passphrase, made visible
+    /// so that the user can learn it
+    fn generate(&mut self) {
+        if let Some(diceware) = &self.diceware {
+            let passphrase = diceware.generate();
+            self.password.set_str(&passphrase);
+            self.password.move_to_end();
+            self.generated = Some(passphrase);
+            // the passphrase is made visible for the user to learn it,
+            // and has to be typed in the confirmation input
+            self.set_hide_chars(false);
+            if let Some(confirmation) = &mut self.confirmation {
+                confirmation.clear();
+            }
+            self.focus_confirmation(true);
+        }
+    }
     pub fn apply_key_event(
         &mut self,
         key: KeyCombination,
     ) -> bool {
+        if let Some(diceware) = &mut self.diceware {
+            let handled = if key == key!(ctrl - g) {
+                true
+            } else if key == key!(ctrl - up) {
+                diceware.add_words(1);
+                true
+            } else if key == key!(ctrl - down) {
+                diceware.add_words(-1);
+                true
+            } else if key == key!(ctrl - e) {
+                diceware.next_separator();
+                true
+            } else if key == key!(ctrl - l) {
+                diceware.language = diceware.language.next();
+                true
+            } else {
+                false
+            };
+            if handled {
+                self.generate();
+                return true;
+            }
+        }
+        if self.confirmation.is_some() && (key == key!(enter) || key == key!(tab)) {
+            return self.apply_confirmation_key(key);
+        }
+        if let Some(confirmation) = &mut self.confirmation {
+            if confirmation.apply_key_combination(key) {
+                return true;
+            }
+        }
         self.password.apply_key_combination(key)
     }
     /// handle a mouse event
@@ -37,6 +200,16 @@ impl PasswordDialogState {
         mouse_event: MouseEvent,
         double_click: bool,
     ) {
-        self.password.apply_mouse_event(mouse_event, double_click);
+        if let Some(confirmation) = &mut self.confirmation {
+            if confirmation.apply_mouse_event(mouse_event, double_click) {
+                self.password.set_focus(false);
+                return;
+            }
+        }
+        if self.password.apply_mouse_event(mouse_event, double_click) {
+            if let Some(confirmation) = &mut self.confirmation {
+                confirmation.set_focus(false);
+            }
+        }
     }
 }
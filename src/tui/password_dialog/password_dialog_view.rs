@@ -1,12 +1,18 @@
 use {
     super::*,
-    crate::tui::*,
+    crate::{
+        core::PasswordStrength,
+        tui::*,
+    },
     termimad::*,
 };
 
 #[derive(Default)]
 pub struct PasswordDialogView {
     area: Area,
+    with_confirmation: bool,
+    with_generator: bool,
+    with_summary: bool,
 }
 
 static MD_CREATE_TOP_DRAWER: &str = r#"Type the passphrase for the new top level drawer:"#;
@@ -14,14 +20,80 @@ static MD_CREATE_DEEP_DRAWER: &str = r#"Type the passphrase for this deep drawer
 static MD_OPEN_TOP_DRAWER: &str = r#"Type the passphrase of the shallow drawer you want to open:"#;
 static MD_OPEN_DEEP_DRAWER: &str = r#"Type the passphrase of the deep drawer you want to open:"#;
 static MD_CHANGE_PASSWORD: &str = r#"Type the new passphrase (the previous version will still be available in a '.old' backup file after you save once):"#;
+static MD_REKEY_CLOSET: &str = r#"Type the passphrase of a drawer to keep (deep drawers too, after their parents), or just hit *enter* when you've typed them all:"#;
+static MD_MERGE_CLOSETS: &str = r#"Type the passphrase of a drawer to merge (deep drawers too, after their parents), or just hit *enter* when you've typed them all:"#;
+static MD_EXTRACT_DEEP_DRAWERS: &str = r#"Type the passphrase of a deep drawer to copy with this one (deeper drawers too, after their parents), or just hit *enter* to write the new closet with the deep drawers typed until now:"#;
+static MD_DELETE_DRAWER: &str = r#"Type the passphrase of this drawer to delete it, with all the drawers it contains (the closet is then saved, the previous version staying in the '.old' backup file until the next save):"#;
 static MD_HIDDEN_CHARS: &str = r#"Characters are hidden. Type *^h* to toggle visibility."#;
 static MD_VISIBLE_CHARS: &str = r#"Characters are visible. Type *^h* to hide them."#;
+static MD_CONFIRM: &str = r#"Type the passphrase again:"#;
+static MD_CONFIRM_MISMATCH: &str = r#"Type the passphrase again (*it doesn't match yet*):"#;
+static MD_GENERATOR: &str = r#"Type *^g* to generate a passphrase made of random words."#;
 
 const INTERNAL_HEIGHT: u16 = 3    // intro: 3
     + 2  // pwd: 2
     + 3; // char hiding text: 3
 
+const CONFIRMATION_HEIGHT: u16 = 2 // strength: 2
+    + 1  // confirmation intro: 1
+    + 2; // confirmation: 2
+
+const GENERATOR_HEIGHT: u16 = 2;
+
+const SUMMARY_HEIGHT: u16 = 3;
+
 impl PasswordDialogView {
+    pub fn new(purpose: PasswordDialogPurpose) -> Self {
+        Self {
+            with_confirmation: matches!(
+                purpose,
+                PasswordDialogPurpose::NewDrawer { .. }
+                    | PasswordDialogPurpose::ChangeDrawerPassword
+            ),
+            with_generator: matches!(purpose, PasswordDialogPurpose::NewDrawer { .. }),
+            with_summary: matches!(purpose, PasswordDialogPurpose::DeleteDrawer),
+            ..Default::default()
+        }
+    }
+    fn strength_text(state: &PasswordDialogState) -> String {
+        if state.password.is_empty() {
+            return String::new();
+        }
+        let strength = state.strength();
+        let mut text = format!(
+            "Estimated strength: *{}* ({:.0} bits)",
+            strength.label(),
+            strength.bits,
+        );
+        if state.is_too_weak() {
+            text.push_str(&format!(
+                ", refused: at least *{}* is required",
+                PasswordStrength::score_label(state.min_score),
+            ));
+        } else if strength.score() < 3 {
+            text.push_str(", a longer passphrase would be safer");
+        }
+        text
+    }
+    fn generator_text(state: &PasswordDialogState) -> String {
+        let Some(diceware) = state.shown_diceware() else {
+            return MD_GENERATOR.to_string();
+        };
+        let separator = match diceware.separator {
+            " " => "spaces",
+            "." => "dots",
+            "_" => "underscores",
+            _ => "dashes",
+        };
+        format!(
+            "*{}* {} words separated by {}: *{:.0}* bits of entropy\n\
+            *^g* new, *^↑* *^↓* words count, *^e* separator, *^l* language",
+            diceware.word_count,
+            diceware.language,
+            separator,
+            diceware.entropy_bits(),
+        )
+    }
     fn introduction_text(state: &PasswordDialogState) -> &'static str {
         match state.purpose {
             PasswordDialogPurpose::NewDrawer { depth } => {
@@ -39,6 +111,10 @@ impl PasswordDialogView {
                 }
             }
             PasswordDialogPurpose::ChangeDrawerPassword => MD_CHANGE_PASSWORD,
+            PasswordDialogPurpose::RekeyCloset => MD_REKEY_CLOSET,
+            PasswordDialogPurpose::MergeClosets => MD_MERGE_CLOSETS,
+            PasswordDialogPurpose::ExtractDeepDrawers => MD_EXTRACT_DEEP_DRAWERS,
+            PasswordDialogPurpose::DeleteDrawer => MD_DELETE_DRAWER,
         }
     }
 }
@@ -53,7 +129,16 @@ impl View<PasswordDialogState> for PasswordDialogView {
             let dhw = (hw * 3 / 4).min(hw - 2);
             area.left = hw - dhw;
             area.width = 2 * dhw;
-            let h = INTERNAL_HEIGHT + 2;
+            let mut h = INTERNAL_HEIGHT + 2;
+            if self.with_confirmation {
+                h += CONFIRMATION_HEIGHT;
+            }
+            if self.with_generator {
+                h += GENERATOR_HEIGHT;
+            }
+            if self.with_summary {
+                h += SUMMARY_HEIGHT;
+            }
             area.top += (area.height - h) / 3;
             area.height = h;
         }
@@ -80,11 +165,42 @@ impl View<PasswordDialogState> for PasswordDialogView {
         let text = Self::introduction_text(state);
         skin.dialog.md.write_in_area_on(w, text, &area)?;
 
+        // what will be lost
+        if self.with_summary {
+            area.top += 3;
+            if let Some(summary) = &state.summary {
+                skin.dialog.md.write_in_area_on(w, summary, &area)?;
+            }
+        }
+
         // password input
         area.top += 3;
         state.password.change_area(area.left, area.top, area.width);
         state.password.display_on(w)?;
 
+        // strength and confirmation
+        if self.with_confirmation {
+            area.top += 2;
+            area.height = 1;
+            let text = Self::strength_text(state);
+            skin.dialog.md.write_in_area_on(w, &text, &area)?;
+            area.top += 2;
+            let mismatched = state.is_mismatched();
+            let Some(confirmation) = &mut state.confirmation else {
+                return Ok(());
+            };
+            let text = if !confirmation.is_empty() && mismatched {
+                MD_CONFIRM_MISMATCH
+            } else {
+                MD_CONFIRM
+            };
+            skin.dialog.md.write_in_area_on(w, text, &area)?;
+            area.top += 1;
+            confirmation.change_area(area.left, area.top, area.width);
+            confirmation.display_on(w)?;
+            area.height = 3;
+        }
+
         // chars hiding
         area.top += 2;
         let tip = if state.password.password_mode {
@@ -94,6 +210,14 @@ impl View<PasswordDialogState> for PasswordDialogView {
         };
         skin.dialog.md.write_in_area_on(w, tip, &area)?;
 
+        // passphrase generator
+        if self.with_generator {
+            area.top += 3;
+            area.height = GENERATOR_HEIGHT;
+            let text = Self::generator_text(state);
+            skin.dialog.md.write_in_area_on(w, &text, &area)?;
+        }
+
         Ok(())
     }
 }
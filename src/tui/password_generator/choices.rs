@@ -0,0 +1,33 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeneratorField {
+    Use,
+    Regenerate,
+    Policy,
+    Length,
+    Lowercase,
+    Uppercase,
+    Digits,
+    Symbols,
+    ExcludeAmbiguous,
+    SavePolicy,
+    Cancel,
+}
+
+/// An item of the generator menu, whose label shows
+/// the current value of the field
+#[derive(Debug, Clone)]
+pub struct GeneratorItem {
+    pub field: GeneratorField,
+    pub label: String,
+}
+
+impl fmt::Display for GeneratorItem {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
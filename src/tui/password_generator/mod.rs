@@ -0,0 +1,84 @@
+mod choices;
+mod password_generator_state;
+mod password_generator_view;
+
+pub use {
+    choices::*,
+    password_generator_state::*,
+    password_generator_view::*,
+};
+
+use {
+    super::*,
+    crate::core::*,
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+    },
+    termimad::Area,
+};
+
+/// A dialog generating a password for the value of an entry,
+/// according to rules which may be saved in the drawer's settings
+pub struct PasswordGenerator {
+    state: PasswordGeneratorState,
+    view: PasswordGeneratorView,
+}
+
+impl PasswordGenerator {
+    pub fn new(
+        policies: Vec<PasswordPolicy>,
+        entry_idx: usize,
+    ) -> Self {
+        let state = PasswordGeneratorState::new(policies, entry_idx);
+        let view = PasswordGeneratorView::default();
+        Self { state, view }
+    }
+    pub fn on_key(
+        &mut self,
+        key: KeyCombination,
+    ) -> bool {
+        self.state.apply_key_event(key)
+    }
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        self.state.on_mouse_event(mouse_event, double_click);
+    }
+    pub fn set_available_area(
+        &mut self,
+        area: Area,
+    ) {
+        self.view.set_available_area(area);
+    }
+    pub fn draw(
+        &mut self,
+        w: &mut W,
+        app_skin: &AppSkin,
+    ) -> Result<(), SafeClosetError> {
+        self.view.draw(w, &mut self.state, app_skin)
+    }
+    pub fn status(&self) -> &'static str {
+        self.state.status()
+    }
+    pub fn is_finished(&self) -> bool {
+        self.state.is_finished()
+    }
+    /// Return the named policies, which the user may have changed
+    pub fn policies(&self) -> &[PasswordPolicy] {
+        &self.state.policies
+    }
+    pub fn entry_idx(&self) -> usize {
+        self.state.entry_idx
+    }
+    /// Return the generated password, if the user chose to use it
+    pub fn take_accepted_password(&mut self) -> Option<String> {
+        if self.state.accepted {
+            self.state.password.take()
+        } else {
+            None
+        }
+    }
+}
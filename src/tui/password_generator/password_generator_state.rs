@@ -0,0 +1,281 @@
+use {
+    super::*,
+    crate::{
+        core::*,
+        tui::menu::*,
+    },
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+        key,
+    },
+    termimad::InputField,
+};
+
+const MIN_LENGTH: usize = 4;
+
+pub enum GeneratorStep {
+    Configure(Menu<GeneratorItem>),
+    NamePolicy(InputField),
+    Finished,
+}
+
+pub struct PasswordGeneratorState {
+    pub step: GeneratorStep,
+    /// the rules currently applied
+    pub policy: PasswordPolicy,
+    /// the named policies of the drawer
+    pub policies: Vec<PasswordPolicy>,
+    /// the last generated password, if the rules allow one
+    pub password: Option<String>,
+    /// index of the entry whose value will be filled
+    pub entry_idx: usize,
+    /// whether the user chose to use the password
+    pub accepted: bool,
+    pub message: Option<&'static str>,
+}
+
+impl PasswordGeneratorState {
+    pub fn new(
+        policies: Vec<PasswordPolicy>,
+        entry_idx: usize,
+    ) -> Self {
+        let policy = policies.first().cloned().unwrap_or_default();
+        let mut state = Self {
+            step: GeneratorStep::Finished,
+            policy,
+            policies,
+            password: None,
+            entry_idx,
+            accepted: false,
+            message: None,
+        };
+        state.regenerate(0);
+        state
+    }
+    fn finish(&mut self) {
+        self.step = GeneratorStep::Finished;
+    }
+    pub fn is_finished(&self) -> bool {
+        matches!(self.step, GeneratorStep::Finished)
+    }
+    /// Generate a new password, then rebuild the menu with
+    /// the given item selected
+    fn regenerate(
+        &mut self,
+        selection: usize,
+    ) {
+        self.password = self.policy.generate().ok();
+        let mut menu = Menu::new();
+        if let Some(password) = &self.password {
+            menu.set_intro(format!(
+                "Generated password:\n`{}`\nEstimated entropy: {:.0} bits",
+                password,
+                self.policy.entropy_bits(),
+            ));
+        } else {
+            menu.set_intro("No password can be generated with those rules.");
+        }
+        let check = |b: bool| if b { "[x]" } else { "[ ]" };
+        let items = [
+            (GeneratorField::Use, "Use this password".to_string()),
+            (
+                GeneratorField::Regenerate,
+                "Generate another one".to_string(),
+            ),
+            (
+                GeneratorField::Policy,
+                format!("Policy: {}", &self.policy.name),
+            ),
+            (
+                GeneratorField::Length,
+                format!("Length: {} (use ← and →)", self.policy.length),
+            ),
+            (
+                GeneratorField::Lowercase,
+                format!("{} Lowercase letters", check(self.policy.lowercase)),
+            ),
+            (
+                GeneratorField::Uppercase,
+                format!("{} Uppercase letters", check(self.policy.uppercase)),
+            ),
+            (
+                GeneratorField::Digits,
+                format!("{} Digits", check(self.policy.digits)),
+            ),
+            (
+                GeneratorField::Symbols,
+                format!("{} Symbols", check(self.policy.symbols)),
+            ),
+            (
+                GeneratorField::ExcludeAmbiguous,
+                format!(
+                    "{} Exclude ambiguous characters",
+                    check(self.policy.exclude_ambiguous)
+                ),
+            ),
+            (
+                GeneratorField::SavePolicy,
+                "Save these rules as a policy".to_string(),
+            ),
+            (GeneratorField::Cancel, "Cancel".to_string()),
+        ];
+        for (field, label) in items {
+            menu.add_item(GeneratorItem { field, label }, None);
+        }
+        menu.state.select(selection);
+        self.step = GeneratorStep::Configure(menu);
+    }
+    /// Switch to the next named policy (the default one
+    /// being available when there's no saved policy)
+    fn next_policy(&mut self) {
+        let mut policies = self.policies.clone();
+        if policies.is_empty() {
+            policies.push(PasswordPolicy::default());
+        }
+        let idx = policies
+            .iter()
+            .position(|p| p.name == self.policy.name)
+            .map_or(0, |idx| (idx + 1) % policies.len());
+        self.policy = policies.swap_remove(idx);
+    }
+    fn save_policy(
+        &mut self,
+        name: String,
+    ) {
+        self.policy.name = name;
+        if let Some(policy) = self
+            .policies
+            .iter_mut()
+            .find(|p| p.name == self.policy.name)
+        {
+            *policy = self.policy.clone();
+        } else {
+            self.policies.push(self.policy.clone());
+        }
+        self.message = Some("Policy saved in the drawer settings (save the drawer to keep it)");
+    }
+    fn on_item(
+        &mut self,
+        field: GeneratorField,
+        selection: usize,
+    ) {
+        self.message = None;
+        match field {
+            GeneratorField::Use => {
+                if self.password.is_some() {
+                    self.accepted = true;
+                    self.finish();
+                    return;
+                }
+                self.message = Some("Change the rules to allow a password");
+            }
+            GeneratorField::Regenerate => {}
+            GeneratorField::Policy => self.next_policy(),
+            GeneratorField::Length => {}
+            GeneratorField::Lowercase => self.policy.lowercase ^= true,
+            GeneratorField::Uppercase => self.policy.uppercase ^= true,
+            GeneratorField::Digits => self.policy.digits ^= true,
+            GeneratorField::Symbols => self.policy.symbols ^= true,
+            GeneratorField::ExcludeAmbiguous => self.policy.exclude_ambiguous ^= true,
+            GeneratorField::SavePolicy => {
+                let mut input = ContentSkin::make_input();
+                if self.policy.name != PasswordPolicy::default().name {
+                    input.set_str(&self.policy.name);
+                }
+                self.step = GeneratorStep::NamePolicy(input);
+                return;
+            }
+            GeneratorField::Cancel => {
+                self.finish();
+                return;
+            }
+        }
+        self.regenerate(selection);
+    }
+    pub fn apply_key_event(
+        &mut self,
+        key: KeyCombination,
+    ) -> bool {
+        let mut step = GeneratorStep::Finished;
+        std::mem::swap(&mut step, &mut self.step);
+        match step {
+            GeneratorStep::Configure(mut menu) => {
+                if key == key!(esc) {
+                    return true;
+                }
+                let selection = menu.state.selection;
+                let on_length = menu
+                    .state
+                    .items
+                    .get(selection)
+                    .map_or(false, |item| item.action.field == GeneratorField::Length);
+                if on_length && (key == key!(left) || key == key!(right)) {
+                    self.policy.length = if key == key!(left) {
+                        self.policy.length.saturating_sub(1).max(MIN_LENGTH)
+                    } else {
+                        (self.policy.length + 1).min(MAX_GENERATED_PASSWORD_LENGTH)
+                    };
+                    self.regenerate(selection);
+                    return true;
+                }
+                match menu.state.on_key(key) {
+                    Some(item) => {
+                        let selection = menu.state.selection;
+                        self.step = GeneratorStep::Configure(menu);
+                        self.on_item(item.field, selection);
+                        true
+                    }
+                    None => {
+                        self.step = GeneratorStep::Configure(menu);
+                        false
+                    }
+                }
+            }
+            GeneratorStep::NamePolicy(mut input) => {
+                if key == key!(esc) {
+                    self.regenerate(0);
+                } else if key == key!(enter) {
+                    let name = input.get_content().trim().to_string();
+                    if name.is_empty() {
+                        self.message = Some("The policy needs a name");
+                        self.step = GeneratorStep::NamePolicy(input);
+                    } else {
+                        self.save_policy(name);
+                        self.regenerate(0);
+                    }
+                } else {
+                    input.apply_key_combination(key);
+                    self.step = GeneratorStep::NamePolicy(input);
+                }
+                true
+            }
+            GeneratorStep::Finished => true,
+        }
+    }
+    /// handle a mouse event
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        match &mut self.step {
+            GeneratorStep::Configure(menu) => {
+                if let Some(item) = menu.state.on_mouse_event(mouse_event, double_click) {
+                    let selection = menu.state.selection;
+                    self.on_item(item.field, selection);
+                }
+            }
+            GeneratorStep::NamePolicy(input) => {
+                input.apply_mouse_event(mouse_event, double_click);
+            }
+            GeneratorStep::Finished => {}
+        }
+    }
+    pub fn status(&self) -> &'static str {
+        self.message.unwrap_or(match self.step {
+            GeneratorStep::NamePolicy(_) => "Hit *enter* to save the policy, *esc* to cancel",
+            _ => "Hit *enter* to use the password or change a rule, *esc* to cancel",
+        })
+    }
+}
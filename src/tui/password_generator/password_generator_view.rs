@@ -0,0 +1,71 @@
+use {
+    super::*,
+    crate::tui::*,
+    termimad::*,
+};
+
+static MD_POLICY_NAME: &str =
+    r#"Name of the policy (for example the site whose rules it follows):"#;
+
+const NAME_DIALOG_INTERNAL_HEIGHT: u16 = 2  // intro: 2
+    + 1; // input: 1
+
+#[derive(Default)]
+pub struct PasswordGeneratorView {
+    available_area: Area,
+}
+
+impl PasswordGeneratorView {
+    fn name_dialog_area(&self) -> Area {
+        let mut area = self.available_area.clone();
+        if area.width > 60 && area.height > 8 {
+            let hw = area.width / 2;
+            let dhw = (hw * 3 / 4).min(hw - 2);
+            area.left = hw - dhw;
+            area.width = 2 * dhw;
+            let h = NAME_DIALOG_INTERNAL_HEIGHT + 2;
+            area.top += (area.height - h) / 3;
+            area.height = h;
+        }
+        area
+    }
+}
+
+impl View<PasswordGeneratorState> for PasswordGeneratorView {
+    fn set_available_area(
+        &mut self,
+        area: Area,
+    ) {
+        self.available_area = area;
+    }
+
+    /// Render the view in its area
+    fn draw(
+        &mut self,
+        w: &mut W,
+        state: &mut PasswordGeneratorState, // mutable to allow adapt to terminal size changes
+        skin: &AppSkin,
+    ) -> Result<(), SafeClosetError> {
+        match &mut state.step {
+            GeneratorStep::Configure(menu) => {
+                menu.set_available_area(self.available_area.clone());
+                menu.draw(w, skin)?;
+            }
+            GeneratorStep::NamePolicy(input) => {
+                let area = self.name_dialog_area();
+                let border_colors = skin.dialog.md.table.compound_style.clone();
+                let mut rect = Rect::new(area.clone(), border_colors);
+                rect.set_fill(true);
+                rect.set_border_style(BORDER_STYLE_BLAND);
+                rect.draw(w)?;
+                let mut area = Area::new(area.left + 1, area.top + 1, area.width - 2, 2);
+                skin.dialog.md.write_in_area_on(w, MD_POLICY_NAME, &area)?;
+                area.top += 2;
+                input.change_area(area.left, area.top, area.width);
+                input.display_on(w)?;
+            }
+            GeneratorStep::Finished => {}
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,20 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy)]
+pub enum ConfirmRekey {
+    Confirm,
+    AddPassword,
+    Cancel,
+}
+impl fmt::Display for ConfirmRekey {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::Confirm => write!(f, "Re-encrypt the closet"),
+            Self::AddPassword => write!(f, "Type another passphrase"),
+            Self::Cancel => write!(f, "Cancel"),
+        }
+    }
+}
@@ -0,0 +1,68 @@
+mod choices;
+mod rekey_state;
+mod rekey_view;
+
+pub use {
+    choices::*,
+    rekey_state::*,
+    rekey_view::*,
+};
+
+use {
+    super::*,
+    crate::core::*,
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+    },
+    termimad::Area,
+};
+
+#[derive(Default)]
+pub struct Rekey {
+    state: RekeyState,
+    view: RekeyView,
+}
+
+impl Rekey {
+    pub fn toggle_hide_chars(&mut self) {
+        self.state.toggle_hide_chars();
+    }
+    pub fn on_key(
+        &mut self,
+        key: KeyCombination,
+        closet: &Closet,
+    ) -> bool {
+        self.state.apply_key_event(key, closet)
+    }
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        self.state.on_mouse_event(mouse_event, double_click);
+    }
+    pub fn set_available_area(
+        &mut self,
+        area: Area,
+    ) {
+        self.view.set_available_area(area);
+    }
+    pub fn draw(
+        &mut self,
+        w: &mut W,
+        app_skin: &AppSkin,
+    ) -> Result<(), SafeClosetError> {
+        self.view.draw(w, &mut self.state, app_skin)
+    }
+    pub fn status(&self) -> &'static str {
+        self.state.status()
+    }
+    pub fn is_finished(&self) -> bool {
+        self.state.is_finished()
+    }
+    /// Return the plan, if the user confirmed it
+    pub fn take_confirmed_plan(&mut self) -> Option<RekeyPlan> {
+        self.state.confirmed_plan.take()
+    }
+}
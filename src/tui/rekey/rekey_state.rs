@@ -0,0 +1,213 @@
+use {
+    super::*,
+    crate::{
+        core::*,
+        tui::menu::*,
+    },
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+        key,
+    },
+};
+
+#[allow(clippy::large_enum_variant)]
+pub enum RekeyStep {
+    TypePassword(PasswordDialog),
+    Confirm {
+        menu: Menu<ConfirmRekey>,
+        plan: RekeyPlan,
+    },
+    Finished,
+}
+
+/// State of the wizard collecting the passphrases of the drawers
+/// to keep before a rekey of the closet
+pub struct RekeyState {
+    pub step: RekeyStep,
+    /// the passphrases typed until now, all of them opening a drawer
+    passwords: Vec<String>,
+    /// the plan for the passphrases typed until now, kept between
+    /// passphrases so that the previous ones aren't derived again
+    plan: Option<RekeyPlan>,
+    /// the plan, once confirmed, to be executed by the app state
+    pub confirmed_plan: Option<RekeyPlan>,
+    pub message: Option<&'static str>,
+    pub hide_chars: bool,
+}
+
+impl Default for RekeyState {
+    fn default() -> Self {
+        Self {
+            step: RekeyStep::TypePassword(PasswordDialog::new(
+                PasswordDialogPurpose::RekeyCloset,
+                true,
+            )),
+            passwords: Vec::new(),
+            plan: None,
+            confirmed_plan: None,
+            message: None,
+            hide_chars: true,
+        }
+    }
+}
+
+impl RekeyState {
+    pub fn toggle_hide_chars(&mut self) {
+        self.hide_chars = !self.hide_chars;
+        if let RekeyStep::TypePassword(dialog) = &mut self.step {
+            dialog.set_hide_chars(self.hide_chars);
+        }
+    }
+    fn finish(&mut self) {
+        self.step = RekeyStep::Finished;
+    }
+    // take the current step, putting Finished instead
+    fn take_step(&mut self) -> RekeyStep {
+        let mut step = RekeyStep::Finished;
+        std::mem::swap(&mut step, &mut self.step);
+        step
+    }
+    fn ask_password(&mut self) {
+        let mut dialog = PasswordDialog::new(PasswordDialogPurpose::RekeyCloset, true);
+        dialog.set_hide_chars(self.hide_chars);
+        self.step = RekeyStep::TypePassword(dialog);
+    }
+    fn on_password(
+        &mut self,
+        dialog: PasswordDialog,
+        closet: &Closet,
+    ) {
+        let password = dialog.get_password();
+        if password.is_empty() {
+            if self.passwords.is_empty() {
+                self.message = Some("Type the passphrase of at least one drawer");
+                self.step = RekeyStep::TypePassword(dialog);
+            } else {
+                self.propose_plan(closet);
+            }
+            return;
+        }
+        if self.passwords.contains(&password) {
+            self.message = Some("This passphrase was already typed");
+            self.ask_password();
+            return;
+        }
+        // The passphrase is accepted only if it opens a new drawer, which,
+        // for a deep drawer, requires the passphrase of its parent
+        let plan = self.plan.get_or_insert_with(|| RekeyPlan::new(closet, &[]));
+        self.passwords.push(password);
+        if plan.add_password(closet, &self.passwords) {
+            info!("rekey passphrase accepted");
+            self.message = Some("Passphrase accepted. Type another one or hit *enter* to continue");
+        } else {
+            info!("rekey passphrase opens nothing");
+            self.passwords.pop();
+            self.message =
+                Some("No drawer found (for a deep drawer, type its parent's passphrase first)");
+        }
+        self.ask_password();
+    }
+    fn propose_plan(
+        &mut self,
+        closet: &Closet,
+    ) {
+        let plan = self
+            .plan
+            .take()
+            .unwrap_or_else(|| RekeyPlan::new(closet, &self.passwords));
+        let mut menu = Menu::new();
+        menu.set_intro(plan.confirm_string());
+        menu.add_item(ConfirmRekey::Confirm, None);
+        menu.add_item(ConfirmRekey::AddPassword, None);
+        menu.add_item(ConfirmRekey::Cancel, None);
+        self.message = None;
+        self.step = RekeyStep::Confirm { menu, plan };
+    }
+    fn on_confirm_choice(
+        &mut self,
+        choice: ConfirmRekey,
+        plan: RekeyPlan,
+    ) {
+        match choice {
+            ConfirmRekey::Confirm => {
+                info!("rekey confirmed");
+                self.confirmed_plan = Some(plan);
+                self.finish();
+            }
+            ConfirmRekey::AddPassword => {
+                self.plan = Some(plan);
+                self.ask_password();
+            }
+            ConfirmRekey::Cancel => {
+                info!("rekey canceled");
+                self.finish();
+            }
+        }
+    }
+    pub fn is_finished(&self) -> bool {
+        matches!(self.step, RekeyStep::Finished)
+    }
+    pub fn apply_key_event(
+        &mut self,
+        key: KeyCombination,
+        closet: &Closet,
+    ) -> bool {
+        if key == key!(esc) {
+            self.step = RekeyStep::Finished;
+            return true;
+        }
+        let step = self.take_step();
+        match step {
+            RekeyStep::TypePassword(mut dialog) => {
+                let mut b = true;
+                if key == key!(enter) {
+                    self.on_password(dialog, closet);
+                } else {
+                    b = dialog.apply_key_event(key);
+                    self.step = RekeyStep::TypePassword(dialog);
+                }
+                b
+            }
+            RekeyStep::Confirm { mut menu, plan } => match menu.state.on_key(key) {
+                Some(choice) => {
+                    self.on_confirm_choice(choice, plan);
+                    true
+                }
+                None => {
+                    self.step = RekeyStep::Confirm { menu, plan };
+                    false
+                }
+            },
+            RekeyStep::Finished => true,
+        }
+    }
+    /// handle a mouse event
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        let step = self.take_step();
+        match step {
+            RekeyStep::TypePassword(mut dialog) => {
+                dialog.on_mouse_event(mouse_event, double_click);
+                self.step = RekeyStep::TypePassword(dialog);
+            }
+            RekeyStep::Confirm { mut menu, plan } => {
+                match menu.state.on_mouse_event(mouse_event, double_click) {
+                    Some(choice) => {
+                        self.on_confirm_choice(choice, plan);
+                    }
+                    None => {
+                        self.step = RekeyStep::Confirm { menu, plan };
+                    }
+                }
+            }
+            RekeyStep::Finished => {}
+        }
+    }
+    pub fn status(&self) -> &'static str {
+        self.message.unwrap_or("Rekey wizard")
+    }
+}
@@ -0,0 +1,57 @@
+use {
+    super::*,
+    crate::tui::*,
+    termimad::*,
+};
+
+#[derive(Default)]
+pub struct RekeyView {
+    available_area: Area,
+}
+
+impl RekeyView {
+    fn set_view_available_area(
+        &self,
+        state: &mut RekeyState,
+    ) {
+        match &mut state.step {
+            RekeyStep::TypePassword(dialog) => {
+                dialog.view.set_available_area(self.available_area.clone());
+            }
+            RekeyStep::Confirm { menu, .. } => {
+                menu.set_available_area(self.available_area.clone());
+            }
+            RekeyStep::Finished => {}
+        }
+    }
+}
+
+impl View<RekeyState> for RekeyView {
+    fn set_available_area(
+        &mut self,
+        area: Area,
+    ) {
+        self.available_area = area;
+    }
+
+    /// Render the view in its area
+    fn draw(
+        &mut self,
+        w: &mut W,
+        state: &mut RekeyState, // mutable to allow adapt to terminal size changes
+        skin: &AppSkin,
+    ) -> Result<(), SafeClosetError> {
+        self.set_view_available_area(state);
+
+        match &mut state.step {
+            RekeyStep::TypePassword(dialog) => {
+                dialog.draw(w, skin)?;
+            }
+            RekeyStep::Confirm { menu, .. } => {
+                menu.draw(w, skin)?;
+            }
+            RekeyStep::Finished => {}
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy)]
+pub enum RelocateChoice {
+    Root,
+    /// the upper open drawer at this depth
+    Upper(usize),
+    Sibling,
+    Cancel,
+}
+impl fmt::Display for RelocateChoice {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::Root => write!(f, "To the top level of the closet"),
+            Self::Upper(0) => write!(f, "Into the top level drawer"),
+            Self::Upper(depth) => write!(f, "Into the upper drawer of level {}", depth + 1),
+            Self::Sibling => write!(f, "Into another drawer of this level (type its passphrase)"),
+            Self::Cancel => write!(f, "Cancel"),
+        }
+    }
+}
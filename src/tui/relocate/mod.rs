@@ -0,0 +1,91 @@
+mod choices;
+mod relocate_state;
+
+pub use {
+    choices::*,
+    relocate_state::*,
+};
+
+use {
+    super::*,
+    crate::core::{
+        OpenCloset,
+        RelocationTarget,
+    },
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+    },
+    termimad::Area,
+};
+
+/// A dialog choosing where to move the current drawer
+pub struct Relocate {
+    state: RelocateState,
+    available_area: Area,
+}
+
+impl Relocate {
+    pub fn new(
+        drawer_state: DrawerState,
+        upper_count: usize,
+    ) -> Self {
+        Self {
+            state: RelocateState::new(drawer_state, upper_count),
+            available_area: Area::default(),
+        }
+    }
+    pub fn toggle_hide_chars(&mut self) {
+        self.state.toggle_hide_chars();
+    }
+    pub fn on_key(
+        &mut self,
+        key: KeyCombination,
+        open_closet: &OpenCloset,
+    ) -> bool {
+        self.state.apply_key_event(key, open_closet)
+    }
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        self.state.on_mouse_event(mouse_event, double_click);
+    }
+    pub fn set_available_area(
+        &mut self,
+        area: Area,
+    ) {
+        self.available_area = area;
+    }
+    pub fn draw(
+        &mut self,
+        w: &mut W,
+        app_skin: &AppSkin,
+    ) -> Result<(), SafeClosetError> {
+        let area = self.available_area.clone();
+        match &mut self.state.step {
+            RelocateStep::ChooseTarget(menu) => {
+                menu.set_available_area(area);
+                menu.draw(w, app_skin)?;
+            }
+            RelocateStep::TypeDrawerPassword(dialog) => {
+                dialog.view.set_available_area(area);
+                dialog.draw(w, app_skin)?;
+            }
+            RelocateStep::Finished => {}
+        }
+        Ok(())
+    }
+    pub fn status(&self) -> &'static str {
+        self.state.status()
+    }
+    pub fn is_finished(&self) -> bool {
+        self.state.is_finished()
+    }
+    /// Give back the drawer, with the place to move it
+    /// to if one was chosen
+    pub fn take_back_drawer(self) -> (DrawerState, Option<RelocationTarget>) {
+        (self.state.drawer_state, self.state.target)
+    }
+}
@@ -0,0 +1,175 @@
+use {
+    super::*,
+    crate::{
+        core::*,
+        tui::menu::*,
+    },
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+        key,
+    },
+};
+
+#[allow(clippy::large_enum_variant)]
+pub enum RelocateStep {
+    ChooseTarget(Menu<RelocateChoice>),
+    TypeDrawerPassword(PasswordDialog),
+    Finished,
+}
+
+pub struct RelocateState {
+    pub drawer_state: DrawerState, // "borrowed" from the appstate
+    pub step: RelocateStep,
+    /// the chosen place, once confirmed
+    pub target: Option<RelocationTarget>,
+    pub message: Option<&'static str>,
+    pub hide_chars: bool,
+}
+
+impl RelocateState {
+    pub fn new(
+        drawer_state: DrawerState,
+        upper_count: usize,
+    ) -> Self {
+        let mut menu = Menu::new();
+        menu.set_intro(
+            "Move this drawer, with the drawers it contains, to another place.\n\
+            A decoy takes its old place, and the closet is saved.",
+        );
+        if upper_count > 0 {
+            menu.add_item(RelocateChoice::Root, None);
+        }
+        // the closet of the last upper drawer is the current one
+        for depth in 0..upper_count.saturating_sub(1) {
+            menu.add_item(RelocateChoice::Upper(depth), None);
+        }
+        menu.add_item(RelocateChoice::Sibling, None);
+        menu.add_item(RelocateChoice::Cancel, None);
+        Self {
+            drawer_state,
+            step: RelocateStep::ChooseTarget(menu),
+            target: None,
+            message: None,
+            hide_chars: true,
+        }
+    }
+    pub fn toggle_hide_chars(&mut self) {
+        self.hide_chars = !self.hide_chars;
+        if let RelocateStep::TypeDrawerPassword(dialog) = &mut self.step {
+            dialog.set_hide_chars(self.hide_chars);
+        }
+    }
+    pub fn is_finished(&self) -> bool {
+        matches!(self.step, RelocateStep::Finished)
+    }
+    fn on_choice(
+        &mut self,
+        choice: RelocateChoice,
+    ) {
+        self.step = RelocateStep::Finished;
+        match choice {
+            RelocateChoice::Root => {
+                self.target = Some(RelocationTarget::Root);
+            }
+            RelocateChoice::Upper(depth) => {
+                self.target = Some(RelocationTarget::Upper(depth));
+            }
+            RelocateChoice::Sibling => {
+                let depth = self.drawer_state.drawer.depth;
+                let mut dialog =
+                    PasswordDialog::new(PasswordDialogPurpose::OpenDrawer { depth }, true);
+                dialog.set_hide_chars(self.hide_chars);
+                self.step = RelocateStep::TypeDrawerPassword(dialog);
+            }
+            RelocateChoice::Cancel => {}
+        }
+    }
+    /// Open the drawer in which to move the current one, or
+    /// stay in the password dialog if it's not possible
+    fn on_password(
+        &mut self,
+        dialog: PasswordDialog,
+        open_closet: &OpenCloset,
+    ) {
+        let drawer = &self.drawer_state.drawer;
+        let sibling = open_closet
+            .deepest_closet()
+            .open_drawer(drawer.depth, &dialog.get_password());
+        match sibling {
+            Some(sibling) if sibling.has_same_id(drawer) => {
+                self.message = Some("That's the passphrase of the current drawer");
+                self.step = RelocateStep::TypeDrawerPassword(dialog);
+            }
+            Some(sibling) => {
+                self.target = Some(RelocationTarget::Sibling(sibling));
+                self.step = RelocateStep::Finished;
+            }
+            None => {
+                info!("wrong pass");
+                self.message = Some("Wrong passphrase");
+                self.step = RelocateStep::TypeDrawerPassword(dialog);
+            }
+        }
+    }
+    pub fn apply_key_event(
+        &mut self,
+        key: KeyCombination,
+        open_closet: &OpenCloset,
+    ) -> bool {
+        let mut step = RelocateStep::Finished;
+        std::mem::swap(&mut step, &mut self.step);
+        if key == key!(esc) {
+            return true;
+        }
+        match step {
+            RelocateStep::ChooseTarget(mut menu) => match menu.state.on_key(key) {
+                Some(choice) => {
+                    self.on_choice(choice);
+                    true
+                }
+                None => {
+                    self.step = RelocateStep::ChooseTarget(menu);
+                    false
+                }
+            },
+            RelocateStep::TypeDrawerPassword(mut dialog) => {
+                let mut b = true;
+                if key == key!(enter) {
+                    self.on_password(dialog, open_closet);
+                } else {
+                    b = dialog.apply_key_event(key);
+                    self.step = RelocateStep::TypeDrawerPassword(dialog);
+                }
+                b
+            }
+            RelocateStep::Finished => true,
+        }
+    }
+    /// handle a mouse event
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        match &mut self.step {
+            RelocateStep::ChooseTarget(menu) => {
+                if let Some(choice) = menu.state.on_mouse_event(mouse_event, double_click) {
+                    self.on_choice(choice);
+                }
+            }
+            RelocateStep::TypeDrawerPassword(dialog) => {
+                dialog.on_mouse_event(mouse_event, double_click);
+            }
+            RelocateStep::Finished => {}
+        }
+    }
+    pub fn status(&self) -> &'static str {
+        self.message.unwrap_or(match self.step {
+            RelocateStep::TypeDrawerPassword(_) => {
+                "Type the passphrase of the drawer which will contain this one, *esc* to cancel"
+            }
+            _ => "Hit *enter* to select, *esc* to cancel",
+        })
+    }
+}
@@ -52,11 +52,58 @@ impl SearchState {
         if self.input.is_empty() {
             self.result = None;
         } else {
-            let pattern = FuzzyPattern::from(&self.input.get_content());
+            let content = self.input.get_content();
+            // tokens like ">6m" filter entries on their age, tokens like
+            // "#prod" on their tags, "!due" on their expiry, the rest of
+            // the input is a pattern on names
+            let mut age_filters: Vec<AgeFilter> = Vec::new();
+            let mut expiry_filters: Vec<ExpiryFilter> = Vec::new();
+            let mut tag_filters: Vec<TagFilter> = Vec::new();
+            let mut name_tokens: Vec<&str> = Vec::new();
+            for token in content.split_whitespace() {
+                if let Some(filter) = AgeFilter::parse(token) {
+                    age_filters.push(filter);
+                } else if let Some(filter) = TagFilter::parse(token) {
+                    tag_filters.push(filter);
+                } else if let Some(filter) = ExpiryFilter::parse(token) {
+                    expiry_filters.push(filter);
+                } else {
+                    name_tokens.push(token);
+                }
+            }
+            let no_filter =
+                age_filters.is_empty() && tag_filters.is_empty() && expiry_filters.is_empty();
+            let pattern = if no_filter {
+                Some(FuzzyPattern::from(&content))
+            } else if name_tokens.is_empty() {
+                None
+            } else {
+                Some(FuzzyPattern::from(&name_tokens.join(" ")))
+            };
+            let now = unix_now();
             let mut entries: Vec<MatchingEntry> = Vec::new();
             let mut best_line: Option<usize> = None;
             for (idx, entry) in drawer.content.entries.iter().enumerate() {
-                if let Some(name_match) = pattern.find(&entry.name) {
+                if !age_filters.iter().all(|filter| filter.accepts(entry, now)) {
+                    continue;
+                }
+                if !tag_filters.iter().all(|filter| filter.accepts(entry)) {
+                    continue;
+                }
+                if !expiry_filters
+                    .iter()
+                    .all(|filter| filter.accepts(entry, now))
+                {
+                    continue;
+                }
+                let name_match = match &pattern {
+                    Some(pattern) => pattern.find(&entry.name),
+                    None => Some(NameMatch {
+                        score: 1,
+                        pos: Vec::new(),
+                    }),
+                };
+                if let Some(name_match) = name_match {
                     if let Some(bl) = best_line {
                         if entries[bl].name_match.score < name_match.score {
                             best_line = Some(entries.len());
@@ -71,6 +118,42 @@ impl SearchState {
             self.result = Some(SearchResult { entries, best_line });
         }
     }
+    /// Return the first tag the entries are filtered on, if any
+    pub fn searched_tag(&self) -> Option<String> {
+        self.input
+            .get_content()
+            .split_whitespace()
+            .find_map(TagFilter::parse)
+            .map(|filter| filter.tag)
+    }
+    /// Replace the search with a filter listing the entries
+    /// expired or expiring soon
+    pub fn filter_due(
+        &mut self,
+        drawer: &OpenDrawer,
+    ) {
+        self.input.set_str("!due");
+        self.input.move_to_end();
+        self.update(drawer);
+    }
+    /// Replace the search with a filter on the given tag, or
+    /// clear it when there's no tag
+    pub fn filter_by_tag(
+        &mut self,
+        tag: Option<&str>,
+        drawer: &OpenDrawer,
+    ) {
+        match tag {
+            Some(tag) => {
+                self.input.set_str(format!("#{tag}"));
+                self.input.move_to_end();
+                self.update(drawer);
+            }
+            None => {
+                self.clear();
+            }
+        }
+    }
     pub fn has_content(&self) -> bool {
         !self.input.is_empty()
     }
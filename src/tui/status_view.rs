@@ -129,6 +129,22 @@ impl View<AppState> for StatusView {
                     "Hit *esc* to cancel, *enter* to validate, *^q* to quit"
                 }
                 Dialog::Import(import) => import.status(),
+                Dialog::Rekey(rekey) => rekey.status(),
+                Dialog::Merge(merge) => merge.status(),
+                Dialog::PasswordGenerator(generator) => generator.status(),
+                Dialog::FieldsEditor(fields_editor) => fields_editor.status(),
+                Dialog::ValueHistory(value_history) => value_history.status(),
+                Dialog::TagsEditor(_) => "Hit *esc* to cancel, *enter* to validate",
+                Dialog::TitleEditor(_) => "Hit *esc* to cancel, *enter* to validate",
+                Dialog::TagFacets(_) => {
+                    "Hit arrows to select a tag, *enter* to filter on it, *esc* to close"
+                }
+                Dialog::Attachments(attachments) => attachments.status(),
+                Dialog::ExpiryEditor(expiry_editor) => expiry_editor.status(),
+                Dialog::Audit(audit) => audit.status(),
+                Dialog::Transfer(transfer) => transfer.status(),
+                Dialog::ExtractDrawer(extract_drawer) => extract_drawer.status(),
+                Dialog::Relocate(relocate) => relocate.status(),
             };
             skin = &app_skin.status.hint;
         }
@@ -0,0 +1,128 @@
+use {
+    super::*,
+    crate::core::*,
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+        key,
+    },
+    std::fmt,
+    termimad::Area,
+};
+
+/// An item of the tag facet list: a tag with its number
+/// of entries, or the lack of tag filtering
+#[derive(Debug, Clone)]
+pub struct TagFacet {
+    pub tag: Option<String>,
+    pub count: usize,
+}
+
+impl fmt::Display for TagFacet {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match &self.tag {
+            Some(tag) => write!(f, "#{} ({})", tag, self.count),
+            None => write!(f, "All entries ({})", self.count),
+        }
+    }
+}
+
+/// A dialog listing the tags of the drawer with their counts,
+/// to filter the entries on one of them
+pub struct TagFacets {
+    menu: Menu<TagFacet>,
+    chosen: Option<TagFacet>,
+    finished: bool,
+}
+
+impl TagFacets {
+    /// Build the list, with the tag currently searched, if any, selected
+    pub fn new(
+        content: &DrawerContent,
+        searched: Option<&str>,
+    ) -> Self {
+        let mut menu = Menu::new();
+        let tag_counts = content.tag_counts();
+        if tag_counts.is_empty() {
+            menu.set_intro("No entry of this drawer is tagged (hit *^t* to edit tags)");
+        } else {
+            menu.set_intro("Select a tag to list only its entries:");
+        }
+        menu.add_item(
+            TagFacet {
+                tag: None,
+                count: content.entries.len(),
+            },
+            None,
+        );
+        let mut selection = 0;
+        for (idx, (tag, count)) in tag_counts.into_iter().enumerate() {
+            if searched.map_or(false, |s| s.eq_ignore_ascii_case(&tag)) {
+                selection = idx + 1;
+            }
+            menu.add_item(
+                TagFacet {
+                    tag: Some(tag),
+                    count,
+                },
+                None,
+            );
+        }
+        menu.state.select(selection);
+        Self {
+            menu,
+            chosen: None,
+            finished: false,
+        }
+    }
+    pub fn on_key(
+        &mut self,
+        key: KeyCombination,
+    ) -> bool {
+        if key == key!(esc) {
+            self.finished = true;
+            return true;
+        }
+        match self.menu.state.on_key(key) {
+            Some(facet) => {
+                self.chosen = Some(facet);
+                self.finished = true;
+                true
+            }
+            None => false,
+        }
+    }
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        if let Some(facet) = self.menu.state.on_mouse_event(mouse_event, double_click) {
+            self.chosen = Some(facet);
+            self.finished = true;
+        }
+    }
+    pub fn set_available_area(
+        &mut self,
+        area: Area,
+    ) {
+        self.menu.set_available_area(area);
+    }
+    pub fn draw(
+        &mut self,
+        w: &mut W,
+        app_skin: &AppSkin,
+    ) -> Result<(), SafeClosetError> {
+        self.menu.draw(w, app_skin)
+    }
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+    /// Return the chosen facet, if the user selected one
+    pub fn take_chosen(&mut self) -> Option<TagFacet> {
+        self.chosen.take()
+    }
+}
@@ -0,0 +1,75 @@
+mod tags_editor_state;
+mod tags_editor_view;
+
+pub use {
+    tags_editor_state::*,
+    tags_editor_view::*,
+};
+
+use {
+    super::*,
+    crate::core::*,
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+    },
+    termimad::Area,
+};
+
+/// A dialog editing the tags of an entry
+pub struct TagsEditor {
+    state: TagsEditorState,
+    view: TagsEditorView,
+}
+
+impl TagsEditor {
+    pub fn new(
+        entry: &Entry,
+        entry_idx: usize,
+        content: &DrawerContent,
+    ) -> Self {
+        let state = TagsEditorState::new(entry, entry_idx, content);
+        let view = TagsEditorView::default();
+        Self { state, view }
+    }
+    pub fn on_key(
+        &mut self,
+        key: KeyCombination,
+    ) -> bool {
+        self.state.apply_key_event(key)
+    }
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        self.state.on_mouse_event(mouse_event, double_click);
+    }
+    pub fn set_available_area(
+        &mut self,
+        area: Area,
+    ) {
+        self.view.set_available_area(area);
+    }
+    pub fn draw(
+        &mut self,
+        w: &mut W,
+        app_skin: &AppSkin,
+    ) -> Result<(), SafeClosetError> {
+        self.view.draw(w, &mut self.state, app_skin)
+    }
+    pub fn is_finished(&self) -> bool {
+        self.state.finished
+    }
+    pub fn entry_idx(&self) -> usize {
+        self.state.entry_idx
+    }
+    /// Return the typed tags, if the user validated them
+    pub fn accepted_tags(&self) -> Option<String> {
+        if self.state.accepted {
+            Some(self.state.input.get_content())
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use {
+    crate::{
+        core::*,
+        tui::ContentSkin,
+    },
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+        key,
+    },
+    termimad::InputField,
+};
+
+pub struct TagsEditorState {
+    pub input: InputField,
+    /// name of the entry, for the dialog's introduction
+    pub entry_name: String,
+    /// index of the entry whose tags are edited
+    pub entry_idx: usize,
+    /// the tags already used in the drawer, as a reminder
+    pub drawer_tags: String,
+    pub accepted: bool,
+    pub finished: bool,
+}
+
+impl TagsEditorState {
+    pub fn new(
+        entry: &Entry,
+        entry_idx: usize,
+        content: &DrawerContent,
+    ) -> Self {
+        let mut input = ContentSkin::make_input();
+        input.set_str(entry.tags_string());
+        input.move_to_end();
+        let drawer_tags = content
+            .tag_counts()
+            .iter()
+            .map(|(tag, _)| format!("#{tag}"))
+            .collect::<Vec<String>>()
+            .join(" ");
+        Self {
+            input,
+            entry_name: entry.name.clone(),
+            entry_idx,
+            drawer_tags,
+            accepted: false,
+            finished: false,
+        }
+    }
+    pub fn apply_key_event(
+        &mut self,
+        key: KeyCombination,
+    ) -> bool {
+        if key == key!(esc) {
+            self.finished = true;
+        } else if key == key!(enter) {
+            self.accepted = true;
+            self.finished = true;
+        } else {
+            return self.input.apply_key_combination(key);
+        }
+        true
+    }
+    /// handle a mouse event
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        self.input.apply_mouse_event(mouse_event, double_click);
+    }
+}
@@ -0,0 +1,71 @@
+use {
+    super::*,
+    crate::tui::*,
+    termimad::*,
+};
+
+const INTERNAL_HEIGHT: u16 = 2  // introduction and input: 2
+    + 1  // blank line
+    + 2; // tags of the drawer: 2
+
+#[derive(Default)]
+pub struct TagsEditorView {
+    available_area: Area,
+}
+
+impl TagsEditorView {
+    fn dialog_area(&self) -> Area {
+        let mut area = self.available_area.clone();
+        if area.width > 60 && area.height > 10 {
+            let hw = area.width / 2;
+            let dhw = (hw * 3 / 4).min(hw - 2);
+            area.left = hw - dhw;
+            area.width = 2 * dhw;
+            let h = INTERNAL_HEIGHT + 2;
+            area.top += (area.height - h) / 3;
+            area.height = h;
+        }
+        area
+    }
+}
+
+impl View<TagsEditorState> for TagsEditorView {
+    fn set_available_area(
+        &mut self,
+        area: Area,
+    ) {
+        self.available_area = area;
+    }
+
+    /// Render the view in its area
+    fn draw(
+        &mut self,
+        w: &mut W,
+        state: &mut TagsEditorState, // mutable to allow adapt to terminal size changes
+        skin: &AppSkin,
+    ) -> Result<(), SafeClosetError> {
+        let area = self.dialog_area();
+        let border_colors = skin.dialog.md.table.compound_style.clone();
+        let mut rect = Rect::new(area.clone(), border_colors);
+        rect.set_fill(true);
+        rect.set_border_style(BORDER_STYLE_BLAND);
+        rect.draw(w)?;
+        let mut area = Area::new(area.left + 1, area.top + 1, area.width - 2, 1);
+        let intro = format!("Tags of *{}*, separated with spaces:", &state.entry_name);
+        skin.dialog.md.write_in_area_on(w, &intro, &area)?;
+        area.top += 1;
+        state.input.change_area(area.left, area.top, area.width);
+        state.input.display_on(w)?;
+        area.top += 2;
+        area.height = 2;
+        if state.drawer_tags.is_empty() {
+            skin.dialog
+                .md
+                .write_in_area_on(w, "No tag in this drawer yet", &area)?;
+        } else {
+            let known = format!("Tags of the drawer: {}", &state.drawer_tags);
+            skin.dialog.md.write_in_area_on(w, &known, &area)?;
+        }
+        Ok(())
+    }
+}
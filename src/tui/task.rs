@@ -1,3 +1,17 @@
+use {
+    super::ExtractDrawerRequest,
+    crate::{
+        core::{
+            RekeyPlan,
+            RelocationTarget,
+        },
+        import::{
+            ClosetMerge,
+            TransferPlan,
+        },
+    },
+};
+
 /// a potentially long task, which is queued before execution
 pub enum Task {
     Save,
@@ -5,6 +19,12 @@ pub enum Task {
     OpenDrawer(String),
     CloseDrawer,
     ChangePassword(String),
+    Rekey(RekeyPlan),
+    Merge(ClosetMerge),
+    DeleteDrawer,
+    Transfer(TransferPlan),
+    ExtractDrawer(ExtractDrawerRequest),
+    RelocateDrawer(RelocationTarget),
 }
 
 impl Task {
@@ -16,6 +36,12 @@ impl Task {
             Self::OpenDrawer(_) => "Opening...",
             Self::CloseDrawer => "Closing...",
             Self::ChangePassword(_) => "Changing password...",
+            Self::Rekey(_) => "Re-encrypting the closet...",
+            Self::Merge(_) => "Merging the closets...",
+            Self::DeleteDrawer => "Deleting the drawer...",
+            Self::Transfer(_) => "Transferring entries...",
+            Self::ExtractDrawer(_) => "Writing the new closet...",
+            Self::RelocateDrawer(_) => "Moving the drawer...",
         }
     }
 }
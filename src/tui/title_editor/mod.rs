@@ -0,0 +1,68 @@
+mod title_editor_state;
+mod title_editor_view;
+
+pub use {
+    title_editor_state::*,
+    title_editor_view::*,
+};
+
+use {
+    super::*,
+    crate::core::*,
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+    },
+    termimad::Area,
+};
+
+/// A dialog editing the title of the open drawer
+pub struct TitleEditor {
+    state: TitleEditorState,
+    view: TitleEditorView,
+}
+
+impl TitleEditor {
+    pub fn new(content: &DrawerContent) -> Self {
+        let state = TitleEditorState::new(content);
+        let view = TitleEditorView::default();
+        Self { state, view }
+    }
+    pub fn on_key(
+        &mut self,
+        key: KeyCombination,
+    ) -> bool {
+        self.state.apply_key_event(key)
+    }
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        self.state.on_mouse_event(mouse_event, double_click);
+    }
+    pub fn set_available_area(
+        &mut self,
+        area: Area,
+    ) {
+        self.view.set_available_area(area);
+    }
+    pub fn draw(
+        &mut self,
+        w: &mut W,
+        app_skin: &AppSkin,
+    ) -> Result<(), SafeClosetError> {
+        self.view.draw(w, &mut self.state, app_skin)
+    }
+    pub fn is_finished(&self) -> bool {
+        self.state.finished
+    }
+    /// Return the typed title, if the user validated it
+    pub fn accepted_title(&self) -> Option<String> {
+        if self.state.accepted {
+            Some(self.state.input.get_content())
+        } else {
+            None
+        }
+    }
+}
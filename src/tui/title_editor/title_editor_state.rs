@@ -0,0 +1,55 @@
+use {
+    crate::{
+        core::*,
+        tui::ContentSkin,
+    },
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+        key,
+    },
+    termimad::InputField,
+};
+
+pub struct TitleEditorState {
+    pub input: InputField,
+    pub accepted: bool,
+    pub finished: bool,
+}
+
+impl TitleEditorState {
+    pub fn new(content: &DrawerContent) -> Self {
+        let mut input = ContentSkin::make_input();
+        if let Some(title) = &content.title {
+            input.set_str(title);
+            input.move_to_end();
+        }
+        Self {
+            input,
+            accepted: false,
+            finished: false,
+        }
+    }
+    pub fn apply_key_event(
+        &mut self,
+        key: KeyCombination,
+    ) -> bool {
+        if key == key!(esc) {
+            self.finished = true;
+        } else if key == key!(enter) {
+            self.accepted = true;
+            self.finished = true;
+        } else {
+            return self.input.apply_key_combination(key);
+        }
+        true
+    }
+    /// handle a mouse event
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        self.input.apply_mouse_event(mouse_event, double_click);
+    }
+}
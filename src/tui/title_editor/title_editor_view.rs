@@ -0,0 +1,69 @@
+use {
+    super::*,
+    crate::tui::*,
+    termimad::*,
+};
+
+const INTERNAL_HEIGHT: u16 = 2  // introduction and input: 2
+    + 1  // blank line
+    + 2; // explanation: 2
+
+#[derive(Default)]
+pub struct TitleEditorView {
+    available_area: Area,
+}
+
+impl TitleEditorView {
+    fn dialog_area(&self) -> Area {
+        let mut area = self.available_area.clone();
+        if area.width > 60 && area.height > 10 {
+            let hw = area.width / 2;
+            let dhw = (hw * 3 / 4).min(hw - 2);
+            area.left = hw - dhw;
+            area.width = 2 * dhw;
+            let h = INTERNAL_HEIGHT + 2;
+            area.top += (area.height - h) / 3;
+            area.height = h;
+        }
+        area
+    }
+}
+
+impl View<TitleEditorState> for TitleEditorView {
+    fn set_available_area(
+        &mut self,
+        area: Area,
+    ) {
+        self.available_area = area;
+    }
+
+    /// Render the view in its area
+    fn draw(
+        &mut self,
+        w: &mut W,
+        state: &mut TitleEditorState, // mutable to allow adapt to terminal size changes
+        skin: &AppSkin,
+    ) -> Result<(), SafeClosetError> {
+        let area = self.dialog_area();
+        let border_colors = skin.dialog.md.table.compound_style.clone();
+        let mut rect = Rect::new(area.clone(), border_colors);
+        rect.set_fill(true);
+        rect.set_border_style(BORDER_STYLE_BLAND);
+        rect.draw(w)?;
+        let mut area = Area::new(area.left + 1, area.top + 1, area.width - 2, 1);
+        skin.dialog
+            .md
+            .write_in_area_on(w, "Title of the drawer:", &area)?;
+        area.top += 1;
+        state.input.change_area(area.left, area.top, area.width);
+        state.input.display_on(w)?;
+        area.top += 2;
+        area.height = 2;
+        skin.dialog.md.write_in_area_on(
+            w,
+            "The title is crypted with the drawer and shown in the title bar when it's open.",
+            &area,
+        )?;
+        Ok(())
+    }
+}
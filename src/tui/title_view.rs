@@ -6,10 +6,29 @@ use {
         minimad::{
             Alignment,
             Composite,
+            Compound,
         },
     },
+    unicode_width::UnicodeWidthStr,
 };
 
+/// A clickable part of the breadcrumb of the title bar
+pub struct Crumb {
+    pub left: u16,
+    pub right: u16,
+    /// number of drawers which stay open when it's clicked
+    pub open_count: usize,
+}
+
+impl Crumb {
+    pub fn contains(
+        &self,
+        column: u16,
+    ) -> bool {
+        self.left <= column && column < self.right
+    }
+}
+
 #[derive(Default)]
 pub struct TitleView {
     area: Area,
@@ -28,18 +47,84 @@ impl View<AppState> for TitleView {
         state: &mut AppState,
         app_skin: &AppSkin,
     ) -> Result<(), SafeClosetError> {
-        let path = state.open_closet.path().to_string_lossy();
-        let md = format!(" **SafeCloset** ` ` {} ` ` {} ", &path, state_info(state));
-        let composite = Composite::from_inline(&md);
+        let path = state.open_closet.path().to_string_lossy().to_string();
+        let labels = drawer_labels(state);
+        let mut composite = Composite::new();
+        let mut crumbs = Vec::new();
+        let mut x = self.area.left;
+        push(&mut composite, &mut x, Compound::raw_str(" "));
+        push(
+            &mut composite,
+            &mut x,
+            Compound::raw_str("SafeCloset").bold(),
+        );
+        push(&mut composite, &mut x, Compound::raw_str(" "));
+        push(&mut composite, &mut x, Compound::raw_str(" ").code());
+        push(&mut composite, &mut x, Compound::raw_str(" "));
+        // the closet, then the chain of open drawers, each one
+        // closing the deeper ones when clicked
+        let (left, right) = push(&mut composite, &mut x, Compound::raw_str(&path));
+        if !labels.is_empty() {
+            crumbs.push(Crumb {
+                left,
+                right,
+                open_count: 0,
+            });
+        }
+        for (depth, label) in labels.iter().enumerate() {
+            push(&mut composite, &mut x, Compound::raw_str(" › "));
+            let (left, right) = push(&mut composite, &mut x, Compound::raw_str(label).bold());
+            if depth + 1 < labels.len() {
+                crumbs.push(Crumb {
+                    left,
+                    right,
+                    open_count: depth + 1,
+                });
+            }
+        }
+        push(&mut composite, &mut x, Compound::raw_str(" "));
+        push(&mut composite, &mut x, Compound::raw_str(" ").code());
+        push(&mut composite, &mut x, Compound::raw_str(" "));
+        composite
+            .compounds
+            .extend(Composite::from_inline(state_info(state)).compounds);
         w.go_to(self.area.left, self.area.top)?;
         let width = self.area.width as usize;
         app_skin
             .title
             .write_composite_fill(w, composite, width, Alignment::Unspecified)?;
+        state.crumbs = crumbs;
         Ok(())
     }
 }
 
+/// Add a compound to the composite, and return the columns it covers
+fn push<'s>(
+    composite: &mut Composite<'s>,
+    x: &mut u16,
+    compound: Compound<'s>,
+) -> (u16, u16) {
+    let left = *x;
+    *x = x.saturating_add(compound.as_str().width() as u16);
+    composite.compounds.push(compound);
+    (left, *x)
+}
+
+/// Return the labels of the open drawers, from the top level one
+/// to the deepest one, which is the one displayed
+fn drawer_labels(state: &AppState) -> Vec<String> {
+    state
+        .open_closet
+        .open_drawers()
+        .iter()
+        .chain(state.drawer_state.as_ref().map(|ds| &ds.drawer))
+        .map(|drawer| match &drawer.content.title {
+            Some(title) => title.clone(),
+            None => format!("level {}", drawer.depth + 1),
+        })
+        .collect()
+}
+
 fn state_info(state: &AppState) -> &'static str {
     match &state.drawer_state {
         None => {
@@ -0,0 +1,61 @@
+use {
+    crate::import::TransferMode,
+    std::fmt,
+};
+
+/// Entries to transfer, and whether to copy or move them
+#[derive(Debug, Clone)]
+pub struct EntriesChoice {
+    pub mode: TransferMode,
+    /// indexes of the entries in the drawer
+    pub entries: Vec<usize>,
+    pub label: String,
+}
+impl fmt::Display for EntriesChoice {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum TargetChoice {
+    /// an upper open drawer, at this depth
+    Upper(usize),
+    Sibling,
+    Deep,
+    Cancel,
+}
+impl fmt::Display for TargetChoice {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::Upper(0) => write!(f, "The top level drawer"),
+            Self::Upper(depth) => write!(f, "The upper drawer of level {}", depth + 1),
+            Self::Sibling => write!(f, "Another drawer of this level (type its passphrase)"),
+            Self::Deep => write!(f, "A deep drawer of this one (type its passphrase)"),
+            Self::Cancel => write!(f, "Cancel"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ConfirmTransfer {
+    Confirm(TransferMode),
+    Cancel,
+}
+impl fmt::Display for ConfirmTransfer {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::Confirm(mode) => write!(f, "{mode} the entries and save both drawers"),
+            Self::Cancel => write!(f, "Cancel"),
+        }
+    }
+}
@@ -0,0 +1,83 @@
+mod choices;
+mod transfer_state;
+mod transfer_view;
+
+pub use {
+    choices::*,
+    transfer_state::*,
+    transfer_view::*,
+};
+
+use {
+    super::*,
+    crate::{
+        core::OpenCloset,
+        import::TransferPlan,
+    },
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+    },
+    termimad::Area,
+};
+
+/// A dialog to copy or move entries of the current drawer
+/// to another one
+pub struct Transfer {
+    state: TransferState,
+    view: TransferView,
+}
+
+impl Transfer {
+    pub fn new(
+        drawer_state: DrawerState,
+        upper_count: usize,
+    ) -> Self {
+        let state = TransferState::new(drawer_state, upper_count);
+        let view = TransferView::default();
+        Self { state, view }
+    }
+    pub fn toggle_hide_chars(&mut self) {
+        self.state.toggle_hide_chars();
+    }
+    pub fn on_key(
+        &mut self,
+        key: KeyCombination,
+        open_closet: &OpenCloset,
+    ) -> bool {
+        self.state.apply_key_event(key, open_closet)
+    }
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+        open_closet: &OpenCloset,
+    ) {
+        self.state
+            .on_mouse_event(mouse_event, double_click, open_closet);
+    }
+    pub fn set_available_area(
+        &mut self,
+        area: Area,
+    ) {
+        self.view.set_available_area(area);
+    }
+    pub fn draw(
+        &mut self,
+        w: &mut W,
+        app_skin: &AppSkin,
+    ) -> Result<(), SafeClosetError> {
+        self.view.draw(w, &mut self.state, app_skin)
+    }
+    pub fn status(&self) -> &'static str {
+        self.state.status()
+    }
+    pub fn is_finished(&self) -> bool {
+        self.state.is_finished()
+    }
+    /// Give back the drawer, with the transfer to execute
+    /// if it was confirmed
+    pub fn take_back_drawer(self) -> (DrawerState, Option<TransferPlan>) {
+        (self.state.drawer_state, self.state.plan)
+    }
+}
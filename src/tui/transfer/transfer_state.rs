@@ -0,0 +1,346 @@
+use {
+    super::*,
+    crate::{
+        core::*,
+        import::*,
+        tui::menu::*,
+    },
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+        key,
+    },
+};
+
+#[allow(clippy::large_enum_variant)]
+pub enum TransferStep {
+    ChooseEntries(Menu<EntriesChoice>),
+    ChooseTarget(Menu<TargetChoice>),
+    TypeDrawerPassword {
+        /// whether the drawer is in the current one (or beside it)
+        deep: bool,
+        dialog: PasswordDialog,
+    },
+    Confirm {
+        menu: Menu<ConfirmTransfer>,
+        target: TransferTarget,
+    },
+    Finished,
+}
+
+pub struct TransferState {
+    pub drawer_state: DrawerState, // "borrowed" from the appstate
+    pub step: TransferStep,
+    /// number of upper open drawers
+    upper_count: usize,
+    mode: TransferMode,
+    entries: Vec<usize>,
+    /// the confirmed transfer, to execute once the dialog is closed
+    pub plan: Option<TransferPlan>,
+    pub message: Option<&'static str>,
+    pub hide_chars: bool,
+}
+
+impl TransferState {
+    /// Create the state, with the entries to choose being the selected
+    /// one and the ones matching the search
+    pub fn new(
+        drawer_state: DrawerState,
+        upper_count: usize,
+    ) -> Self {
+        let mut menu = Menu::new();
+        menu.set_intro("Copy or move entries to another drawer.");
+        let selected = drawer_state
+            .focus
+            .line()
+            .and_then(|line| drawer_state.listed_entry_idx(line))
+            .and_then(|idx| {
+                drawer_state
+                    .drawer
+                    .content
+                    .entries
+                    .get(idx)
+                    .map(|e| (idx, e))
+            });
+        if let Some((idx, entry)) = selected {
+            for mode in [TransferMode::Move, TransferMode::Copy] {
+                menu.add_item(
+                    EntriesChoice {
+                        mode,
+                        entries: vec![idx],
+                        label: format!("{mode} the selected entry (*{}*)", entry.name),
+                    },
+                    None,
+                );
+            }
+        }
+        let matching: Vec<usize> = drawer_state
+            .search
+            .result
+            .as_ref()
+            .map(|result| result.entries.iter().map(|m| m.idx).collect())
+            .unwrap_or_default();
+        if matching.len() > 1 {
+            for mode in [TransferMode::Move, TransferMode::Copy] {
+                menu.add_item(
+                    EntriesChoice {
+                        mode,
+                        entries: matching.clone(),
+                        label: format!("{mode} the {} entries matching the search", matching.len()),
+                    },
+                    None,
+                );
+            }
+        }
+        Self {
+            drawer_state,
+            step: TransferStep::ChooseEntries(menu),
+            upper_count,
+            mode: TransferMode::Copy,
+            entries: Vec::new(),
+            plan: None,
+            message: None,
+            hide_chars: true,
+        }
+    }
+    pub fn toggle_hide_chars(&mut self) {
+        self.hide_chars = !self.hide_chars;
+        if let TransferStep::TypeDrawerPassword { dialog, .. } = &mut self.step {
+            dialog.set_hide_chars(self.hide_chars);
+        }
+    }
+    pub fn is_finished(&self) -> bool {
+        matches!(self.step, TransferStep::Finished)
+    }
+    fn finish(&mut self) {
+        self.step = TransferStep::Finished;
+    }
+    // take the current step, putting Finished instead
+    fn take_step(&mut self) -> TransferStep {
+        let mut step = TransferStep::Finished;
+        std::mem::swap(&mut step, &mut self.step);
+        step
+    }
+    fn on_entries_choice(
+        &mut self,
+        choice: EntriesChoice,
+    ) {
+        self.mode = choice.mode;
+        self.entries = choice.entries;
+        let mut menu = Menu::new();
+        menu.set_intro(format!(
+            "{} {} entries to which drawer?",
+            self.mode,
+            self.entries.len()
+        ));
+        for depth in (0..self.upper_count).rev() {
+            menu.add_item(TargetChoice::Upper(depth), None);
+        }
+        menu.add_item(TargetChoice::Sibling, None);
+        menu.add_item(TargetChoice::Deep, None);
+        menu.add_item(TargetChoice::Cancel, None);
+        self.step = TransferStep::ChooseTarget(menu);
+    }
+    fn on_target_choice(
+        &mut self,
+        choice: TargetChoice,
+        open_closet: &OpenCloset,
+    ) {
+        match choice {
+            TargetChoice::Upper(depth) => {
+                if let Some(dst) = open_closet.open_drawers().get(depth) {
+                    let import_set = ImportSet::new(self.transferred_entries(), dst);
+                    self.confirm(import_set, TransferTarget::Upper(depth));
+                } else {
+                    self.finish();
+                }
+            }
+            TargetChoice::Sibling | TargetChoice::Deep => {
+                let deep = matches!(choice, TargetChoice::Deep);
+                let depth = self.drawer_state.drawer.depth + usize::from(deep);
+                let mut dialog =
+                    PasswordDialog::new(PasswordDialogPurpose::OpenDrawer { depth }, true);
+                dialog.set_hide_chars(self.hide_chars);
+                self.step = TransferStep::TypeDrawerPassword { deep, dialog };
+            }
+            TargetChoice::Cancel => {
+                self.finish();
+            }
+        }
+    }
+    fn transferred_entries(&self) -> Vec<Entry> {
+        let entries = &self.drawer_state.drawer.content.entries;
+        self.entries
+            .iter()
+            .filter_map(|&idx| entries.get(idx))
+            .cloned()
+            .collect()
+    }
+    fn confirm(
+        &mut self,
+        import_set: ImportSet,
+        target: TransferTarget,
+    ) {
+        let mut menu = Menu::new();
+        let mut intro = import_set.confirm_string();
+        if self.mode == TransferMode::Move {
+            intro.push_str("\nThe entries will be removed from this drawer.");
+        }
+        menu.set_intro(intro);
+        menu.add_item(ConfirmTransfer::Confirm(self.mode), None);
+        menu.add_item(ConfirmTransfer::Cancel, None);
+        self.step = TransferStep::Confirm { menu, target };
+    }
+    /// Open the destination drawer with the typed passphrase, or
+    /// stay in the password dialog if it's not possible
+    fn on_password(
+        &mut self,
+        deep: bool,
+        dialog: PasswordDialog,
+        open_closet: &OpenCloset,
+    ) {
+        let password = dialog.get_password();
+        let src = &self.drawer_state.drawer;
+        let dst = if deep {
+            src.content.closet.open_drawer(src.depth + 1, &password)
+        } else {
+            open_closet
+                .deepest_closet()
+                .open_drawer(src.depth, &password)
+        };
+        match dst {
+            Some(dst) if dst.has_same_id(src) => {
+                self.message = Some("That's the passphrase of the current drawer");
+                self.step = TransferStep::TypeDrawerPassword { deep, dialog };
+            }
+            Some(dst) => {
+                self.message = None;
+                let import_set = ImportSet::new(self.transferred_entries(), &dst);
+                let target = if deep {
+                    TransferTarget::Deep(dst)
+                } else {
+                    TransferTarget::Sibling(dst)
+                };
+                self.confirm(import_set, target);
+            }
+            None => {
+                info!("wrong pass");
+                self.message = Some("Wrong passphrase");
+                self.step = TransferStep::TypeDrawerPassword { deep, dialog };
+            }
+        }
+    }
+    fn on_confirm(
+        &mut self,
+        choice: ConfirmTransfer,
+        target: TransferTarget,
+    ) {
+        if let ConfirmTransfer::Confirm(mode) = choice {
+            self.plan = Some(TransferPlan {
+                mode,
+                entries: std::mem::take(&mut self.entries),
+                target,
+            });
+        }
+        self.finish();
+    }
+    pub fn apply_key_event(
+        &mut self,
+        key: KeyCombination,
+        open_closet: &OpenCloset,
+    ) -> bool {
+        if key == key!(esc) {
+            self.finish();
+            return true;
+        }
+        let step = self.take_step();
+        match step {
+            TransferStep::ChooseEntries(mut menu) => match menu.state.on_key(key) {
+                Some(choice) => {
+                    self.on_entries_choice(choice);
+                    true
+                }
+                None => {
+                    self.step = TransferStep::ChooseEntries(menu);
+                    false
+                }
+            },
+            TransferStep::ChooseTarget(mut menu) => match menu.state.on_key(key) {
+                Some(choice) => {
+                    self.on_target_choice(choice, open_closet);
+                    true
+                }
+                None => {
+                    self.step = TransferStep::ChooseTarget(menu);
+                    false
+                }
+            },
+            TransferStep::TypeDrawerPassword { deep, mut dialog } => {
+                let mut b = true;
+                if key == key!(enter) {
+                    self.on_password(deep, dialog, open_closet);
+                } else {
+                    b = dialog.apply_key_event(key);
+                    self.step = TransferStep::TypeDrawerPassword { deep, dialog };
+                }
+                b
+            }
+            TransferStep::Confirm { mut menu, target } => match menu.state.on_key(key) {
+                Some(choice) => {
+                    self.on_confirm(choice, target);
+                    true
+                }
+                None => {
+                    self.step = TransferStep::Confirm { menu, target };
+                    false
+                }
+            },
+            TransferStep::Finished => true,
+        }
+    }
+    /// handle a mouse event
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+        open_closet: &OpenCloset,
+    ) {
+        let step = self.take_step();
+        match step {
+            TransferStep::ChooseEntries(mut menu) => {
+                if let Some(choice) = menu.state.on_mouse_event(mouse_event, double_click) {
+                    self.on_entries_choice(choice);
+                } else {
+                    self.step = TransferStep::ChooseEntries(menu);
+                }
+            }
+            TransferStep::ChooseTarget(mut menu) => {
+                if let Some(choice) = menu.state.on_mouse_event(mouse_event, double_click) {
+                    self.on_target_choice(choice, open_closet);
+                } else {
+                    self.step = TransferStep::ChooseTarget(menu);
+                }
+            }
+            TransferStep::TypeDrawerPassword { deep, mut dialog } => {
+                dialog.on_mouse_event(mouse_event, double_click);
+                self.step = TransferStep::TypeDrawerPassword { deep, dialog };
+            }
+            TransferStep::Confirm { mut menu, target } => {
+                if let Some(choice) = menu.state.on_mouse_event(mouse_event, double_click) {
+                    self.on_confirm(choice, target);
+                } else {
+                    self.step = TransferStep::Confirm { menu, target };
+                }
+            }
+            TransferStep::Finished => {}
+        }
+    }
+    pub fn status(&self) -> &'static str {
+        self.message.unwrap_or(match self.step {
+            TransferStep::TypeDrawerPassword { .. } => {
+                "Type the passphrase of the destination drawer, *esc* to cancel"
+            }
+            _ => "Hit *enter* to select, *esc* to cancel",
+        })
+    }
+}
@@ -0,0 +1,49 @@
+use {
+    super::*,
+    crate::tui::*,
+    termimad::*,
+};
+
+#[derive(Default)]
+pub struct TransferView {
+    available_area: Area,
+}
+
+impl View<TransferState> for TransferView {
+    fn set_available_area(
+        &mut self,
+        area: Area,
+    ) {
+        self.available_area = area;
+    }
+
+    /// Render the view in its area
+    fn draw(
+        &mut self,
+        w: &mut W,
+        state: &mut TransferState, // mutable to allow adapt to terminal size changes
+        skin: &AppSkin,
+    ) -> Result<(), SafeClosetError> {
+        let area = self.available_area.clone();
+        match &mut state.step {
+            TransferStep::ChooseEntries(menu) => {
+                menu.set_available_area(area);
+                menu.draw(w, skin)?;
+            }
+            TransferStep::ChooseTarget(menu) => {
+                menu.set_available_area(area);
+                menu.draw(w, skin)?;
+            }
+            TransferStep::TypeDrawerPassword { dialog, .. } => {
+                dialog.view.set_available_area(area);
+                dialog.draw(w, skin)?;
+            }
+            TransferStep::Confirm { menu, .. } => {
+                menu.set_available_area(area);
+                menu.draw(w, skin)?;
+            }
+            TransferStep::Finished => {}
+        }
+        Ok(())
+    }
+}
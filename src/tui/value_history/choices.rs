@@ -0,0 +1,27 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryAction {
+    /// a past value, by index in the history
+    Past(usize),
+    Depth,
+    PurgeEntry,
+    PurgeDrawer,
+    Close,
+}
+
+/// An item of the history menu
+#[derive(Debug, Clone)]
+pub struct HistoryItem {
+    pub action: HistoryAction,
+    pub label: String,
+}
+
+impl fmt::Display for HistoryItem {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
@@ -0,0 +1,88 @@
+mod choices;
+mod value_history_state;
+
+pub use {
+    choices::*,
+    value_history_state::*,
+};
+
+use {
+    super::*,
+    crate::core::*,
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+    },
+    termimad::Area,
+};
+
+/// A dialog browsing the previous values of an entry, to
+/// restore or copy one of them, and managing the history depth
+pub struct ValueHistory {
+    state: ValueHistoryState,
+}
+
+impl ValueHistory {
+    pub fn new(
+        entry: &Entry,
+        entry_idx: usize,
+        settings: &DrawerSettings,
+    ) -> Self {
+        let state = ValueHistoryState::new(entry, entry_idx, settings);
+        Self { state }
+    }
+    pub fn on_key(
+        &mut self,
+        key: KeyCombination,
+    ) -> bool {
+        self.state.apply_key_event(key)
+    }
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        self.state.on_mouse_event(mouse_event, double_click);
+    }
+    pub fn set_available_area(
+        &mut self,
+        area: Area,
+    ) {
+        self.state.menu.set_available_area(area);
+    }
+    pub fn draw(
+        &mut self,
+        w: &mut W,
+        app_skin: &AppSkin,
+    ) -> Result<(), SafeClosetError> {
+        self.state.menu.draw(w, app_skin)
+    }
+    pub fn status(&self) -> &'static str {
+        self.state.status()
+    }
+    pub fn is_finished(&self) -> bool {
+        self.state.finished
+    }
+    pub fn entry_idx(&self) -> usize {
+        self.state.entry_idx
+    }
+    pub fn depth(&self) -> usize {
+        self.state.depth
+    }
+    /// Return the history of the entry, possibly purged or truncated
+    pub fn history(&self) -> &[PastValue] {
+        &self.state.history
+    }
+    /// Return the index in the history of the value to restore, if any
+    pub fn restored(&self) -> Option<usize> {
+        self.state.restored
+    }
+    /// Tell whether the user asked to purge the histories of all entries
+    pub fn purge_drawer(&self) -> bool {
+        self.state.purge_drawer
+    }
+    /// Return the past value the user asked to copy, if any
+    pub fn take_copy_request(&mut self) -> Option<String> {
+        self.state.copy_request.take()
+    }
+}
@@ -0,0 +1,193 @@
+use {
+    super::*,
+    crate::{
+        core::*,
+        tui::menu::*,
+    },
+    crokey::{
+        KeyCombination,
+        crossterm::event::MouseEvent,
+        key,
+    },
+};
+
+pub struct ValueHistoryState {
+    pub menu: Menu<HistoryItem>,
+    entry_name: String,
+    /// index of the entry whose history is browsed
+    pub entry_idx: usize,
+    /// the previous values of the entry, most recent first
+    pub history: Vec<PastValue>,
+    /// whether values must be masked in the list
+    masked: bool,
+    /// the history depth of the drawer
+    pub depth: usize,
+    /// index in the history of the value to restore
+    pub restored: Option<usize>,
+    /// whether the histories of all entries must be purged
+    pub purge_drawer: bool,
+    /// a past value to copy in the clipboard
+    pub copy_request: Option<String>,
+    pub finished: bool,
+    pub message: Option<&'static str>,
+}
+
+impl ValueHistoryState {
+    pub fn new(
+        entry: &Entry,
+        entry_idx: usize,
+        settings: &DrawerSettings,
+    ) -> Self {
+        let mut state = Self {
+            menu: Menu::new(),
+            entry_name: entry.name.clone(),
+            entry_idx,
+            history: entry.history.clone(),
+            masked: settings.hide_values,
+            depth: settings.history_depth,
+            restored: None,
+            purge_drawer: false,
+            copy_request: None,
+            finished: false,
+            message: None,
+        };
+        state.rebuild(0);
+        state
+    }
+    /// Rebuild the menu with the given item selected
+    fn rebuild(
+        &mut self,
+        selection: usize,
+    ) {
+        let mut menu = Menu::new();
+        if self.history.is_empty() {
+            menu.set_intro(format!("No previous value of *{}*", &self.entry_name));
+        } else {
+            menu.set_intro(format!(
+                "Previous values of *{}*, with the date they were replaced:",
+                &self.entry_name,
+            ));
+        }
+        for (idx, past) in self.history.iter().enumerate() {
+            let value = if self.masked {
+                "▦▦▦▦▦▦"
+            } else {
+                past.value.split('\n').next().unwrap_or("")
+            };
+            let label = format!("{}  {}", format_date(past.replaced), value);
+            menu.add_item(
+                HistoryItem {
+                    action: HistoryAction::Past(idx),
+                    label,
+                },
+                None,
+            );
+        }
+        let items = [
+            (
+                HistoryAction::Depth,
+                format!("Values kept per entry: {} (use ← and →)", self.depth),
+            ),
+            (
+                HistoryAction::PurgeEntry,
+                "Purge the history of this entry".to_string(),
+            ),
+            (
+                HistoryAction::PurgeDrawer,
+                "Purge the histories of all entries of the drawer".to_string(),
+            ),
+            (HistoryAction::Close, "Close".to_string()),
+        ];
+        for (action, label) in items {
+            menu.add_item(HistoryItem { action, label }, None);
+        }
+        menu.state.select(selection);
+        self.menu = menu;
+    }
+    fn selected_action(&self) -> Option<HistoryAction> {
+        self.menu
+            .state
+            .items
+            .get(self.menu.state.selection)
+            .map(|item| item.action.action)
+    }
+    fn on_item(
+        &mut self,
+        action: HistoryAction,
+    ) {
+        self.message = None;
+        match action {
+            HistoryAction::Past(idx) => {
+                self.restored = Some(idx);
+                self.finished = true;
+            }
+            HistoryAction::Depth => {}
+            HistoryAction::PurgeEntry => {
+                self.history.clear();
+                self.message = Some("History purged (save the drawer to make it definitive)");
+                self.rebuild(0);
+            }
+            HistoryAction::PurgeDrawer => {
+                self.history.clear();
+                self.purge_drawer = true;
+                self.message = Some("All histories purged (save the drawer to make it definitive)");
+                self.rebuild(0);
+            }
+            HistoryAction::Close => {
+                self.finished = true;
+            }
+        }
+    }
+    pub fn apply_key_event(
+        &mut self,
+        key: KeyCombination,
+    ) -> bool {
+        if key == key!(esc) {
+            self.finished = true;
+            return true;
+        }
+        match self.selected_action() {
+            Some(HistoryAction::Depth) if key == key!(left) || key == key!(right) => {
+                self.depth = if key == key!(left) {
+                    self.depth.saturating_sub(1)
+                } else {
+                    (self.depth + 1).min(MAX_HISTORY_DEPTH)
+                };
+                self.history.truncate(self.depth);
+                let selection = self.history.len();
+                self.rebuild(selection);
+                return true;
+            }
+            Some(HistoryAction::Past(idx)) if key == key!(c) => {
+                self.copy_request = Some(self.history[idx].value.clone());
+                return true;
+            }
+            _ => {}
+        }
+        match self.menu.state.on_key(key) {
+            Some(item) => {
+                self.on_item(item.action);
+                true
+            }
+            None => false,
+        }
+    }
+    /// handle a mouse event
+    pub fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        double_click: bool,
+    ) {
+        if let Some(item) = self.menu.state.on_mouse_event(mouse_event, double_click) {
+            self.on_item(item.action);
+        }
+    }
+    pub fn status(&self) -> &'static str {
+        self.message.unwrap_or(match self.selected_action() {
+            Some(HistoryAction::Past(_)) => {
+                "Hit *enter* to restore this value, *c* to copy it, *esc* to close"
+            }
+            _ => "Hit *enter* to select, *esc* to close",
+        })
+    }
+}